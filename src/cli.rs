@@ -4,10 +4,19 @@
 //! agnostic to `clap`.  The `Cli` struct is parsed once in `main` and then
 //! passed (by reference) into the command handlers.
 
-use std::path::PathBuf;
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
 
+/// File name looked for when no path is given.
+pub const DEFAULT_CONFIG_FILE: &str = "backup.toml";
+
+/// Environment variable naming the config file when `--config` is absent.
+pub const CONFIG_ENV_VAR: &str = "BACKUP_TOML";
+
 /// Top-level CLI arguments, shared across every subcommand.
 #[derive(Parser, Debug)]
 #[command(
@@ -27,12 +36,14 @@ use clap::Parser;
 pub struct Cli {
     /// Path to the configuration file.
     ///
-    /// Defaults to `backup.toml` in the current working directory.  Use
-    /// `--config /path/to/other.toml` to point at a project-specific config
-    /// stored elsewhere (useful when running from a cron job or a different
-    /// working directory).
-    #[arg(short, long, default_value = "backup.toml")]
-    pub config: PathBuf,
+    /// When omitted, `$BACKUP_TOML` is used if set; otherwise the nearest
+    /// `backup.toml` in the current directory or one of its parents; otherwise
+    /// `backup.toml` in the current directory.  Use `--config
+    /// /path/to/other.toml` to point at a project-specific config stored
+    /// elsewhere (useful when running from a cron job or a different working
+    /// directory).  Resolved by [`Cli::effective_config_path`].
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
 
     /// Subcommand to run.  Omit to run the full backup pipeline.
     #[command(subcommand)]
@@ -45,6 +56,25 @@ pub struct Cli {
     #[arg(long)]
     pub print_config: bool,
 
+    /// Suppress the startup banner and the progress spinners.
+    ///
+    /// Useful for cron jobs and scripts where the version/config header is
+    /// just noise in the log.
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Print the planned pipeline stages as a tree before running them.
+    ///
+    /// Skipped stages are marked, so you can see what a combination such as
+    /// `--no-prune --no-check` will actually do.  Successful stages also
+    /// show what rustic wrote to stderr, e.g. files it could not read.
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Print stage results and the summary without colours or styling.
+    #[arg(long)]
+    pub no_color: bool,
+
     /// Skip the NAS mount step even if `[mount]` is configured.
     ///
     /// Useful when the share is already mounted, or when running on a machine
@@ -52,6 +82,21 @@ pub struct Cli {
     #[arg(long)]
     pub no_mount: bool,
 
+    /// Check for an existing mount without `doas`, then mount if there is
+    /// none.
+    ///
+    /// The usual check runs `doas mount` with no arguments; use this where
+    /// `doas.conf` does not allow that.  This one only compares the
+    /// mountpoint's device with its parent's.  Same as
+    /// `[mount].force_recheck`.
+    #[arg(long, conflicts_with = "skip_if_mounted")]
+    pub force_mount: bool,
+
+    /// Report the Mount stage as skipped when the share is already mounted,
+    /// instead of as a success.
+    #[arg(long)]
+    pub skip_if_mounted: bool,
+
     /// Skip the `forget` and `prune` (compaction) steps.
     ///
     /// All snapshots are kept; no disk space is reclaimed.  Useful when you
@@ -66,6 +111,29 @@ pub struct Cli {
     #[arg(long)]
     pub no_check: bool,
 
+    /// Skip confirming that the backup recorded a snapshot.
+    ///
+    /// After Backup, `rustic snapshots latest --json` is run and the pipeline
+    /// fails unless it returns a snapshot taken during this run.
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Create the repository if it does not exist yet.
+    ///
+    /// Without this flag a missing repository is an error, so a typo in
+    /// `[repo].path` fails loudly instead of silently starting a new, empty
+    /// repository somewhere unexpected.
+    #[arg(long)]
+    pub init_if_missing: bool,
+
+    /// Preview the run without modifying the repository.
+    ///
+    /// Backup, Forget and Compact are run with rustic's `--dry-run`, and their
+    /// reports — which snapshots would be forgotten, how much space would be
+    /// reclaimed — are printed.  Exits zero if every preview succeeds.
+    #[arg(long)]
+    pub dry_run: bool,
+
     /// Elevate commands via `doas`.
     ///
     /// When set, `rustic` (and any mount commands) are prefixed with `doas`.
@@ -73,6 +141,129 @@ pub struct Cli {
     /// replaces.
     #[arg(long)]
     pub sudo: bool,
+
+    /// Use this repository instead of `[repo].path`.
+    ///
+    /// Intended for testing and one-off runs against a scratch repository;
+    /// for anything permanent, edit `backup.toml`.
+    #[arg(long, value_name = "PATH")]
+    pub repo: Option<String>,
+
+    /// Use this password instead of `[repo].password`.
+    ///
+    /// Intended for testing and one-off runs.  The value is visible to other
+    /// users in the process list, so prefer the config file for real secrets.
+    #[arg(long, value_name = "PASSWORD")]
+    pub password: Option<String>,
+
+    /// Read the repository password from the first line of stdin.
+    ///
+    /// For secret managers that print the password, e.g.
+    /// `pass show backup | backup --stdin-password`.  Cannot be combined
+    /// with `--password` or `[repo].password_env`.
+    #[arg(long, conflicts_with = "password")]
+    pub stdin_password: bool,
+
+    /// Back up this path instead of `[backup].sources`.  Repeatable.
+    ///
+    /// When given, the configured sources are ignored entirely for this run —
+    /// the flags *replace* the list, they do not add to it.  Exclusion globs
+    /// and every other `[backup]` setting still apply.
+    #[arg(long = "source", value_name = "PATH")]
+    pub sources: Vec<String>,
+
+    /// Leave out paths matching this glob for this run.  Repeatable.
+    ///
+    /// Forwarded to rustic as `--glob=!<GLOB>` after every configured
+    /// exclusion; `backup show-excludes` lists them all in order.
+    #[arg(long = "exclude", value_name = "GLOB")]
+    pub excludes: Vec<String>,
+
+    /// Skip directories tagged with a `CACHEDIR.TAG` file for this run.
+    ///
+    /// Same as `exclude_caches = true` in `[backup]`.
+    #[arg(long)]
+    pub exclude_caches: bool,
+
+    /// Skip rustic's pre-backup scan of the sources for this run.
+    ///
+    /// Same as `no_scan = true` in `[backup]`: the backup starts sooner,
+    /// but its progress display has no total or ETA.
+    #[arg(long)]
+    pub no_scan: bool,
+
+    /// Annotate this run: forwarded to rustic as the snapshot label and
+    /// recorded in the run log.
+    ///
+    /// Filter on it later with `backup log --label <LABEL>`.
+    #[arg(long, value_name = "LABEL")]
+    pub label: Option<String>,
+
+    /// zstd compression level (1–22) for this run's Backup stage.
+    ///
+    /// Overrides `[backup].compression` without editing the config, e.g. for
+    /// one slow but maximally compressed snapshot.
+    #[arg(long, value_name = "LEVEL", value_parser = clap::value_parser!(u8).range(1..=22))]
+    pub compress_level: Option<u8>,
+
+    /// Kill any pipeline stage that runs longer than this many seconds.
+    ///
+    /// Overrides `stage_timeout_secs` from the config for this run.  Protects
+    /// unattended runs against rustic hanging on an unreachable NAS.
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Diagnostic log verbosity: `error`, `warn`, `info`, `debug`, or `trace`.
+    ///
+    /// Log lines are written to stderr.  At `debug` and above the spinner is
+    /// hidden so log output can stream without being overdrawn.
+    #[arg(long, default_value = "warn", value_name = "LEVEL")]
+    pub log_level: tracing::Level,
+}
+
+impl Cli {
+    /// The config file to read, resolved in order:
+    ///
+    /// 1. `--config`
+    /// 2. `$BACKUP_TOML` (ignored when empty)
+    /// 3. the nearest `backup.toml` walking up from the current directory
+    /// 4. `backup.toml` in the current directory, whether or not it exists
+    pub fn effective_config_path(&self) -> PathBuf {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        resolve_config_path(
+            self.config.as_deref(),
+            std::env::var_os(CONFIG_ENV_VAR),
+            &cwd,
+        )
+    }
+
+    /// The path a *new* config file is written to (`init`, `import`).
+    ///
+    /// Same as [`Self::effective_config_path`] without the upward walk, so a
+    /// config in a parent directory never stops a nested project from getting
+    /// its own.
+    pub fn new_config_path(&self) -> PathBuf {
+        explicit_config_path(self.config.as_deref(), std::env::var_os(CONFIG_ENV_VAR))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE))
+    }
+}
+
+/// Resolution chain behind [`Cli::effective_config_path`], with the flag,
+/// environment value and working directory passed in so it can be tested.
+pub fn resolve_config_path(flag: Option<&Path>, env: Option<OsString>, cwd: &Path) -> PathBuf {
+    explicit_config_path(flag, env)
+        .or_else(|| {
+            cwd.ancestors()
+                .map(|dir| dir.join(DEFAULT_CONFIG_FILE))
+                .find(|candidate| candidate.is_file())
+        })
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE))
+}
+
+/// `--config`, else a non-empty `$BACKUP_TOML`.
+fn explicit_config_path(flag: Option<&Path>, env: Option<OsString>) -> Option<PathBuf> {
+    flag.map(Path::to_path_buf)
+        .or_else(|| env.filter(|v| !v.is_empty()).map(PathBuf::from))
 }
 
 /// Explicit subcommands.  Running `backup` with no subcommand triggers the
@@ -83,11 +274,484 @@ pub enum Subcommand {
     ///
     /// The generated file is pre-populated with sensible defaults:
     /// - `[backup].sources` is set to the current working directory.
-    /// - `[repo].path` uses `~/nfs/new-backups/rustic/<dirname>` as the target.
+    /// - `[repo].path` uses `~/nfs/new-backups/rustic/<name>` as the target, where `<name>` is the
+    ///   project name from the git remote URL, or the directory name outside a git checkout.
     /// - Common exclusion globs (`.git`, `target/`, `node_modules/`, ...) are included and ready to
     ///   be uncommented or removed.
     ///
+    /// `--repo` and `--source` replace the detected values; with both given,
+    /// the working directory is not inspected at all.
+    ///
     /// Exits with an error if `backup.toml` already exists to avoid
     /// accidental overwrites.
-    Init,
+    Init {
+        /// Repository path (or URI) to write to `[repo].path`.
+        #[arg(long, value_name = "PATH")]
+        repo: Option<String>,
+        /// Source path for `[backup].sources` (repeatable).
+        #[arg(long = "source", value_name = "PATH")]
+        sources: Vec<String>,
+        /// Starting globs for the file: default, rust, node or home.
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
+        /// Print the available templates and exit without writing anything.
+        #[arg(long, conflicts_with_all = ["repo", "sources", "template"])]
+        list_templates: bool,
+        /// Write the global `~/.config/backup.rs/config.toml` instead, with
+        /// `[mount]` defaults shared by every project.
+        #[arg(long, conflicts_with_all = ["repo", "sources", "template", "list_templates"])]
+        global: bool,
+    },
+
+    /// Print a file from a snapshot to stdout (`rustic dump`).
+    ///
+    /// `PATH` is the file's path inside the snapshot, i.e. the absolute path
+    /// it had when it was backed up.
+    Dump {
+        /// Snapshot to read from: `latest` or a snapshot ID.
+        snapshot: String,
+        /// Path of the file inside the snapshot.
+        path: String,
+    },
+
+    /// List the files in a snapshot (`rustic ls`).
+    Ls {
+        /// Snapshot to list: `latest` or a snapshot ID.
+        snapshot: String,
+        /// Only list the subtree of `[backup].sources[N]` (0-based).
+        #[arg(long, value_name = "N")]
+        source_index: Option<usize>,
+    },
+
+    /// Print a snapshot's root tree as pretty JSON (`rustic cat tree`).
+    ///
+    /// Looks up the snapshot's tree hash first, so no hash is needed.
+    CatTree {
+        /// Snapshot to inspect: `latest` or a snapshot ID.
+        snapshot: String,
+    },
+
+    /// List the snapshots in the repository.
+    Snapshots {
+        /// Print one section per host or per tag.
+        #[arg(long, value_enum, value_name = "FIELD", conflicts_with = "count")]
+        group_by: Option<GroupBy>,
+        /// Print only the number of snapshots, for use in scripts.
+        #[arg(long)]
+        count: bool,
+        /// With `--count`, print `{"count": N}` instead of the bare number.
+        #[arg(long, requires = "count")]
+        json: bool,
+        /// Print a table, rustic's raw JSON, or CSV.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            default_value_t = OutputFormat::Table,
+            conflicts_with = "count"
+        )]
+        output_format: OutputFormat,
+    },
+
+    /// Print one snapshot's ID and nothing else, for use in scripts.
+    ///
+    /// No label and no trailing newline, so `$(backup snapshot-id)` is safe.
+    /// Exits non-zero when there is no such snapshot.
+    SnapshotId {
+        /// The newest snapshot (the default).
+        #[arg(long, conflicts_with = "index")]
+        latest: bool,
+        /// The snapshot N places before the newest (0 = newest).
+        #[arg(long, value_name = "N")]
+        index: Option<usize>,
+    },
+
+    /// Print the number of snapshots and nothing else, for use in scripts.
+    ///
+    /// Like `snapshots --count`, but with no trailing newline, so
+    /// `$(backup snapshot-count)` compares cleanly in `[ ... -gt 0 ]`.
+    SnapshotCount,
+
+    /// Print how much data one snapshot holds, e.g. `1.4 GiB`.
+    ///
+    /// The size is the source bytes rustic recorded for the backup, before
+    /// deduplication and compression.
+    SnapshotSize {
+        /// Snapshot to measure: `latest` or a (prefix of a) snapshot ID.
+        snapshot: String,
+        /// Print the exact byte count with no unit and no trailing newline.
+        #[arg(long)]
+        raw_bytes: bool,
+    },
+
+    /// Read a file from a backup — same as `dump`, under a friendlier name.
+    Cat {
+        /// Snapshot to read from: `latest` or a snapshot ID.
+        snapshot: String,
+        /// Path of the file inside the snapshot.
+        path: String,
+    },
+
+    /// Mount the configured NAS share, or FUSE-mount a snapshot.
+    ///
+    /// With no arguments, mounts `[mount].share` without running a backup.
+    /// With `SNAPSHOT MOUNTPOINT`, serves that snapshot read-only at
+    /// `MOUNTPOINT` via `rustic mount`; this needs FUSE on the host.
+    Mount {
+        /// Snapshot to mount: `latest` or a snapshot ID.
+        #[arg(requires = "mountpoint", conflicts_with = "list_shares")]
+        snapshot: Option<String>,
+        /// Existing directory to mount the snapshot on.
+        mountpoint: Option<PathBuf>,
+        /// Forward `--foreground` to `rustic mount`.
+        #[arg(long, requires = "snapshot")]
+        foreground: bool,
+        /// List the share names `[mount].share` accepts and exit.
+        #[arg(long)]
+        list_shares: bool,
+    },
+
+    /// Unmount the configured NAS share.
+    ///
+    /// Runs `doas umount` for an NFS share and `fusermount3 -u` for an
+    /// rclone one.  With `--dry-run`, prints the command instead.
+    Umount,
+
+    /// Check that the NAS share's server is reachable, without mounting.
+    ///
+    /// Pings the NFS server of `[mount].share` and opens a TCP connection to
+    /// its NFS port, then prints a pass/fail table.
+    MountTest {
+        /// NFS port to probe.
+        #[arg(long, default_value_t = 2049)]
+        port: u16,
+    },
+
+    /// Write a `backup.toml` for an existing rustic repository.
+    ///
+    /// Sources are taken from the repository's most recent snapshot.  Like
+    /// `init`, refuses to overwrite an existing config file.
+    Import {
+        /// Path (or URI) of the existing repository.
+        #[arg(long, value_name = "PATH")]
+        repo: String,
+        /// Repository password; omit for an unencrypted repository.
+        #[arg(long, value_name = "PASSWORD", default_value = "")]
+        password: String,
+    },
+
+    /// Verify this binary's SHA-256 against its release checksum file.
+    ///
+    /// Only warns when no checksum file is available; fails on a mismatch.
+    SelfCheck {
+        /// Checksum file (a hex digest or `sha256sum` output).
+        /// Defaults to `$BACKUP_CHECKSUM_FILE`.
+        #[arg(long, value_name = "FILE")]
+        checksum_file: Option<PathBuf>,
+    },
+
+    /// Reclaim space from forgotten snapshots (`rustic prune` only).
+    ///
+    /// Unlike the default pipeline, which forgets snapshots per `[retention]`
+    /// and then prunes, `gc` never removes a snapshot — it only frees packs
+    /// that nothing references any more, e.g. after a manual `rustic forget`.
+    Gc,
+
+    /// Back up, then copy the new snapshots to the `[mirror]` replica.
+    ///
+    /// Runs the default pipeline against `[repo]`, then copies every snapshot
+    /// taken since the last successful mirror to `[mirror].dest_repo`.
+    Mirror,
+
+    /// Back up data piped to stdin as a single file (`rustic backup -`).
+    ///
+    /// For dump pipelines: `pg_dump app | backup backup-stdin app.sql`.
+    #[command(name = "backup-stdin")]
+    Stdin {
+        /// Name the data is stored under in the snapshot.
+        filename: String,
+    },
+
+    /// Verify repository integrity (`rustic check`).
+    ///
+    /// Without a mode this is the pipeline's Check stage on its own.
+    Check {
+        /// Narrow the check to one of rustic's modes.
+        #[command(subcommand)]
+        mode: Option<CheckMode>,
+    },
+
+    /// Rebuild the repository index from its pack files
+    /// (`rustic repair index`).
+    ///
+    /// Never rewrites snapshots or pack data, so it needs no confirmation.
+    /// Use it when `check` reports index problems after an interrupted run.
+    RepairIndex,
+
+    /// Remove specific snapshots by ID (`rustic forget <id>...`).
+    ///
+    /// Ignores `[retention]` and does not prune; follow with `backup gc` to
+    /// reclaim the space.
+    Forget {
+        /// IDs of the snapshots to remove.
+        #[arg(required = true, value_name = "ID")]
+        snapshots: Vec<String>,
+    },
+
+    /// Show past pipeline runs from the run log.
+    Log {
+        /// Only show runs started with this `--label`.
+        #[arg(long, value_name = "LABEL")]
+        label: Option<String>,
+        /// Only show runs started on or after this date (`2026-10-16`) or
+        /// timestamp (`2026-10-16T09:30:00Z`); dates are UTC.
+        #[arg(long, value_name = "DATE", value_parser = crate::commands::log::parse_since)]
+        since: Option<jiff::Timestamp>,
+        /// Only show runs started on or before this date or timestamp; a date
+        /// includes the whole day.
+        #[arg(long, value_name = "DATE", value_parser = crate::commands::log::parse_until)]
+        until: Option<jiff::Timestamp>,
+    },
+
+    /// Print a shell completion script to stdout.
+    ///
+    /// For example, `backup completions bash >
+    /// ~/.local/share/bash-completion/completions/backup-rs`.
+    Completions {
+        /// Shell to generate completions for.
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print a JSON Schema describing every `backup.toml` key.
+    ///
+    /// Each field's description is its documentation.  Point an editor at
+    /// the output, e.g. `backup export-schema > backup.schema.json`, to get
+    /// completion and checking while editing the config.
+    ExportSchema,
+
+    /// Show the runtime environment: config path, rustic binary, user, repo
+    /// backend.  Passwords are masked.
+    Env,
+
+    /// List every exclusion rule a backup would apply, in the order rustic
+    /// receives them, each tagged with where it came from.
+    ShowExcludes,
+
+    /// List every path the config refers to: config file, sources, exclude
+    /// files and the repository.
+    Paths {
+        /// Also check that each path exists and is readable (the repository's
+        /// parent must be writable).  Fails if a source is missing.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Inspect or check the merged configuration.
+    Config {
+        /// What to do with the configuration.
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Compare the current config with another file, field by field.
+    ///
+    /// Prints only the fields whose resolved values differ.
+    DiffConfig {
+        /// The config file to compare against.
+        other: PathBuf,
+    },
+
+    /// Manage repository keys.
+    Key {
+        /// What to do with the repository keys.
+        #[command(subcommand)]
+        action: KeyAction,
+    },
+}
+
+/// Actions under `backup config`.
+#[derive(clap::Subcommand, Debug, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Check the merged config and report every error and warning.
+    ///
+    /// Exits non-zero only when there is at least one error; warnings are
+    /// reported but accepted.  Run this before wiring `backup` into cron.
+    Validate,
+
+    /// Like `validate`, with a machine-readable report for CI.
+    ///
+    /// Exits non-zero only when there is at least one error.
+    Lint {
+        /// Print the `validate` report, or a JSON array of issues.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            default_value_t = LintFormat::Text
+        )]
+        output_format: LintFormat,
+    },
+
+    /// Print the merged, resolved config as TOML.
+    ///
+    /// Unlike `--print-config`, the output is a valid `backup.toml`.
+    Export {
+        /// Write to PATH instead of stdout.
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Only write fields that differ from their defaults.
+        #[arg(long)]
+        minimal: bool,
+    },
+
+    /// Print the config file in use and whether it exists.
+    ///
+    /// Follows the same order as every other command: `--config`,
+    /// `$BACKUP_TOML`, the nearest `backup.toml` upwards, then `./backup.toml`.
+    Path,
+}
+
+/// Field `backup snapshots --group-by` groups on.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The snapshot's hostname.
+    Host,
+    /// Each of the snapshot's tags.
+    Tag,
+}
+
+/// How `backup snapshots` prints the listing.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One line per snapshot, for people.
+    #[default]
+    Table,
+    /// rustic's own `snapshots --json` output, unchanged.
+    Json,
+    /// `id,time,host,size` rows under a header line.
+    Csv,
+}
+
+/// How `backup config lint` prints its findings.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LintFormat {
+    /// The same report as `backup config validate`.
+    #[default]
+    Text,
+    /// `[{"level": …, "field": …, "message": …}]`, one object per issue.
+    Json,
+}
+
+/// Modes of `backup check`.
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckMode {
+    /// Check the index, snapshots and trees without reading pack data.
+    Index,
+    /// Also read and verify every pack file (`--read-data`); slow.
+    Data,
+    /// Also report packs no snapshot uses (`--check-unused`).
+    Snapshots,
+}
+
+/// Actions under `backup key`.
+#[derive(clap::Subcommand, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Replace the repository password (`rustic key add` + `key remove`).
+    ///
+    /// Adds a key for the new password, then removes the key for the old
+    /// one.  If the removal fails, the new key is removed again so the old
+    /// password keeps working.  Update `[repo].password` afterwards.
+    #[command(group(clap::ArgGroup::new("new").required(true)))]
+    ChangePassword {
+        /// The new repository password.
+        #[arg(long, value_name = "PASSWORD", group = "new")]
+        new_password: Option<String>,
+        /// Read the new password from the first line of FILE.
+        #[arg(long, value_name = "FILE", group = "new")]
+        new_password_file: Option<PathBuf>,
+        /// Prompt for the new password again and abort on a mismatch.
+        #[arg(long)]
+        confirm_password: bool,
+    },
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// A temp tree `root/backup.toml` + `root/project/sub/`, returning the
+    /// root and the nested directory.
+    fn tree() -> (tempfile::TempDir, PathBuf) {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join(DEFAULT_CONFIG_FILE), "").unwrap();
+        let nested = root.path().join("project").join("sub");
+        fs::create_dir_all(&nested).unwrap();
+        (root, nested)
+    }
+
+    #[test]
+    fn flag_beats_everything() {
+        let (_root, nested) = tree();
+        let path = resolve_config_path(
+            Some(Path::new("/etc/a.toml")),
+            Some("/b.toml".into()),
+            &nested,
+        );
+        assert_eq!(path, Path::new("/etc/a.toml"));
+    }
+
+    #[test]
+    fn env_beats_directory_walk() {
+        let (_root, nested) = tree();
+        let path = resolve_config_path(None, Some("/b.toml".into()), &nested);
+        assert_eq!(path, Path::new("/b.toml"));
+    }
+
+    #[test]
+    fn empty_env_is_ignored() {
+        let (root, nested) = tree();
+        let path = resolve_config_path(None, Some(OsString::new()), &nested);
+        assert_eq!(path, root.path().join(DEFAULT_CONFIG_FILE));
+    }
+
+    #[test]
+    fn walk_finds_nearest_ancestor_config() {
+        let (root, nested) = tree();
+        assert_eq!(
+            resolve_config_path(None, None, &nested),
+            root.path().join(DEFAULT_CONFIG_FILE)
+        );
+
+        let closer = root.path().join("project").join(DEFAULT_CONFIG_FILE);
+        fs::write(&closer, "").unwrap();
+        assert_eq!(resolve_config_path(None, None, &nested), closer);
+    }
+
+    #[test]
+    fn default_when_nothing_is_found() {
+        let dir = tempfile::tempdir().unwrap();
+        // The temp dir's ancestors (e.g. /tmp, /) are assumed not to hold a
+        // backup.toml.
+        assert_eq!(
+            resolve_config_path(None, None, dir.path()),
+            Path::new(DEFAULT_CONFIG_FILE)
+        );
+    }
+
+    #[test]
+    fn new_config_path_skips_the_walk() {
+        assert_eq!(
+            explicit_config_path(None, None).unwrap_or_else(|| DEFAULT_CONFIG_FILE.into()),
+            Path::new(DEFAULT_CONFIG_FILE)
+        );
+        assert_eq!(
+            explicit_config_path(None, Some("/b.toml".into())),
+            Some(PathBuf::from("/b.toml"))
+        );
+    }
 }
@@ -0,0 +1,149 @@
+//! `backup cat-tree` — print a snapshot's root tree as pretty JSON.
+//!
+//! `rustic cat tree <id>` shows the raw tree object, but needs the tree's
+//! hash rather than a snapshot.  This looks the hash up first:
+//!
+//! 1. `rustic snapshots <snapshot> --json` — read the snapshot's `tree` field.
+//! 2. `rustic cat tree <tree>` — fetch the tree and re-indent it.
+//!
+//! ```text
+//! backup cat-tree latest | jq '.nodes[].name'
+//! ```
+//!
+//! Only the JSON goes to stdout, so the output pipes cleanly into `jq`.
+
+use std::io::Write as _;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    cli::Cli,
+    commands::import::collect_snapshots,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Print the root tree of `snapshot` (`latest` or an ID).
+pub fn run(cli: &Cli, cfg: &Config, snapshot: &str) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let listing = run_stage(
+        ui,
+        "Snapshot",
+        &build_snapshot_args(cli, cfg, snapshot),
+        timeout,
+    );
+    if listing.failed() {
+        anyhow::bail!(
+            "looking up snapshot '{snapshot}' failed: {}",
+            listing.error.as_deref().unwrap_or_default()
+        );
+    }
+    let tree = root_tree(&listing.stdout)?;
+
+    let cat = run_stage(ui, "Tree", &build_cat_tree_args(cli, cfg, &tree), timeout);
+    if cat.failed() {
+        anyhow::bail!(
+            "reading tree {tree} failed: {}",
+            cat.error.as_deref().unwrap_or_default()
+        );
+    }
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", pretty(&cat.stdout)?)?;
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic snapshots <snapshot> --json`.
+pub fn build_snapshot_args(cli: &Cli, cfg: &Config, snapshot: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["snapshots".into(), snapshot.into(), "--json".into()]);
+    cmd
+}
+
+/// Arguments for `rustic cat tree <tree>`.
+pub fn build_cat_tree_args(cli: &Cli, cfg: &Config, tree: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["cat".into(), "tree".into(), tree.into()]);
+    cmd
+}
+
+// ─── Output parsing ───────────────────────────────────────────────────────────
+
+/// The `tree` hash of the snapshot in `rustic snapshots <id> --json` output.
+pub fn root_tree(json: &str) -> Result<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("parsing `rustic snapshots --json` output")?;
+    let mut snapshots = Vec::new();
+    collect_snapshots(&value, &mut snapshots);
+    let snapshot = snapshots.first().context("no such snapshot")?;
+    snapshot
+        .get("tree")
+        .and_then(serde_json::Value::as_str)
+        .map(String::from)
+        .context("snapshot has no `tree` field")
+}
+
+/// Re-indent the JSON tree rustic prints.
+pub fn pretty(json: &str) -> Result<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("parsing `rustic cat tree` output")?;
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    #[test]
+    fn snapshot_args_select_one_snapshot() {
+        let args = build_snapshot_args(&make_cli(&[]), &Config::default(), "latest");
+        assert_eq!(&args[args.len() - 3..], ["snapshots", "latest", "--json"]);
+    }
+
+    #[test]
+    fn cat_tree_args_end_with_tree_hash() {
+        let cli = make_cli(&["--sudo"]);
+        let cfg = Config::default();
+        let args = build_cat_tree_args(&cli, &cfg, "abcd");
+        assert!(args.starts_with(&rustic_base(&cli, &cfg)));
+        assert_eq!(&args[args.len() - 3..], ["cat", "tree", "abcd"]);
+    }
+
+    #[test]
+    fn root_tree_is_read_from_grouped_output() {
+        let json = r#"[[{"hostname": "box"}, [{"id": "s1", "tree": "t1", "paths": ["/a"]}]]]"#;
+        assert_eq!(root_tree(json).unwrap(), "t1");
+    }
+
+    #[test]
+    fn missing_snapshot_errors() {
+        let err = root_tree("[]").unwrap_err().to_string();
+        assert!(err.contains("no such snapshot"), "got: {err}");
+    }
+
+    #[test]
+    fn pretty_indents_json() {
+        assert_eq!(
+            pretty(r#"{"nodes":[{"name":"a"}]}"#).unwrap(),
+            "{\n  \"nodes\": [\n    {\n      \"name\": \"a\"\n    }\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn pretty_rejects_non_json() {
+        assert!(pretty("tree not found").is_err());
+    }
+}
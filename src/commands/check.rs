@@ -0,0 +1,44 @@
+//! `backup check` — verify repository integrity on demand.
+//!
+//! Runs the pipeline's Check stage on its own, optionally narrowed to one
+//! of rustic's check modes:
+//!
+//! | Invocation                | rustic flags       | Reads                     |
+//! |---------------------------|--------------------|---------------------------|
+//! | `backup check`            | —                  | index, snapshots, trees   |
+//! | `backup check index`      | —                  | the same, named outright  |
+//! | `backup check data`       | `--read-data`      | every pack file, in full  |
+//! | `backup check snapshots`  | `--check-unused`   | also reports unused packs |
+//!
+//! `data` downloads the whole repository, so it is the slow one; run it
+//! from a machine close to the storage.
+
+use anyhow::Result;
+
+use crate::{
+    cli::{CheckMode, Cli},
+    commands::run::build_check_args,
+    config::Config,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run `rustic check` in `mode` as a single stage.
+pub fn run(cli: &Cli, cfg: &Config, mode: Option<CheckMode>) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    println!();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let label = match mode {
+        None => "Check",
+        Some(CheckMode::Index) => "Check index",
+        Some(CheckMode::Data) => "Check data",
+        Some(CheckMode::Snapshots) => "Check snapshots",
+    };
+    let check = run_stage(ui, label, &build_check_args(cli, cfg, mode), timeout);
+    check.print(ui);
+    if check.failed() {
+        anyhow::bail!("check failed");
+    }
+    Ok(())
+}
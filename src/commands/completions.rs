@@ -0,0 +1,44 @@
+//! `backup completions <shell>` — print a shell completion script.
+//!
+//! ```text
+//! backup completions bash > ~/.local/share/bash-completion/completions/backup-rs
+//! backup completions zsh  > "${fpath[1]}/_backup-rs"
+//! backup completions fish > ~/.config/fish/completions/backup-rs.fish
+//! backup completions powershell >> $PROFILE
+//! ```
+//!
+//! The script is generated from the same clap definition that parses the
+//! command line, so it never drifts from the real flags.
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::cli::Cli;
+
+/// Entry point for `backup completions`.
+pub fn run(shell: Shell) {
+    print!("{}", render(shell));
+}
+
+/// The completion script for `shell`, completing the installed binary name.
+pub fn render(shell: Shell) -> String {
+    let mut buf = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), env!("CARGO_BIN_NAME"), &mut buf);
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shell_mentions_pipeline_flags() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let script = render(shell);
+            assert!(script.contains("no-check"), "{shell}: missing --no-check");
+            assert!(script.contains("backup-rs"), "{shell}: missing binary name");
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! `backup config …` — inspect and check the merged configuration.
+//!
+//! | Invocation                       | Description                               |
+//! |----------------------------------|-------------------------------------------|
+//! | `backup config validate`         | Report every config error and warning     |
+//! | `backup config lint`             | `validate`, optionally as JSON for CI     |
+//! | `backup config export`           | Print the merged config as TOML           |
+//! | `backup config export --minimal` | Only the fields that differ from defaults |
+//! | `backup config path`             | Print which config file is in use         |
+//!
+//! These commands never touch the repository, so they are safe to run from a
+//! provisioning script before any backup has been set up.
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use console::style;
+
+use crate::{
+    cli::LintFormat,
+    config::{Config, Severity, ValidationIssue},
+};
+
+// ─── backup config validate ───────────────────────────────────────────────────
+
+/// Run every validation check on `cfg` and print the results.
+///
+/// Exits successfully when there are no errors; warnings are printed but do
+/// not fail the command.
+pub fn validate(cfg: &Config, config_path: &Path) -> Result<()> {
+    lint(cfg, config_path, LintFormat::Text)
+}
+
+/// [`validate`], printing the issues in `format`.
+///
+/// JSON output is a bare array on stdout, `[]` when the config is clean, so
+/// CI can parse it whatever the exit status.
+pub fn lint(cfg: &Config, config_path: &Path, format: LintFormat) -> Result<()> {
+    let issues = cfg.validate();
+    match format {
+        LintFormat::Text => print!("{}", render_issues(&issues, config_path)),
+        LintFormat::Json => println!("{}", render_issues_json(&issues)),
+    }
+
+    let errors = issues.iter().filter(|i| i.is_error()).count();
+    if errors > 0 {
+        anyhow::bail!("{errors} error(s) in '{}'", config_path.display());
+    }
+    Ok(())
+}
+
+/// Render `issues` as an indented, human-readable report.
+///
+/// Each issue gets a severity line followed by a `fix:` line.  An empty list
+/// renders as a single "no problems" line.
+pub fn render_issues(issues: &[ValidationIssue], config_path: &Path) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    if issues.is_empty() {
+        let _ = writeln!(
+            out,
+            "  {}  No problems found in '{}'.",
+            style("✓").green().bold(),
+            config_path.display()
+        );
+        return out;
+    }
+
+    for issue in issues {
+        let (icon, level) = match issue.severity {
+            Severity::Error => (style("✗").red().bold(), style("Error  ").red().bold()),
+            Severity::Warning => (style("⚠").yellow().bold(), style("Warning").yellow().bold()),
+        };
+        let _ = writeln!(
+            out,
+            "  {icon}  {level}  {}: {}",
+            style(&issue.field).bold(),
+            issue.message
+        );
+        let _ = writeln!(out, "              fix: {}", issue.suggestion);
+    }
+    out
+}
+
+/// `issues` as a JSON array of `{"level", "field", "message"}` objects.
+pub fn render_issues_json(issues: &[ValidationIssue]) -> String {
+    let issues: Vec<_> = issues
+        .iter()
+        .map(|issue| {
+            serde_json::json!({
+                "level": match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                },
+                "field": issue.field,
+                "message": issue.message,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&issues).unwrap_or_else(|_| "[]".into())
+}
+
+// ─── backup config export ─────────────────────────────────────────────────────
+
+/// Write `cfg` as TOML to `output`, or to stdout when `None`.
+///
+/// The result is the config after the global/local merge, `password_env` and
+/// `--repo`/`--password`, so any resolved password appears in plain text.
+/// With `minimal`, fields equal to their defaults are left out.
+pub fn export(cfg: &Config, output: Option<&Path>, minimal: bool) -> Result<()> {
+    let text = if minimal {
+        cfg.to_minimal_toml()?
+    } else {
+        render_export(cfg)?
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, text).with_context(|| format!("writing '{}'", path.display()))?;
+            println!(
+                "  {}  Wrote '{}'.",
+                style("✓").green().bold(),
+                path.display()
+            );
+        },
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+/// `cfg` serialised as a `backup.toml`.
+pub fn render_export(cfg: &Config) -> Result<String> {
+    toml::to_string_pretty(cfg).context("serialising config")
+}
+
+// ─── backup config path ───────────────────────────────────────────────────────
+
+/// Print `config_path` and whether it exists.
+///
+/// Never fails: a missing file is the answer, not an error.
+pub fn path(config_path: &Path) {
+    print!("{}", render_path(config_path));
+}
+
+/// `config_path` on the first line, so scripts can take it with `head -1`,
+/// then `exists` or `does not exist`.
+pub fn render_path(config_path: &Path) -> String {
+    let state = if config_path.is_file() {
+        "exists"
+    } else {
+        "does not exist"
+    };
+    format!("{}\n{state}\n", config_path.display())
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> &'static Path {
+        Path::new("backup.toml")
+    }
+
+    #[test]
+    fn render_no_issues_says_so() {
+        let out = render_issues(&[], path());
+        assert!(out.contains("No problems found in 'backup.toml'"));
+    }
+
+    #[test]
+    fn render_shows_severity_field_message_and_fix() {
+        let issue = ValidationIssue {
+            severity: Severity::Error,
+            field: "backup.max_file_size_mb".into(),
+            message: "must be greater than zero".into(),
+            suggestion: "remove the key".into(),
+        };
+        let out = render_issues(&[issue], path());
+        assert!(out.contains("Error"));
+        assert!(out.contains("backup.max_file_size_mb: must be greater than zero"));
+        assert!(out.contains("fix: remove the key"));
+    }
+
+    #[test]
+    fn json_lists_level_field_and_message() {
+        let issues = [
+            ValidationIssue {
+                severity: Severity::Error,
+                field: "stage_timeout_secs".into(),
+                message: "must be greater than zero".into(),
+                suggestion: "remove the key".into(),
+            },
+            ValidationIssue {
+                severity: Severity::Warning,
+                field: "backup.exclude_files".into(),
+                message: "'x' does not exist".into(),
+                suggestion: "create it".into(),
+            },
+        ];
+        let parsed: serde_json::Value = serde_json::from_str(&render_issues_json(&issues)).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!([
+                {
+                    "level": "error",
+                    "field": "stage_timeout_secs",
+                    "message": "must be greater than zero"
+                },
+                {
+                    "level": "warning",
+                    "field": "backup.exclude_files",
+                    "message": "'x' does not exist"
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn json_of_no_issues_is_an_empty_array() {
+        assert_eq!(render_issues_json(&[]), "[]");
+    }
+
+    #[test]
+    fn lint_fails_on_errors_but_not_on_warnings() {
+        let mut cfg = Config::default();
+        cfg.backup.exclude_files = vec!["/definitely/missing.ignore".into()];
+        assert!(lint(&cfg, path(), LintFormat::Json).is_ok());
+
+        cfg.stage_timeout_secs = Some(0);
+        assert!(lint(&cfg, path(), LintFormat::Json).is_err());
+    }
+
+    #[test]
+    fn validate_ok_for_default_config() {
+        assert!(validate(&Config::default(), path()).is_ok());
+    }
+
+    #[test]
+    fn validate_errors_for_invalid_config() {
+        let mut cfg = Config::default();
+        cfg.backup.max_file_size_mb = Some(0);
+        assert!(validate(&cfg, path()).is_err());
+    }
+
+    #[test]
+    fn export_parses_back_to_the_same_config() {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/mnt/nas/rustic/app".into();
+        cfg.retention.keep_all_within = Some("7d".into());
+        let text = render_export(&cfg).unwrap();
+        let parsed: Config = toml::from_str(&text).unwrap();
+        assert_eq!(render_export(&parsed).unwrap(), text);
+    }
+
+    #[test]
+    fn export_writes_to_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("exported.toml");
+        export(&Config::default(), Some(&out), false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            render_export(&Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn minimal_export_writes_only_changed_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("exported.toml");
+        let mut cfg = Config::default();
+        cfg.repo.path = "/mnt/nas/rustic/app".into();
+        export(&cfg, Some(&out), true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "[repo]\npath = \"/mnt/nas/rustic/app\"\n"
+        );
+    }
+
+    #[test]
+    fn render_path_reports_an_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("backup.toml");
+        std::fs::write(&file, "").unwrap();
+        assert_eq!(render_path(&file), format!("{}\nexists\n", file.display()));
+    }
+
+    #[test]
+    fn render_path_reports_a_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nope.toml");
+        assert!(render_path(&file).ends_with("\ndoes not exist\n"));
+    }
+}
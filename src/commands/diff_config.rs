@@ -0,0 +1,106 @@
+//! `backup diff-config` — compare the current config with another file.
+//!
+//! Both files are resolved (missing keys take their defaults) and compared
+//! field by field.  Only differing fields are printed, the current file's
+//! value in red and the other file's in green:
+//!
+//! ```text
+//! --- backup.toml
+//! +++ ../bob/backup.toml
+//!   - repo.path = "/mnt/nas/rustic/app"
+//!   + repo.path = "/mnt/nas/rustic/app-bob"
+//!   + backup.max_file_size_mb = 512
+//! ```
+//!
+//! Identical configs print nothing.  The global config is not merged into
+//! either side, so the comparison is between the two files as written.
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+
+use crate::config::parse_partial;
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Print the fields that differ between `current` and `other`.
+///
+/// A missing `current` file compares as all defaults; a missing `other` is
+/// an error, since it was named explicitly.
+pub fn run(current: &Path, other: &Path) -> Result<()> {
+    print!("{}", render_diff(current, other)?);
+    Ok(())
+}
+
+/// The diff between the files at `current` and `other`, with a `---`/`+++`
+/// header naming them, or `""` when they resolve to the same config.
+pub fn render_diff(current: &Path, other: &Path) -> Result<String> {
+    let left = parse_partial(current)?.unwrap_or_default();
+    let right =
+        parse_partial(other)?.with_context(|| format!("'{}' does not exist", other.display()))?;
+
+    let body = left.display_diff(&right)?;
+    if body.is_empty() {
+        return Ok(body);
+    }
+    Ok(format!(
+        "--- {}\n+++ {}\n{body}",
+        current.display(),
+        other.display()
+    ))
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn identical_configs_produce_empty_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "[repo]\npath = \"/srv/repo\"\n[retention]\ndaily = 9\n";
+        let a = write(dir.path(), "a.toml", text);
+        let b = write(dir.path(), "b.toml", text);
+        assert_eq!(render_diff(&a, &b).unwrap(), "");
+    }
+
+    #[test]
+    fn explicit_default_is_not_a_difference() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(dir.path(), "a.toml", "");
+        let b = write(dir.path(), "b.toml", "[backup]\ncompression = 3\n");
+        assert_eq!(render_diff(&a, &b).unwrap(), "");
+    }
+
+    #[test]
+    fn differing_fields_are_listed_with_both_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(dir.path(), "a.toml", "[repo]\npath = \"/a\"\n");
+        let b = write(
+            dir.path(),
+            "b.toml",
+            "[repo]\npath = \"/b\"\n[backup]\nmax_file_size_mb = 512\n",
+        );
+        let out = render_diff(&a, &b).unwrap();
+        let plain = console::strip_ansi_codes(&out);
+        assert!(plain.starts_with(&format!("--- {}\n+++ {}\n", a.display(), b.display())));
+        assert!(plain.contains("  - repo.path = \"/a\"\n  + repo.path = \"/b\"\n"));
+        assert!(plain.contains("  + backup.max_file_size_mb = 512\n"));
+        assert!(!plain.contains("- backup.max_file_size_mb"));
+        assert!(!plain.contains("compression"), "got:\n{plain}");
+    }
+
+    #[test]
+    fn missing_other_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(dir.path(), "a.toml", "");
+        assert!(render_diff(&a, &dir.path().join("absent.toml")).is_err());
+    }
+}
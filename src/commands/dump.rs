@@ -0,0 +1,83 @@
+//! `backup dump` / `backup cat` — print a file from a snapshot to stdout.
+//!
+//! Both subcommands run `rustic dump <snapshot>:<path>` with stdout inherited,
+//! so the file's bytes stream straight to the terminal (or a pipe) without
+//! being buffered behind a spinner.  `cat` exists purely because that is what
+//! people reach for when they want to "read a file from the backup".
+//!
+//! ```text
+//! backup cat latest /home/alice/project/Cargo.toml
+//! backup dump 1a2b3c4d /home/alice/project/db.sqlite > db.sqlite
+//! ```
+
+use anyhow::Result;
+
+use crate::{cli::Cli, config::Config, runner::rustic_base, ui::run_interactive};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Stream `path` from `snapshot` to stdout.
+///
+/// `snapshot` accepts anything rustic does (`latest`, a full or short ID).
+/// `path` is the path *inside* the snapshot, which for rustic is the absolute
+/// path the file had when it was backed up.
+pub fn run(cli: &Cli, cfg: &Config, snapshot: &str, path: &str) -> Result<()> {
+    run_interactive(&build_dump_args(cli, cfg, snapshot, path))
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic dump <snapshot>:<path>`.
+pub fn build_dump_args(cli: &Cli, cfg: &Config, snapshot: &str, path: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["dump".into(), format!("{snapshot}:{path}")]);
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    #[test]
+    fn dump_args_join_snapshot_and_path() {
+        let args = build_dump_args(&make_cli(&[]), &Config::default(), "latest", "/etc/hosts");
+        assert_eq!(&args[args.len() - 2..], ["dump", "latest:/etc/hosts"]);
+    }
+
+    #[test]
+    fn dump_args_start_with_rustic_base() {
+        let cli = make_cli(&["--sudo"]);
+        let cfg = Config::default();
+        let args = build_dump_args(&cli, &cfg, "abc123", "/a");
+        assert!(args.starts_with(&rustic_base(&cli, &cfg)));
+    }
+
+    #[test]
+    fn cat_and_dump_parse_to_the_same_arguments() {
+        use crate::cli::Subcommand;
+
+        let dump = make_cli(&["dump", "latest", "/a/b.txt"]);
+        let cat = make_cli(&["cat", "latest", "/a/b.txt"]);
+        match (dump.command, cat.command) {
+            (
+                Some(Subcommand::Dump {
+                    snapshot: s1,
+                    path: p1,
+                }),
+                Some(Subcommand::Cat {
+                    snapshot: s2,
+                    path: p2,
+                }),
+            ) => assert_eq!((s1, p1), (s2, p2)),
+            other => panic!("unexpected parse: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! `backup export-schema` — print a JSON Schema for `backup.toml`.
+//!
+//! The schema is generated from [`Config`] by `schemars`, so it always
+//! matches the keys this build accepts, and each field's doc comment becomes
+//! its `description`.  Editors that understand JSON Schema (e.g. via
+//! taplo's `#:schema` directive) can then complete and check the file:
+//!
+//! ```text
+//! backup export-schema > backup.schema.json
+//! ```
+//!
+//! `[repo].path`, `[repo].password` and `[backup].sources` have no serde
+//! default on [`Config`] itself, but every config file is loaded through
+//! [`PartialConfig`](crate::config::PartialConfig), which fills them in.  The
+//! schema gives them defaults too, so it never rejects a file `backup`
+//! would accept.
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Print the schema to stdout.
+pub fn run() -> Result<()> {
+    println!("{}", render_schema()?);
+    Ok(())
+}
+
+/// The JSON Schema for [`Config`], pretty-printed.
+pub fn render_schema() -> Result<String> {
+    serde_json::to_string_pretty(&schemars::schema_for!(Config)).context("serialising the schema")
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> serde_json::Value {
+        serde_json::from_str(&render_schema().unwrap()).expect("schema is valid JSON")
+    }
+
+    /// The definition `[section]` refers to, e.g. `$defs/RepoConfig` for `repo`.
+    fn definition<'a>(schema: &'a serde_json::Value, section: &str) -> &'a serde_json::Value {
+        let reference = schema["properties"][section]["$ref"]
+            .as_str()
+            .unwrap_or_else(|| panic!("[{section}] is a $ref"));
+        let name = reference.rsplit('/').next().unwrap();
+        &schema["$defs"][name]
+    }
+
+    #[test]
+    fn schema_is_valid_json_with_the_top_level_sections() {
+        let schema = schema();
+        assert_eq!(schema["type"], "object");
+        for section in [
+            "repo",
+            "backup",
+            "retention",
+            "mount",
+            "cold_repo",
+            "mirror",
+        ] {
+            assert!(
+                schema["properties"].get(section).is_some(),
+                "[{section}] missing"
+            );
+        }
+    }
+
+    #[test]
+    fn field_descriptions_come_from_doc_comments() {
+        let schema = schema();
+        let description = definition(&schema, "backup")["properties"]["compression"]["description"]
+            .as_str()
+            .unwrap();
+        assert!(description.contains("zstd"), "got: {description}");
+    }
+
+    #[test]
+    fn no_field_of_a_minimal_config_is_required() {
+        let schema = schema();
+        for section in ["repo", "backup"] {
+            let required = definition(&schema, section).get("required");
+            assert!(
+                required.is_none_or(|r| r.as_array().is_some_and(Vec::is_empty)),
+                "[{section}] requires {required:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn defaults_are_recorded() {
+        let schema = schema();
+        let backup = &definition(&schema, "backup")["properties"];
+        assert_eq!(backup["compression"]["default"], 3);
+        assert_eq!(backup["exclude_if_present"]["default"], "ignore");
+    }
+}
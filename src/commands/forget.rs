@@ -0,0 +1,110 @@
+//! `backup forget <id>...` — remove specific snapshots by ID.
+//!
+//! All IDs go to a single `rustic forget <id1> <id2> …` invocation, so the
+//! repository is locked once no matter how many snapshots are removed.  No
+//! `[retention]` policy is applied and nothing is pruned; run `backup gc`
+//! afterwards to reclaim the space:
+//!
+//! ```text
+//! backup forget 1a2b3c4d 5e6f7a8b
+//! backup gc
+//! ```
+//!
+//! `--dry-run` lists what would be removed without removing it.
+
+use anyhow::Result;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Forget every snapshot in `snapshots`.
+pub fn run(cli: &Cli, cfg: &Config, snapshots: &[String]) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    println!();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let forget = run_stage(
+        ui,
+        "Forget",
+        &build_forget_ids_args(cli, cfg, snapshots),
+        timeout,
+    );
+    forget.print(ui);
+    if cli.dry_run {
+        forget.print_stdout();
+    }
+    if forget.failed() {
+        anyhow::bail!("forget failed");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic forget <id>...`.
+pub fn build_forget_ids_args(cli: &Cli, cfg: &Config, snapshots: &[String]) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.push("forget".into());
+    if cli.dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd.extend(snapshots.iter().cloned());
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    #[test]
+    fn every_id_is_passed_in_one_invocation() {
+        let args = build_forget_ids_args(
+            &make_cli(&[]),
+            &Config::default(),
+            &ids(&["1a2b3c4d", "5e6f7a8b", "9c0d1e2f"]),
+        );
+        assert_eq!(&args[args.len() - 4..], [
+            "forget", "1a2b3c4d", "5e6f7a8b", "9c0d1e2f"
+        ]);
+        assert_eq!(args.iter().filter(|a| *a == "forget").count(), 1);
+    }
+
+    #[test]
+    fn forget_ids_never_prune() {
+        let args = build_forget_ids_args(&make_cli(&[]), &Config::default(), &ids(&["abc"]));
+        assert!(!args.iter().any(|a| a == "--prune"));
+        assert!(!args.iter().any(|a| a.starts_with("--keep-")));
+    }
+
+    #[test]
+    fn dry_run_precedes_ids() {
+        let args = build_forget_ids_args(
+            &make_cli(&["--dry-run"]),
+            &Config::default(),
+            &ids(&["abc"]),
+        );
+        assert_eq!(&args[args.len() - 3..], ["forget", "--dry-run", "abc"]);
+    }
+
+    #[test]
+    fn forget_requires_at_least_one_id() {
+        assert!(Cli::try_parse_from(["backup", "forget"]).is_err());
+    }
+}
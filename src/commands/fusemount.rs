@@ -0,0 +1,166 @@
+//! `backup mount <snapshot> <mountpoint>` — browse a snapshot as a filesystem.
+//!
+//! Runs `rustic mount <snapshot> <mountpoint>`, which serves the snapshot
+//! read-only over FUSE until the mountpoint is unmounted:
+//!
+//! ```text
+//! backup mount latest /mnt/snapshot
+//! backup mount 1a2b3c4d ~/browse --foreground
+//! fusermount -u /mnt/snapshot
+//! ```
+//!
+//! # Requirements
+//!
+//! The host needs FUSE: the `/dev/fuse` device (the `fuse` kernel module on
+//! Linux, macFUSE on macOS) and a `rustic` built with its `mount` feature.
+//! The device is checked up front so a missing module gives a clear error
+//! instead of an opaque rustic failure.
+//!
+//! Without a snapshot, `backup mount` mounts the NAS share instead; see
+//! [`crate::commands::mount_cmd`].
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+/// Device node every FUSE filesystem is served through.
+const FUSE_DEVICE: &str = "/dev/fuse";
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Mount `snapshot` at `mountpoint`.
+///
+/// Blocks until rustic exits, i.e. until the mountpoint is unmounted.  No
+/// stage timeout applies: a mount is expected to outlive any backup stage.
+pub fn run(
+    cli: &Cli,
+    cfg: &Config,
+    snapshot: &str,
+    mountpoint: &Path,
+    foreground: bool,
+) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    if !Path::new(FUSE_DEVICE).exists() {
+        anyhow::bail!("FUSE is not available on this host ({FUSE_DEVICE} is missing)");
+    }
+    if !mountpoint.is_dir() {
+        anyhow::bail!(
+            "mountpoint '{}' is not an existing directory",
+            mountpoint.display()
+        );
+    }
+
+    println!();
+    let outcome = run_stage(
+        ui,
+        "Mount snapshot",
+        &build_fusemount_args(cli, cfg, snapshot, mountpoint, foreground),
+        None,
+    );
+    outcome.print(ui);
+    if outcome.failed() {
+        anyhow::bail!("mounting snapshot '{snapshot}' failed");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic mount [--foreground] <snapshot> <mountpoint>`.
+pub fn build_fusemount_args(
+    cli: &Cli,
+    cfg: &Config,
+    snapshot: &str,
+    mountpoint: &Path,
+    foreground: bool,
+) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.push("mount".into());
+    if foreground {
+        cmd.push("--foreground".into());
+    }
+    cmd.extend([snapshot.into(), mountpoint.display().to_string()]);
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::Subcommand;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    #[test]
+    fn mount_args_end_with_snapshot_and_mountpoint() {
+        let args = build_fusemount_args(
+            &make_cli(&[]),
+            &Config::default(),
+            "latest",
+            Path::new("/mnt/snap"),
+            false,
+        );
+        assert_eq!(&args[args.len() - 3..], ["mount", "latest", "/mnt/snap"]);
+    }
+
+    #[test]
+    fn foreground_is_forwarded() {
+        let args = build_fusemount_args(
+            &make_cli(&[]),
+            &Config::default(),
+            "abc123",
+            Path::new("/mnt/snap"),
+            true,
+        );
+        assert_eq!(&args[args.len() - 4..], [
+            "mount",
+            "--foreground",
+            "abc123",
+            "/mnt/snap"
+        ]);
+    }
+
+    #[test]
+    fn mount_args_start_with_rustic_base() {
+        let cli = make_cli(&["--sudo"]);
+        let cfg = Config::default();
+        let args = build_fusemount_args(&cli, &cfg, "latest", Path::new("/m"), false);
+        assert!(args.starts_with(&rustic_base(&cli, &cfg)));
+    }
+
+    #[test]
+    fn snapshot_requires_a_mountpoint() {
+        let err = Cli::try_parse_from(["backup", "mount", "latest"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn snapshot_conflicts_with_list_shares() {
+        assert!(Cli::try_parse_from(["backup", "mount", "latest", "/m", "--list-shares"]).is_err());
+    }
+
+    #[test]
+    fn bare_mount_still_parses() {
+        match make_cli(&["mount"]).command {
+            Some(Subcommand::Mount {
+                snapshot: None,
+                mountpoint: None,
+                foreground: false,
+                list_shares: false,
+            }) => {},
+            other => panic!("unexpected parse: {other:?}"),
+        }
+    }
+}
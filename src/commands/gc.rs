@@ -0,0 +1,84 @@
+//! `backup gc` — reclaim space without touching any snapshot.
+//!
+//! Runs only the pipeline's Compact stage (`rustic prune`).  The default
+//! pipeline's Forget stage applies `[retention]` and *then* prunes; `gc`
+//! skips the retention pass entirely, so it never removes a snapshot.  Use it
+//! after forgetting specific snapshots by hand:
+//!
+//! ```text
+//! backup forget 1a2b3c4d
+//! backup gc
+//! ```
+//!
+//! `--dry-run` reports how much space would be reclaimed.
+
+use anyhow::Result;
+
+use crate::{
+    cli::Cli,
+    commands::run::build_compact_args,
+    config::Config,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run the Compact stage on its own.
+pub fn run(cli: &Cli, cfg: &Config) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    println!();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let compact = run_stage(ui, "Compact", &build_gc_args(cli, cfg), timeout);
+    compact.print(ui);
+    if cli.dry_run {
+        compact.print_stdout();
+    }
+    if compact.failed() {
+        anyhow::bail!("gc failed");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `backup gc` — identical to the pipeline's Compact stage.
+pub fn build_gc_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    build_compact_args(cli, cfg, cli.dry_run)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn make_cfg() -> Config {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/tmp/repo".into();
+        cfg
+    }
+
+    #[test]
+    fn gc_args_run_prune_only() {
+        let args = build_gc_args(&make_cli(&[]), &make_cfg());
+        assert_eq!(args.last().map(String::as_str), Some("prune"));
+        assert!(!args.iter().any(|a| a == "forget"));
+    }
+
+    #[test]
+    fn gc_args_pass_dry_run() {
+        let args = build_gc_args(&make_cli(&["--dry-run"]), &make_cfg());
+        assert_eq!(args.last().map(String::as_str), Some("--dry-run"));
+    }
+
+    #[test]
+    fn snapshot_gc_args() {
+        insta::assert_debug_snapshot!(build_gc_args(&make_cli(&["--sudo"]), &make_cfg()));
+    }
+}
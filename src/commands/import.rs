@@ -0,0 +1,263 @@
+//! `backup import` — write a `backup.toml` for an existing rustic repository.
+//!
+//! # What it does
+//!
+//! 1. Runs `rustic repoinfo --json` to confirm the repository opens with the given password.
+//! 2. Runs `rustic snapshots --json` and picks the most recent snapshot by its `time` field.
+//! 3. Writes a `backup.toml` whose `[repo]` section points at the repository and whose
+//!    `[backup].sources` are that snapshot's paths.
+//!
+//! Everything else is left at its default, so the next `backup` run keeps
+//! adding snapshots of the same paths to the same repository.  Like `init`,
+//! an existing config file is never overwritten.
+//!
+//! ```text
+//! backup import --repo /mnt/nas/rustic/myapp --password hunter2
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+
+use crate::{
+    cli::Cli,
+    config::{Config, RepoConfig},
+    runner::rustic_base,
+    ui::{StageOutcome, UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run the `import` subcommand, writing the generated config to
+/// [`Cli::new_config_path`].
+pub fn run(cli: &Cli, repo: &str, password: &str) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    let dest = &cli.new_config_path();
+    if dest.exists() {
+        anyhow::bail!(
+            "'{}' already exists — refusing to overwrite; use --config to choose another path",
+            dest.display()
+        );
+    }
+
+    let cfg = Config {
+        repo: RepoConfig {
+            path: repo.into(),
+            password: password.into(),
+            ..RepoConfig::default()
+        },
+        ..Config::default()
+    };
+
+    println!();
+    let info = run_stage(ui, "Repo info", &build_repoinfo_args(cli, &cfg), None);
+    info.print(ui);
+    if info.failed() {
+        anyhow::bail!("could not open repository '{repo}'");
+    }
+
+    let snapshots = run_stage(ui, "Snapshots", &build_snapshots_args(cli, &cfg), None);
+    snapshots.print(ui);
+    if snapshots.failed() {
+        anyhow::bail!("could not list snapshots in '{repo}'");
+    }
+
+    let paths = latest_snapshot_paths(&snapshots.stdout)?;
+    write_config(ui, dest, &render_import_config(repo, password, &paths))
+}
+
+fn write_config(ui: UiContext, dest: &Path, content: &str) -> Result<()> {
+    std::fs::write(dest, content).with_context(|| format!("writing '{}'", dest.display()))?;
+    StageOutcome {
+        label: format!("Created '{}'", dest.display()),
+        stage_id: None,
+        success: true,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+        error_kind: None,
+    }
+    .print(ui);
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic repoinfo --json`.
+pub fn build_repoinfo_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["repoinfo".into(), "--json".into()]);
+    cmd
+}
+
+/// Arguments for `rustic snapshots --json`.
+pub fn build_snapshots_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["snapshots".into(), "--json".into()]);
+    cmd
+}
+
+// ─── Snapshot parsing ─────────────────────────────────────────────────────────
+
+/// The `paths` of the most recent snapshot in `rustic snapshots --json` output.
+///
+/// Snapshot objects are found wherever they sit in the JSON, so both a flat
+/// array and rustic's grouped `[[group, [snapshots…]]]` shape work.  Returns
+/// an empty list when the repository has no snapshots.
+pub fn latest_snapshot_paths(json: &str) -> Result<Vec<String>> {
+    let value: serde_json::Value = if json.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(json).context("parsing `rustic snapshots --json` output")?
+    };
+
+    let mut snapshots = Vec::new();
+    collect_snapshots(&value, &mut snapshots);
+
+    let latest = snapshots.into_iter().max_by_key(|s| {
+        s.get("time")
+            .and_then(|t| t.as_str()?.parse::<jiff::Timestamp>().ok())
+    });
+
+    Ok(latest
+        .and_then(|s| s.get("paths")?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|p| p.as_str().map(String::from))
+        .collect())
+}
+
+/// Push every JSON object with a `paths` array found under `value`.
+///
+/// Shared with `backup snapshot-id`, which needs the same shape-agnostic walk.
+pub fn collect_snapshots<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a serde_json::Value>) {
+    match value {
+        serde_json::Value::Object(map)
+            if map.get("paths").is_some_and(serde_json::Value::is_array) =>
+        {
+            out.push(value);
+        },
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_snapshots(item, out);
+            }
+        },
+        _ => {},
+    }
+}
+
+// ─── Config generation ────────────────────────────────────────────────────────
+
+/// Render the imported `backup.toml`.
+///
+/// Values are written as TOML string literals, so quotes and backslashes in
+/// paths or the password survive the round trip.
+pub fn render_import_config(repo: &str, password: &str, sources: &[String]) -> String {
+    let quote = |s: &str| toml::Value::String(s.into()).to_string();
+    let sources_line = if sources.is_empty() {
+        "sources = []  # the repository has no snapshots yet; defaults to \".\"".to_string()
+    } else {
+        let list: Vec<String> = sources.iter().map(|s| quote(s)).collect();
+        format!("sources = [{}]", list.join(", "))
+    };
+
+    format!(
+        r"# backup.toml — generated by `backup import` from an existing rustic repository.
+# Review [backup].sources before the first run; every other setting uses its
+# default.  Run `backup init` elsewhere for a fully commented example.
+
+[repo]
+path     = {path}
+password = {password}
+
+[backup]
+# Paths of the most recent snapshot in the repository.
+{sources_line}
+",
+        path = quote(repo),
+        password = quote(password),
+    )
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Config {
+        toml::from_str(text).expect("generated config should parse")
+    }
+
+    // ── render_import_config ──────────────────────────────────────────────────
+
+    #[test]
+    fn generated_config_points_at_repo() {
+        let cfg = parse(&render_import_config("/mnt/nas/rustic/app", "hunter2", &[
+            "/home/alice/app".into(),
+        ]));
+        assert_eq!(cfg.repo.path, "/mnt/nas/rustic/app");
+        assert_eq!(cfg.repo.password, "hunter2");
+        assert_eq!(cfg.backup.sources, ["/home/alice/app"]);
+    }
+
+    #[test]
+    fn generated_config_keeps_every_source() {
+        let sources = vec!["/etc".to_string(), "/home/alice".to_string()];
+        let cfg = parse(&render_import_config("/r", "", &sources));
+        assert_eq!(cfg.backup.sources, sources);
+    }
+
+    #[test]
+    fn generated_config_escapes_special_characters() {
+        let cfg = parse(&render_import_config(r"C:\backups", "pa\"ss", &[
+            "/with space/and \"quotes\"".into(),
+        ]));
+        assert_eq!(cfg.repo.path, r"C:\backups");
+        assert_eq!(cfg.repo.password, "pa\"ss");
+        assert_eq!(cfg.backup.sources, ["/with space/and \"quotes\""]);
+    }
+
+    #[test]
+    fn generated_config_without_snapshots_has_empty_sources() {
+        let text = render_import_config("/r", "", &[]);
+        assert!(text.contains("no snapshots yet"));
+        assert!(parse(&text).backup.sources.is_empty());
+    }
+
+    #[test]
+    fn generated_config_uses_default_retention() {
+        let cfg = parse(&render_import_config("/r", "", &[]));
+        assert_eq!(cfg.retention.daily, Config::default().retention.daily);
+    }
+
+    // ── latest_snapshot_paths ─────────────────────────────────────────────────
+
+    #[test]
+    fn latest_snapshot_is_chosen_by_time() {
+        let json = r#"[
+            {"time": "2026-10-16T09:00:00Z", "paths": ["/new"]},
+            {"time": "2026-10-01T09:00:00+02:00", "paths": ["/old"]}
+        ]"#;
+        assert_eq!(latest_snapshot_paths(json).unwrap(), ["/new"]);
+    }
+
+    #[test]
+    fn grouped_snapshot_output_is_searched() {
+        let json = r#"[[{"hostname": "box", "paths": null},
+                        [{"time": "2026-10-16T09:00:00Z", "paths": ["/a", "/b"]}]]]"#;
+        assert_eq!(latest_snapshot_paths(json).unwrap(), ["/a", "/b"]);
+    }
+
+    #[test]
+    fn no_snapshots_yields_no_paths() {
+        assert!(latest_snapshot_paths("[]").unwrap().is_empty());
+        assert!(latest_snapshot_paths("null").unwrap().is_empty());
+        assert!(latest_snapshot_paths("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(latest_snapshot_paths("not json").is_err());
+    }
+}
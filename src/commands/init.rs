@@ -7,53 +7,227 @@
 //! 3. Writes the file to the path specified by `--config` (default: `./backup.toml`).
 //! 4. Exits with an error if the destination file already exists, to prevent accidental overwrites.
 //!
+//! # Git projects
+//!
+//! When the current directory contains a `.git` folder, the repository
+//! directory name is taken from the project's remote URL instead of the
+//! directory name.  The heuristic, in [`detect_git_context`]:
+//!
+//! 1. Read `.git/config` and collect the `url` of every `[remote "…"]` section.
+//! 2. Prefer `origin`; otherwise use the first remote listed.
+//! 3. Take the last path segment of the URL and strip a trailing `.git`, so
+//!    `git@github.com:alice/myapp.git` and `https://host/alice/myapp` both
+//!    yield `myapp`.
+//!
+//! A checkout named `myapp-wip` therefore still backs up into `…/rustic/myapp`,
+//! keeping every clone of the same project in one repository.  If there is no
+//! `.git/config` or no remote, the directory name is used as before.
+//!
+//! # Scripted use
+//!
+//! `--repo <path>` and `--source <path>` (repeatable) replace the detected
+//! values and are written to the file verbatim.  With both given, the working
+//! directory is not inspected at all; with only one, the other is still
+//! derived as above.
+//!
+//! ```text
+//! backup init --repo /mnt/nas/rustic/app --source /srv/app --source /etc/app
+//! ```
+//!
+//! # Existing repositories
+//!
+//! A directory holding a rustic (or restic) repository — a `config` file plus
+//! `keys/` and `snapshots/` directories — is never written into: `init`
+//! warns, points at `backup import`, and fails.  A `--repo` that already
+//! holds a repository only gets the warning, since `import` can fill in its
+//! sources from the snapshots.
+//!
+//! # `.gitignore`
+//!
+//! `backup.toml` can hold the repository password, so when `init` writes it
+//! next to a `.git` directory it also appends `backup.toml` to that
+//! directory's `.gitignore` (creating the file if needed).  An existing
+//! entry is left alone.
+//!
+//! # Templates
+//!
+//! `--template <name>` picks the `[backup].globs` the file starts with, so a
+//! Rust checkout leaves out `target/` and a home directory its caches.
+//! `backup init --list-templates` prints every name with a one-line
+//! description and writes nothing.  Without `--template`, `default` is used.
+//!
+//! # Global config
+//!
+//! `backup init --global` writes `~/.config/backup.rs/config.toml` instead
+//! (following `$XDG_CONFIG_HOME`), creating its directory.  It holds the
+//! settings every project shares — mainly `[mount]` and extra NFS shares —
+//! all commented out, and is never overwritten either.
+//!
 //! # Generated file
 //!
 //! The generated file is a commented TOML with all supported keys.  Users are
 //! expected to open it, read the comments, and adjust paths/passwords before
 //! running `backup` for the first time.
 
-use std::path::{Path, PathBuf};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context as _, Result};
 
-use crate::ui::StageOutcome;
+use crate::ui::{StageOutcome, UiContext, print_warning};
 
 // ─── Entry point ──────────────────────────────────────────────────────────────
 
 /// Run the `init` subcommand.
 ///
-/// Writes a starter `backup.toml` to `dest`.  Returns an error if the file
-/// already exists or if the working directory / username cannot be determined.
-pub fn run(dest: &Path) -> Result<()> {
+/// Writes a starter `backup.toml` to `dest` from the `template` named (or
+/// `default`), using `repo` and `sources` in place of the detected values
+/// when given.  With `list_templates` it only prints the available
+/// templates.  Returns an error if the file already exists, the template is
+/// unknown, or the working directory cannot be determined.
+pub fn run(
+    ui: UiContext,
+    dest: &Path,
+    repo: Option<&str>,
+    sources: &[String],
+    template: Option<&str>,
+    list_templates: bool,
+) -> Result<()> {
+    if list_templates {
+        print!("{}", render_template_list());
+        return Ok(());
+    }
+    let template = template.map_or(Ok(&TEMPLATES[0]), find_template)?;
+
     if dest.exists() {
-        let outcome = StageOutcome {
-            label: format!(
+        report(
+            ui,
+            format!(
                 "'{}' already exists — refusing to overwrite.\n                 Delete it manually or use --config to specify a different path.",
                 dest.display()
             ),
-            success: false,
-            stdout: String::new(),
-            stderr: String::new(),
-            error: None,
-        };
-        outcome.print();
+            false,
+        );
         anyhow::bail!("");
     }
 
-    let content = generate_config()?;
+    let dir = dest
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    if is_existing_repo(dir) {
+        print_warning(&format!(
+            "'{}' is a rustic repository — use `backup import --repo {}` instead",
+            dir.display(),
+            dir.display()
+        ));
+        anyhow::bail!(
+            "refusing to write '{}' into an existing repository",
+            dest.display()
+        );
+    }
+    if let Some(repo) = repo
+        && is_existing_repo(Path::new(repo))
+    {
+        print_warning(&format!(
+            "'{repo}' already holds a repository — `backup import --repo {repo}` fills in its sources"
+        ));
+    }
+
+    let content = generate_config(template, repo, sources)?;
 
     std::fs::write(dest, &content).with_context(|| format!("writing '{}'", dest.display()))?;
 
-    let outcome = StageOutcome {
-        label: format!("Created '{}'", dest.display()),
-        success: true,
+    if dest.file_name().is_some_and(|name| name == GITIGNORE_ENTRY) {
+        scaffold_gitignore_entry(dir)?;
+    }
+
+    report(ui, format!("Created '{}'", dest.display()), true);
+    Ok(())
+}
+
+/// Run `init --global`: write the commented global config to `dest`,
+/// creating its parent directory.
+///
+/// Returns an error if the file already exists.
+pub fn run_global(ui: UiContext, dest: &Path) -> Result<()> {
+    if dest.exists() {
+        report(
+            ui,
+            format!(
+                "'{}' already exists — refusing to overwrite.\n                 Edit it directly or delete it first.",
+                dest.display()
+            ),
+            false,
+        );
+        anyhow::bail!("");
+    }
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("mkdir -p {}", dir.display()))?;
+    }
+    std::fs::write(dest, render_global_config(&current_username()))
+        .with_context(|| format!("writing '{}'", dest.display()))?;
+    report(ui, format!("Created '{}'", dest.display()), true);
+    Ok(())
+}
+
+/// Print a one-line outcome for `init`, like a pipeline stage.
+fn report(ui: UiContext, label: String, success: bool) {
+    StageOutcome {
+        label,
+        stage_id: None,
+        success,
         stdout: String::new(),
         stderr: String::new(),
         error: None,
+        error_kind: None,
+    }
+    .print(ui);
+}
+
+/// `true` when `path` looks like a rustic or restic repository: a `config`
+/// file alongside `keys/` and `snapshots/` directories.
+pub fn is_existing_repo(path: &Path) -> bool {
+    path.join("config").is_file() && path.join("keys").is_dir() && path.join("snapshots").is_dir()
+}
+
+/// The line [`scaffold_gitignore_entry`] adds to `.gitignore`.
+const GITIGNORE_ENTRY: &str = "backup.toml";
+
+/// Append `backup.toml` to `work_dir/.gitignore` when `work_dir` is a git
+/// checkout, creating the file if it does not exist.
+///
+/// Does nothing outside a checkout, or when `.gitignore` already lists
+/// `backup.toml` (or `/backup.toml`).
+pub fn scaffold_gitignore_entry(work_dir: &Path) -> Result<()> {
+    if !work_dir.join(".git").exists() {
+        return Ok(());
+    }
+    let path = work_dir.join(".gitignore");
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("reading '{}'", path.display())),
     };
-    outcome.print();
+    if existing
+        .lines()
+        .any(|line| line.trim().trim_start_matches('/') == GITIGNORE_ENTRY)
+    {
+        return Ok(());
+    }
 
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    let _ = writeln!(
+        content,
+        "# Added by `backup init`: may hold the repository password\n{GITIGNORE_ENTRY}"
+    );
+    std::fs::write(&path, content).with_context(|| format!("writing '{}'", path.display()))?;
+    tracing::info!(path = %path.display(), "added {GITIGNORE_ENTRY} to .gitignore");
     Ok(())
 }
 
@@ -76,17 +250,19 @@ impl EnvContext {
             .to_string_lossy()
             .into_owned();
 
-        // Try $USER first (set by most login shells), fall back to $LOGNAME
-        // (POSIX), then give up gracefully with a placeholder.
-        let username = std::env::var("USER")
-            .or_else(|_| std::env::var("LOGNAME"))
-            .unwrap_or_else(|_| "user".into());
+        let username = current_username();
 
         // The last path component makes a good default repo name, e.g.
-        // running inside "/home/alice/projects/myapp" → "myapp".
-        let repo_name = PathBuf::from(&cwd)
-            .file_name()
-            .map_or_else(|| "backup".into(), |n| n.to_string_lossy().into_owned());
+        // running inside "/home/alice/projects/myapp" → "myapp".  A git
+        // remote, when there is one, names the project more reliably.
+        let repo_name = detect_git_context(Path::new(&cwd)).map_or_else(
+            || {
+                PathBuf::from(&cwd)
+                    .file_name()
+                    .map_or_else(|| "backup".into(), |n| n.to_string_lossy().into_owned())
+            },
+            |git| git.project_name,
+        );
 
         Ok(Self {
             cwd,
@@ -96,20 +272,215 @@ impl EnvContext {
     }
 }
 
+/// Login name of the user running the process.
+///
+/// Tries `$USER` first (set by most login shells), falls back to `$LOGNAME`
+/// (POSIX), then gives up gracefully with a placeholder.
+fn current_username() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .unwrap_or_else(|_| "user".into())
+}
+
+// ─── Git detection ────────────────────────────────────────────────────────────
+
+/// What `backup init` learned from a git checkout in the working directory.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GitContext {
+    /// Remote the project name was taken from, e.g. `"origin"`.
+    pub remote: String,
+    /// Project name derived from that remote's URL, e.g. `"myapp"`.
+    pub project_name: String,
+}
+
+/// Inspect `cwd/.git/config` and derive a project name from its remotes.
+///
+/// Returns `None` when `cwd` is not a git checkout, the config cannot be read,
+/// or no remote has a usable URL.  See the module docs for the heuristic.
+pub fn detect_git_context(cwd: &Path) -> Option<GitContext> {
+    let text = std::fs::read_to_string(cwd.join(".git").join("config")).ok()?;
+
+    let mut remotes: Vec<(String, String)> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            current = line
+                .strip_prefix("[remote \"")
+                .and_then(|rest| rest.strip_suffix("\"]"))
+                .map(String::from);
+        } else if let Some(remote) = &current
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+        {
+            remotes.push((remote.clone(), value.trim().to_string()));
+        }
+    }
+
+    let (remote, url) = remotes
+        .iter()
+        .find(|(name, _)| name == "origin")
+        .or_else(|| remotes.first())?;
+
+    Some(GitContext {
+        remote: remote.clone(),
+        project_name: project_name_from_url(url)?,
+    })
+}
+
+/// Last path segment of a git remote URL, without a trailing `.git`.
+fn project_name_from_url(url: &str) -> Option<String> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// ─── Templates ────────────────────────────────────────────────────────────────
+
+/// A named starting point for `backup init`.
+#[derive(Debug)]
+pub struct Template {
+    /// Name given to `--template`.
+    pub name: &'static str,
+    /// One line for `--list-templates`.
+    pub description: &'static str,
+    /// `[backup].globs` entries, each with its trailing comment.
+    globs: &'static [(&'static str, &'static str)],
+}
+
+/// Every template `backup init` knows; the first is the default.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "default",
+        description: "Any directory, with VCS data, build output and disk images excluded",
+        globs: &[
+            ("!**/.git", "Git object store (large, reconstructible)"),
+            ("!tmp/", "Temporary files"),
+            ("!**/target/", "Rust build artefacts"),
+            ("!**/node_modules/", "Node.js dependencies (reinstallable)"),
+            ("!**/*.iso", "Disk images"),
+            ("!**/*.swap", "Swap files"),
+        ],
+    },
+    Template {
+        name: "rust",
+        description: "Rust project with target/ excluded",
+        globs: &[
+            ("!**/.git", "Git object store (large, reconstructible)"),
+            ("!**/target/", "Cargo build artefacts"),
+            ("!**/*.rs.bk", "rustfmt backups"),
+        ],
+    },
+    Template {
+        name: "node",
+        description: "Node.js project with node_modules/ and dist/ excluded",
+        globs: &[
+            ("!**/.git", "Git object store (large, reconstructible)"),
+            ("!**/node_modules/", "Dependencies (reinstallable)"),
+            ("!**/dist/", "Build output"),
+        ],
+    },
+    Template {
+        name: "home",
+        description: "Home directory with caches, downloads and trash excluded",
+        globs: &[
+            ("!**/.cache/", "Application caches"),
+            ("!Downloads/", "Re-downloadable files"),
+            ("!.local/share/Trash/", "Desktop trash"),
+            ("!**/node_modules/", "Node.js dependencies (reinstallable)"),
+            ("!**/target/", "Rust build artefacts"),
+        ],
+    },
+];
+
+/// The template called `name`, or an error listing the valid names.
+pub fn find_template(name: &str) -> Result<&'static Template> {
+    TEMPLATES.iter().find(|t| t.name == name).ok_or_else(|| {
+        let names: Vec<_> = TEMPLATES.iter().map(|t| t.name).collect();
+        anyhow::anyhow!(
+            "unknown template '{name}' (available: {})",
+            names.join(", ")
+        )
+    })
+}
+
+/// The `--list-templates` table: one `name  description` line per template.
+pub fn render_template_list() -> String {
+    let width = TEMPLATES.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    TEMPLATES.iter().fold(String::new(), |mut out, t| {
+        let _ = writeln!(out, "{:<width$}  {}", t.name, t.description);
+        out
+    })
+}
+
 /// Generate the full text of a starter `backup.toml`.
 ///
-/// Exposed as a public function so it can be tested independently of the
-/// filesystem.
-pub fn generate_config() -> Result<String> {
+/// `repo` and `sources` override the values derived from the working
+/// directory; when both are given it is never read.  Exposed as a public
+/// function so it can be tested independently of the filesystem.
+pub fn generate_config(
+    template: &Template,
+    repo: Option<&str>,
+    sources: &[String],
+) -> Result<String> {
+    if let Some(repo) = repo
+        && !sources.is_empty()
+    {
+        return Ok(render_template(
+            template,
+            repo,
+            sources,
+            &current_username(),
+        ));
+    }
     let ctx = EnvContext::resolve()?;
-    Ok(render_template(&ctx.cwd, &ctx.username, &ctx.repo_name))
+    let repo_path = repo.map_or_else(
+        || default_repo_path(&ctx.username, &ctx.repo_name),
+        String::from,
+    );
+    let sources = if sources.is_empty() {
+        std::slice::from_ref(&ctx.cwd)
+    } else {
+        sources
+    };
+    Ok(render_template(
+        template,
+        &repo_path,
+        sources,
+        &ctx.username,
+    ))
 }
 
-/// Render the TOML template given the three dynamic values.
+/// The repository path `backup init` suggests for `repo_name`.
+fn default_repo_path(username: &str, repo_name: &str) -> String {
+    format!("/home/{username}/nfs/new-backups/rustic/{repo_name}")
+}
+
+/// Render `template` for a repository path, sources and username.
 ///
 /// Kept separate from `Context::resolve` so tests can call it with
 /// controlled inputs without touching the environment.
-pub fn render_template(cwd: &str, username: &str, repo_name: &str) -> String {
+pub fn render_template(
+    template: &Template,
+    repo_path: &str,
+    sources: &[String],
+    username: &str,
+) -> String {
+    let repo_path = toml::Value::String(repo_path.into());
+    let sources = sources.iter().fold(String::new(), |mut out, s| {
+        let _ = writeln!(out, "    {},", toml::Value::String(s.clone()));
+        out
+    });
+    let globs = template
+        .globs
+        .iter()
+        .fold(String::new(), |mut out, (glob, comment)| {
+            let entry = format!("{},", toml::Value::String((*glob).into()));
+            let _ = writeln!(out, "    {entry:<22} # {comment}");
+            out
+        });
     format!(
         r#"# backup configuration
 # Run with: backup  (reads backup.toml in the current directory)
@@ -117,11 +488,16 @@ pub fn render_template(cwd: &str, username: &str, repo_name: &str) -> String {
 
 [repo]
 # Filesystem path (or sftp:/rclone: URI) of the rustic repository.
-# The directory will be created automatically on the first run.
-path = "/home/{username}/nfs/new-backups/rustic/{repo_name}"
+# Run `backup --init-if-missing` once to create it.
+path = {repo_path}
 # Encryption password.  Use "" for an unencrypted repository.
 # WARNING: do not commit real passwords to version control.
 password = ""
+# Or read it from an environment variable (a .env file beside this config is
+# loaded automatically — keep it out of version control too).
+# password_env = "BACKUP_PASSWORD"
+# Global rustic flags added to every command, before the subcommand.
+# extra_args = ["--no-cache"]
 
 [mount]
 # Optional: mount a NAS share before backing up.
@@ -130,12 +506,16 @@ password = ""
 # pictures, movies, videos, backups, owncloud, lan-share, repos, documents.
 # share = "new-backups"
 # user  = "{username}"   # defaults to $USER if omitted
+# mountpoint = "/Volumes/new-backups"   # defaults to /home/<user>/nfs/<share>
+# timeout_secs = 30   # give up if the server does not answer in time
+# Extra share names, or another server for a built-in one:
+# [mount.shares]
+# media = "media.lan:/export/media"
 
 [backup]
 # Paths to include in the snapshot.  Defaults to "." if this list is empty.
 sources = [
-    "{cwd}",
-]
+{sources}]
 
 # zstd compression level (1 = fastest / largest, 22 = slowest / smallest).
 # Level 3 is a good default for mixed source trees.
@@ -146,16 +526,26 @@ compression = 3
 # you never want archived.
 exclude_if_present = "ignore"
 
+# Also skip directories holding a CACHEDIR.TAG file (the cache-directory
+# standard many tools follow).  Works alongside exclude_if_present.
+# exclude_caches = true
+
+# Skip files rustic cannot read instead of failing the whole backup.  The
+# run then succeeds even though those files are missing from the snapshot.
+# ignore_inaccessible = true
+
+# Skip rustic's scan of the sources before backing up.  Incremental runs of
+# a large tree start sooner, but the progress display has no total or ETA.
+# no_scan = true
+
 # Glob patterns forwarded to rustic --glob.
 # Patterns starting with "!" exclude matching paths.
 globs = [
-    "!**/.git",            # Git object store (large, reconstructible)
-    "!tmp/",               # Temporary files
-    "!**/target/",         # Rust build artefacts
-    "!**/node_modules/",   # Node.js dependencies (reinstallable)
-    "!**/*.iso",           # Disk images
-    "!**/*.swap",          # Swap files
-]
+{globs}]
+
+# Key/value annotations stored with every snapshot.
+# [backup.custom_metadata]
+# project = "my-project"
 
 [retention]
 # How many snapshots to keep when pruning.  rustic selects the most recent
@@ -163,6 +553,34 @@ globs = [
 keep_daily   = 2   # keep one snapshot per day for the last N days
 keep_weekly  = 1   # keep one snapshot per week for the last N weeks
 keep_monthly = 1   # keep one snapshot per month for the last N months
+# group_by = "host"  # apply the windows per host when machines share a repo
+"#
+    )
+}
+
+/// Render the global `config.toml`: an empty `[mount]` section whose keys,
+/// and a few `[mount.shares]` entries, are commented examples.
+pub fn render_global_config(username: &str) -> String {
+    format!(
+        r#"# backup.rs global configuration
+# Defaults for every project; a key set in a project's backup.toml wins.
+# Generated by: backup init --global
+
+[mount]
+# Mount a NAS share before every backup, unless the project names another.
+# share = "new-backups"
+# user  = "{username}"   # defaults to $USER if omitted
+# mountpoint = "/Volumes/new-backups"   # defaults to /home/<user>/nfs/<share>
+# method = "nfs"   # or {{ rclone = {{ remote = "gdrive" }} }} for cloud storage
+# timeout_secs = 30   # give up if the server does not answer in time
+
+# NFS shares every project can name in [mount].share, each mapped to its
+# server:/export/path.  An entry here can also point a built-in share name
+# at a different server.
+# [mount.shares]
+# media   = "media.lan:/export/media"
+# archive = "nas.lan:/volume1/archive"
+# photos  = "192.168.1.20:/srv/nfs/photos"
 "#
     )
 }
@@ -177,9 +595,19 @@ mod tests {
 
     // ── render_template ───────────────────────────────────────────────────────
 
+    /// The template as `generate_config` renders it for `cwd` with no flags.
+    pub(super) fn render(cwd: &str, username: &str, repo_name: &str) -> String {
+        render_template(
+            &TEMPLATES[0],
+            &default_repo_path(username, repo_name),
+            &[cwd.to_string()],
+            username,
+        )
+    }
+
     #[test]
     fn template_contains_cwd() {
-        let out = render_template("/home/alice/myapp", "alice", "myapp");
+        let out = render("/home/alice/myapp", "alice", "myapp");
         assert!(
             out.contains("/home/alice/myapp"),
             "sources must contain cwd"
@@ -188,7 +616,7 @@ mod tests {
 
     #[test]
     fn template_contains_username() {
-        let out = render_template("/home/bob/proj", "bob", "proj");
+        let out = render("/home/bob/proj", "bob", "proj");
         assert!(
             out.contains("/home/bob/nfs/new-backups/rustic/proj"),
             "repo path must include username"
@@ -197,27 +625,21 @@ mod tests {
 
     #[test]
     fn template_contains_repo_name() {
-        let out = render_template("/srv/apps/widget", "alice", "widget");
+        let out = render("/srv/apps/widget", "alice", "widget");
         assert!(out.contains("widget"), "repo name must appear in repo path");
     }
 
     #[test]
     fn template_is_valid_toml() {
-        let out = render_template("/tmp/test", "testuser", "test");
+        let out = render("/tmp/test", "testuser", "test");
         // Strip TOML inline comments before parsing — the `toml` crate
         // handles `# …` comments on their own lines but the version we use
         // can balk at trailing inline comments on value lines.  Strip them
         // so the test stays robust across crate versions.
         let stripped: String = out
             .lines()
-            .map(|l| {
-                // Keep the whole line unless it has an inline comment after a value.
-                if let Some(idx) = l.find("   #") {
-                    &l[..idx]
-                } else {
-                    l
-                }
-            })
+            // Keep the whole line unless it has an inline comment after a value.
+            .map(|l| l.find("   #").map_or(l, |idx| &l[..idx]))
             .collect::<Vec<_>>()
             .join("\n");
 
@@ -226,12 +648,185 @@ mod tests {
 
     #[test]
     fn template_has_expected_sections() {
-        let out = render_template("/tmp/x", "x", "x");
+        let out = render("/tmp/x", "x", "x");
         for section in &["[repo]", "[mount]", "[backup]", "[retention]"] {
             assert!(out.contains(section), "missing section {section}");
         }
     }
 
+    // ── --repo / --source ─────────────────────────────────────────────────────
+
+    #[test]
+    fn explicit_repo_and_sources_appear_verbatim() {
+        let sources = ["/srv/app".to_string(), "/etc/my app".to_string()];
+        let out = generate_config(&TEMPLATES[0], Some("sftp:nas:/rustic/app"), &sources).unwrap();
+        assert!(out.contains("path = \"sftp:nas:/rustic/app\"\n"), "{out}");
+        assert!(out.contains("sources = [\n    \"/srv/app\",\n    \"/etc/my app\",\n]"));
+        assert!(!out.contains("nfs/new-backups/rustic"));
+    }
+
+    #[test]
+    fn missing_repo_falls_back_to_default_path() {
+        let out = generate_config(&TEMPLATES[0], None, &["/data".to_string()]).unwrap();
+        assert!(out.contains("nfs/new-backups/rustic/"), "{out}");
+        assert!(out.contains("    \"/data\",\n"));
+    }
+
+    #[test]
+    fn explicit_values_parse_back_into_config() {
+        let out = generate_config(&TEMPLATES[0], Some("/r"), &[
+            "/a".to_string(),
+            "/b".to_string(),
+        ])
+        .unwrap();
+        let stripped: String = out
+            .lines()
+            .map(|l| l.find("   #").map_or(l, |idx| &l[..idx]))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let cfg: crate::config::Config = toml::from_str(&stripped).unwrap();
+        assert_eq!(cfg.repo.path, "/r");
+        assert_eq!(cfg.backup.sources, ["/a", "/b"]);
+    }
+
+    #[test]
+    fn init_flags_parse() {
+        use clap::Parser;
+
+        use crate::cli::{Cli, Subcommand};
+
+        let cli = Cli::parse_from([
+            "backup", "init", "--repo", "/r", "--source", "/a", "--source", "/b",
+        ]);
+        assert_eq!(
+            cli.command,
+            Some(Subcommand::Init {
+                repo: Some("/r".into()),
+                sources: vec!["/a".into(), "/b".into()],
+                template: None,
+                list_templates: false,
+                global: false,
+            })
+        );
+    }
+
+    // ── templates ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn template_list_shows_rust_with_description() {
+        let list = render_template_list();
+        let rust = list
+            .lines()
+            .find(|l| l.starts_with("rust "))
+            .expect("rust template listed");
+        assert!(
+            rust.ends_with("Rust project with target/ excluded"),
+            "{rust}"
+        );
+        assert_eq!(list.lines().count(), TEMPLATES.len());
+    }
+
+    #[test]
+    fn list_templates_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("backup.toml");
+        run(UiContext::default(), &dest, None, &[], None, true).unwrap();
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn unknown_template_is_an_error_naming_the_choices() {
+        let err = find_template("haskell").unwrap_err().to_string();
+        assert!(err.contains("'haskell'"), "{err}");
+        assert!(err.contains("default, rust, node, home"), "{err}");
+    }
+
+    #[test]
+    fn every_template_renders_valid_toml() {
+        for template in TEMPLATES {
+            let out = render_template(template, "/r", &["/src".into()], "alice");
+            let cfg: crate::config::Config =
+                toml::from_str(&out).unwrap_or_else(|e| panic!("{}: {e}", template.name));
+            assert_eq!(
+                cfg.backup.globs,
+                template.globs.iter().map(|(g, _)| *g).collect::<Vec<_>>(),
+                "{}",
+                template.name
+            );
+        }
+    }
+
+    #[test]
+    fn rust_template_excludes_target() {
+        let out =
+            generate_config(find_template("rust").unwrap(), Some("/r"), &["/a".into()]).unwrap();
+        assert!(out.contains(r#""!**/target/","#));
+        assert!(!out.contains("node_modules"));
+    }
+
+    // ── detect_git_context ────────────────────────────────────────────────────
+
+    fn git_checkout(config: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git").join("config"), config).unwrap();
+        dir
+    }
+
+    #[test]
+    fn git_context_uses_origin_remote() {
+        let dir = git_checkout(
+            "[core]\n\tbare = false\n\
+             [remote \"upstream\"]\n\turl = https://example.com/org/upstream-name.git\n\
+             [remote \"origin\"]\n\turl = git@github.com:alice/myapp.git\n\
+             \tfetch = +refs/heads/*:refs/remotes/origin/*\n",
+        );
+        assert_eq!(
+            detect_git_context(dir.path()),
+            Some(GitContext {
+                remote: "origin".into(),
+                project_name: "myapp".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn git_context_falls_back_to_first_remote() {
+        let dir = git_checkout("[remote \"mirror\"]\n\turl = https://example.com/org/widget/\n");
+        let ctx = detect_git_context(dir.path()).unwrap();
+        assert_eq!(ctx.remote, "mirror");
+        assert_eq!(ctx.project_name, "widget");
+    }
+
+    #[test]
+    fn git_context_none_without_remote() {
+        let dir = git_checkout("[core]\n\tbare = false\n");
+        assert!(detect_git_context(dir.path()).is_none());
+    }
+
+    #[test]
+    fn git_context_none_outside_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(detect_git_context(dir.path()).is_none());
+    }
+
+    #[test]
+    fn project_name_handles_common_url_shapes() {
+        for url in [
+            "git@github.com:alice/myapp.git",
+            "https://github.com/alice/myapp.git",
+            "https://github.com/alice/myapp",
+            "ssh://git@host:22/alice/myapp.git/",
+            "/srv/git/myapp.git",
+        ] {
+            assert_eq!(
+                project_name_from_url(url).as_deref(),
+                Some("myapp"),
+                "{url}"
+            );
+        }
+    }
+
     // ── run ───────────────────────────────────────────────────────────────────
 
     #[test]
@@ -239,7 +834,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let dest = dir.path().join("backup.toml");
 
-        run(&dest).expect("init should succeed");
+        run(UiContext::default(), &dest, None, &[], None, false).expect("init should succeed");
 
         assert!(dest.exists(), "backup.toml should have been created");
         let content = fs::read_to_string(&dest).unwrap();
@@ -252,18 +847,182 @@ mod tests {
         let dest = dir.path().join("backup.toml");
         fs::write(&dest, "existing content").unwrap();
 
-        let result = run(&dest);
+        let result = run(UiContext::default(), &dest, None, &[], None, false);
         assert!(result.is_err(), "should refuse to overwrite existing file");
 
         // Confirm the file was not modified.
         assert_eq!(fs::read_to_string(&dest).unwrap(), "existing content");
     }
 
+    // ── --global ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn run_global_creates_missing_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("backup.rs").join("config.toml");
+
+        run_global(UiContext::default(), &dest).expect("init --global should succeed");
+
+        let content = fs::read_to_string(&dest).unwrap();
+        assert!(content.contains("[mount]"), "{content}");
+        assert!(content.contains("# [mount.shares]"), "{content}");
+    }
+
+    #[test]
+    fn run_global_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("config.toml");
+        fs::write(&dest, "[mount]\nshare = \"isos\"\n").unwrap();
+
+        assert!(run_global(UiContext::default(), &dest).is_err());
+        assert_eq!(
+            fs::read_to_string(&dest).unwrap(),
+            "[mount]\nshare = \"isos\"\n"
+        );
+    }
+
+    #[test]
+    fn global_config_changes_nothing_until_uncommented() {
+        let out = render_global_config("alice");
+        let partial: crate::config::PartialConfig = toml::from_str(&out).unwrap();
+        assert!(partial.is_empty(), "{partial:?}");
+    }
+
+    #[test]
+    fn uncommented_global_config_parses() {
+        let rendered = render_global_config("alice");
+        let out: Vec<_> = rendered
+            .lines()
+            .map(|l| l.trim_start_matches("# "))
+            .filter(|l| l.contains(" = ") || l.starts_with('['))
+            .collect();
+        let partial: crate::config::PartialConfig = toml::from_str(&out.join("\n")).unwrap();
+        let cfg = partial.resolve();
+        assert_eq!(cfg.mount.share.as_deref(), Some("new-backups"));
+        assert_eq!(cfg.mount.user.as_deref(), Some("alice"));
+        assert_eq!(cfg.mount.timeout_secs, Some(30));
+        assert_eq!(cfg.mount.shares["media"], "media.lan:/export/media");
+    }
+
+    /// A directory with the layout of a rustic repository.
+    fn fake_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config"), "").unwrap();
+        fs::create_dir(dir.path().join("keys")).unwrap();
+        fs::create_dir(dir.path().join("snapshots")).unwrap();
+        dir
+    }
+
+    // ── scaffold_gitignore_entry ──────────────────────────────────────────────
+
+    #[test]
+    fn gitignore_is_created_in_a_checkout() {
+        let dir = git_checkout("");
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        let text = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(
+            text.starts_with("# "),
+            "entry should be commented: {text:?}"
+        );
+        assert!(text.lines().any(|l| l == "backup.toml"), "got {text:?}");
+    }
+
+    #[test]
+    fn gitignore_entry_is_appended_after_existing_lines() {
+        let dir = git_checkout("");
+        fs::write(dir.path().join(".gitignore"), "target").unwrap();
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        let text = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(text.starts_with("target\n#"), "got {text:?}");
+        assert!(text.ends_with("\nbackup.toml\n"), "got {text:?}");
+    }
+
+    #[test]
+    fn gitignore_entry_is_added_once() {
+        let dir = git_checkout("");
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        let first = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            first
+        );
+    }
+
+    #[test]
+    fn anchored_gitignore_entry_counts_as_present() {
+        let dir = git_checkout("");
+        fs::write(dir.path().join(".gitignore"), "/backup.toml\n").unwrap();
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            "/backup.toml\n"
+        );
+    }
+
+    #[test]
+    fn gitignore_is_left_alone_outside_a_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        scaffold_gitignore_entry(dir.path()).unwrap();
+        assert!(!dir.path().join(".gitignore").exists());
+    }
+
+    #[test]
+    fn init_in_a_checkout_ignores_the_new_config() {
+        let dir = git_checkout("");
+        let dest = dir.path().join("backup.toml");
+        run(UiContext::default(), &dest, None, &[], None, false).unwrap();
+        let text = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(text.lines().any(|l| l == "backup.toml"), "got {text:?}");
+    }
+
+    #[test]
+    fn repo_layout_is_detected() {
+        assert!(is_existing_repo(fake_repo().path()));
+    }
+
+    #[test]
+    fn partial_repo_layout_is_not_a_repo() {
+        let dir = fake_repo();
+        fs::remove_dir(dir.path().join("snapshots")).unwrap();
+        assert!(!is_existing_repo(dir.path()));
+        assert!(!is_existing_repo(tempfile::tempdir().unwrap().path()));
+    }
+
+    #[test]
+    fn run_refuses_to_write_into_a_repo() {
+        let dir = fake_repo();
+        let dest = dir.path().join("backup.toml");
+        let err = run(UiContext::default(), &dest, None, &[], None, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("existing repository"), "got: {err}");
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn run_with_existing_repo_flag_still_writes_config() {
+        let repo = fake_repo();
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("backup.toml");
+        let sources = ["/srv/app".to_string()];
+        run(
+            UiContext::default(),
+            &dest,
+            repo.path().to_str(),
+            &sources,
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(dest.exists());
+    }
+
     #[test]
     fn run_writes_non_empty_toml() {
         let dir = tempfile::tempdir().unwrap();
         let dest = dir.path().join("backup.toml");
-        run(&dest).unwrap();
+        run(UiContext::default(), &dest, None, &[], None, false).unwrap();
 
         let content = fs::read_to_string(&dest).unwrap();
         // At minimum the four expected sections must be present.
@@ -277,26 +1036,26 @@ mod tests {
 
 #[cfg(test)]
 mod snapshot_tests {
-    use super::render_template;
+    use super::tests::render;
 
     /// Lock down the exact shape of the generated config so any formatting
     /// change shows up as an explicit snapshot diff.
     #[test]
     fn snapshot_template_typical() {
-        let out = render_template("/home/alice/projects/myapp", "alice", "myapp");
+        let out = render("/home/alice/projects/myapp", "alice", "myapp");
         insta::assert_snapshot!(out);
     }
 
     #[test]
     fn snapshot_template_path_with_spaces() {
-        let out = render_template("/home/alice/my projects/widget", "alice", "widget");
+        let out = render("/home/alice/my projects/widget", "alice", "widget");
         insta::assert_snapshot!(out);
     }
 
     #[test]
     fn snapshot_template_root_fallback() {
         // When repo_name falls back to "backup" (e.g. cwd is "/")
-        let out = render_template("/", "root", "backup");
+        let out = render("/", "root", "backup");
         insta::assert_snapshot!(out);
     }
 }
@@ -0,0 +1,339 @@
+//! `backup key change-password` — rotate the repository password in place.
+//!
+//! rustic has no single "change password" operation: a new key is added for
+//! the new password and the old key is removed.  This runs both steps and
+//! keeps the repository openable whatever happens in between:
+//!
+//! 1. `rustic key list --json` with the old password — the repository must hold exactly one key, so
+//!    it is clear which one to remove.
+//! 2. `rustic key add --new-password <new>`.
+//! 3. `rustic key list --json` again; the one new ID is the added key.
+//! 4. `rustic key remove <old-id>`, authenticated with the *new* password.
+//!
+//! If step 4 fails, the added key is removed again with the old password, so
+//! the repository ends up exactly as it started.
+//!
+//! ```text
+//! backup key change-password --new-password-file ~/.config/backup/new-pw
+//! ```
+//!
+//! Afterwards, update `[repo].password` (or the variable named by
+//! `password_env`) — this command does not edit `backup.toml`.
+
+use std::{collections::BTreeSet, path::Path};
+
+use anyhow::{Context as _, Result};
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run `backup key change-password`.
+///
+/// The new password comes from `new_password` or, failing that, from the
+/// first line of `new_password_file`.
+pub fn run(
+    cli: &Cli,
+    cfg: &Config,
+    new_password: Option<&str>,
+    new_password_file: Option<&Path>,
+    confirm: bool,
+) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    let new = match (new_password, new_password_file) {
+        (Some(pw), _) => pw.to_string(),
+        (None, Some(path)) => read_password_file(path)?,
+        (None, None) => anyhow::bail!("--new-password or --new-password-file is required"),
+    };
+    if confirm {
+        let term = console::Term::stderr();
+        term.write_str("Confirm new password: ")
+            .context("prompting for password confirmation")?;
+        let again = term
+            .read_secure_line()
+            .context("reading password confirmation")?;
+        if again != new {
+            anyhow::bail!("passwords do not match — nothing was changed");
+        }
+    }
+    if new == cfg.repo.password {
+        anyhow::bail!("the new password is the same as the current one");
+    }
+
+    println!();
+    let list = run_stage(ui, "List keys", &build_key_list_args(cli, cfg), None);
+    list.print(ui);
+    if list.failed() {
+        anyhow::bail!("could not list repository keys");
+    }
+    let before = key_ids(&list.stdout)?;
+    let [old_id] = before.iter().collect::<Vec<_>>()[..] else {
+        anyhow::bail!(
+            "the repository has {} keys; remove the extra ones with `rustic key remove` first",
+            before.len()
+        );
+    };
+
+    let add = run_stage(ui, "Add key", &build_key_add_args(cli, cfg, &new), None);
+    add.print(ui);
+    if add.failed() {
+        anyhow::bail!("adding the new key failed — the old password still works");
+    }
+
+    let relist = run_stage(ui, "List keys", &build_key_list_args(cli, cfg), None);
+    relist.print(ui);
+    let added: Vec<String> = if relist.failed() {
+        vec![]
+    } else {
+        key_ids(&relist.stdout)?
+            .difference(&before)
+            .cloned()
+            .collect()
+    };
+    let [new_id] = &added[..] else {
+        anyhow::bail!(
+            "could not identify the added key; both passwords now open the repository — \
+             remove key {old_id} with `rustic key remove` once `[repo].password` is updated"
+        );
+    };
+
+    let remove = run_stage(
+        ui,
+        "Remove old key",
+        &build_key_remove_args(cli, cfg, &new, old_id),
+        None,
+    );
+    remove.print(ui);
+    if remove.failed() {
+        let rollback = run_stage(
+            ui,
+            "Roll back",
+            &build_key_remove_args(cli, cfg, &cfg.repo.password, new_id),
+            None,
+        );
+        rollback.print(ui);
+        if rollback.failed() {
+            anyhow::bail!(
+                "removing the old key failed and so did the rollback; both passwords \
+                 open the repository — remove key {new_id} or {old_id} by hand"
+            );
+        }
+        anyhow::bail!("removing the old key failed; rolled back — the old password still works");
+    }
+
+    println!("\n  Password changed.  Update [repo].password before the next run.");
+    Ok(())
+}
+
+/// The first line of `path`, without its line ending.
+fn read_password_file(path: &Path) -> Result<String> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("reading password file '{}'", path.display()))?;
+    Ok(text.lines().next().unwrap_or_default().to_string())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic key list --json`.
+pub fn build_key_list_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["key".into(), "list".into(), "--json".into()]);
+    cmd
+}
+
+/// Arguments for `rustic key add --new-password <new>`, authenticated with
+/// the current password.
+pub fn build_key_add_args(cli: &Cli, cfg: &Config, new: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend([
+        "key".into(),
+        "add".into(),
+        "--new-password".into(),
+        new.into(),
+    ]);
+    cmd
+}
+
+/// Arguments for `rustic key remove <id>`, authenticated with `password`
+/// rather than `[repo].password`.
+pub fn build_key_remove_args(cli: &Cli, cfg: &Config, password: &str, id: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    if let Some(i) = cmd.iter().position(|a| a == "--password") {
+        cmd[i + 1] = password.into();
+    }
+    cmd.extend(["key".into(), "remove".into(), id.into()]);
+    cmd
+}
+
+// ─── Key parsing ──────────────────────────────────────────────────────────────
+
+/// Key IDs in `rustic key list --json` output.
+///
+/// Accepts a list of key objects carrying an `id` field as well as a plain
+/// list of ID strings.
+pub fn key_ids(json: &str) -> Result<BTreeSet<String>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("parsing `rustic key list --json` output")?;
+    let items = value.as_array().map(Vec::as_slice).unwrap_or_default();
+    Ok(items
+        .iter()
+        .filter_map(|item| item.get("id").unwrap_or(item).as_str())
+        .map(String::from)
+        .collect())
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::{KeyAction, Subcommand};
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn make_cfg() -> Config {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/tmp/repo".into();
+        cfg.repo.password = "old".into();
+        cfg
+    }
+
+    // ── argument sequence ─────────────────────────────────────────────────────
+
+    #[test]
+    fn key_list_args() {
+        assert_eq!(build_key_list_args(&make_cli(&[]), &make_cfg()), vec![
+            "rustic",
+            "-r",
+            "/tmp/repo",
+            "--password",
+            "old",
+            "key",
+            "list",
+            "--json"
+        ]);
+    }
+
+    #[test]
+    fn key_add_authenticates_with_old_password() {
+        assert_eq!(
+            build_key_add_args(&make_cli(&[]), &make_cfg(), "new"),
+            vec![
+                "rustic",
+                "-r",
+                "/tmp/repo",
+                "--password",
+                "old",
+                "key",
+                "add",
+                "--new-password",
+                "new"
+            ]
+        );
+    }
+
+    #[test]
+    fn key_remove_authenticates_with_given_password() {
+        assert_eq!(
+            build_key_remove_args(&make_cli(&[]), &make_cfg(), "new", "abc123"),
+            vec![
+                "rustic",
+                "-r",
+                "/tmp/repo",
+                "--password",
+                "new",
+                "key",
+                "remove",
+                "abc123"
+            ]
+        );
+    }
+
+    #[test]
+    fn key_commands_honour_sudo() {
+        let cli = make_cli(&["--sudo"]);
+        let cfg = make_cfg();
+        for cmd in [
+            build_key_list_args(&cli, &cfg),
+            build_key_add_args(&cli, &cfg, "new"),
+            build_key_remove_args(&cli, &cfg, "new", "abc123"),
+        ] {
+            assert_eq!(cmd[..2], ["doas", "rustic"]);
+        }
+    }
+
+    // ── key_ids ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn key_ids_from_objects() {
+        let ids = key_ids(r#"[{"id": "bbb", "hostname": "box"}, {"id": "aaa"}]"#).unwrap();
+        assert_eq!(ids.into_iter().collect::<Vec<_>>(), ["aaa", "bbb"]);
+    }
+
+    #[test]
+    fn key_ids_from_strings() {
+        assert_eq!(key_ids(r#"["aaa"]"#).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn key_ids_rejects_invalid_json() {
+        assert!(key_ids("not json").is_err());
+    }
+
+    // ── CLI ───────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn change_password_requires_a_new_password() {
+        assert!(Cli::try_parse_from(["backup", "key", "change-password"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "backup",
+                "key",
+                "change-password",
+                "--new-password",
+                "a",
+                "--new-password-file",
+                "f",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn change_password_parses_flags() {
+        let cli = make_cli(&[
+            "key",
+            "change-password",
+            "--new-password",
+            "n",
+            "--confirm-password",
+        ]);
+        assert_eq!(
+            cli.command,
+            Some(Subcommand::Key {
+                action: KeyAction::ChangePassword {
+                    new_password: Some("n".into()),
+                    new_password_file: None,
+                    confirm_password: true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn password_file_first_line_is_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pw");
+        std::fs::write(&path, "s3cr3t\ntrailing\n").unwrap();
+        assert_eq!(read_password_file(&path).unwrap(), "s3cr3t");
+    }
+}
@@ -0,0 +1,233 @@
+//! `backup log` — show past pipeline runs from the persistent run log.
+//!
+//! | Invocation                   | Description                           |
+//! |------------------------------|---------------------------------------|
+//! | `backup log`                 | Every recorded run, oldest first      |
+//! | `backup log --label <label>` | Only runs started with that `--label` |
+//! | `backup log --since <date>`  | Only runs started on or after `date`  |
+//! | `backup log --until <date>`  | Only runs started on or before `date` |
+//!
+//! `--since` and `--until` take an ISO 8601 date such as `2026-10-16`, which
+//! covers that whole day in UTC, or a timestamp with an offset such as
+//! `2026-10-16T09:30:00Z`.  Both bounds are inclusive.
+//!
+//! See [`crate::runlog`] for where the log lives and what it contains.
+
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use console::style;
+use jiff::{Timestamp, civil, tz::TimeZone};
+
+use crate::runlog::{self, RunLogEntry};
+
+/// Entry point for `backup log`.
+pub fn run(label: Option<&str>, since: Option<Timestamp>, until: Option<Timestamp>) -> Result<()> {
+    if let (Some(since), Some(until)) = (since, until)
+        && since > until
+    {
+        anyhow::bail!("--since ({since}) is after --until ({until}), so no run can match");
+    }
+    let path = runlog::default_path().context("no data directory for the run log")?;
+    let entries = runlog::read(&path)?;
+    let shown: Vec<_> = filter_by_label(&entries, label)
+        .into_iter()
+        .filter(|e| started_within(e, since, until))
+        .collect();
+    if shown.is_empty() {
+        println!("  No runs recorded in '{}'.", path.display());
+        return Ok(());
+    }
+    print!("{}", render_entries(&shown));
+    Ok(())
+}
+
+/// Keep only entries whose label equals `label`; `None` keeps everything.
+pub fn filter_by_label<'a>(
+    entries: &'a [RunLogEntry],
+    label: Option<&str>,
+) -> Vec<&'a RunLogEntry> {
+    entries
+        .iter()
+        .filter(|e| label.is_none_or(|l| e.label.as_deref() == Some(l)))
+        .collect()
+}
+
+/// Whether `entry` started between `since` and `until`, both inclusive; a
+/// `None` bound is open.
+pub fn started_within(
+    entry: &RunLogEntry,
+    since: Option<Timestamp>,
+    until: Option<Timestamp>,
+) -> bool {
+    since.is_none_or(|s| entry.started_at >= s) && until.is_none_or(|u| entry.started_at <= u)
+}
+
+/// Parse a `--since` value; a bare date means the start of that day (UTC).
+pub fn parse_since(value: &str) -> Result<Timestamp, String> {
+    parse_bound(value, civil::Time::midnight())
+}
+
+/// Parse an `--until` value; a bare date means the end of that day (UTC).
+pub fn parse_until(value: &str) -> Result<Timestamp, String> {
+    parse_bound(value, civil::Time::MAX)
+}
+
+/// A timestamp with an offset, or a `YYYY-MM-DD` date at `time_of_day` UTC.
+fn parse_bound(value: &str, time_of_day: civil::Time) -> Result<Timestamp, String> {
+    if let Ok(at) = value.parse::<Timestamp>() {
+        return Ok(at);
+    }
+    civil::Date::strptime("%Y-%m-%d", value)
+        .and_then(|date| date.to_datetime(time_of_day).to_zoned(TimeZone::UTC))
+        .map(|zoned| zoned.timestamp())
+        .map_err(|_| {
+            format!(
+                "'{value}' is not an ISO 8601 date; use e.g. 2026-10-16 or 2026-10-16T09:30:00Z"
+            )
+        })
+}
+
+/// Render one line per entry: status icon, start time, label, repo.
+pub fn render_entries(entries: &[&RunLogEntry]) -> String {
+    let mut out = String::new();
+    for e in entries {
+        let icon = if e.success {
+            style("✓").green().bold()
+        } else {
+            style("✗").red().bold()
+        };
+        let label = e.label.as_deref().unwrap_or("-");
+        let _ = writeln!(
+            out,
+            "  {icon}  {}  {}  {}",
+            e.started_at,
+            style(label).bold(),
+            style(&e.repo).dim()
+        );
+    }
+    out
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: Option<&str>) -> RunLogEntry {
+        RunLogEntry {
+            started_at: "2026-10-16T09:30:00Z".parse().unwrap(),
+            label: label.map(Into::into),
+            repo: "/tmp/repo".into(),
+            success: true,
+        }
+    }
+
+    /// A log with one run on each of 14–18 October, written and read back
+    /// the way `backup log` reads it.
+    fn seeded_log() -> Vec<RunLogEntry> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runs.jsonl");
+        for day in 14..=18 {
+            let e = RunLogEntry {
+                started_at: format!("2026-10-{day}T09:30:00Z").parse().unwrap(),
+                ..entry(None)
+            };
+            runlog::append(&path, &e).unwrap();
+        }
+        runlog::read(&path).unwrap()
+    }
+
+    fn days_within(since: Option<&str>, until: Option<&str>) -> Vec<i8> {
+        let since = since.map(|s| parse_since(s).unwrap());
+        let until = until.map(|u| parse_until(u).unwrap());
+        seeded_log()
+            .iter()
+            .filter(|e| started_within(e, since, until))
+            .map(|e| e.started_at.to_zoned(TimeZone::UTC).day())
+            .collect()
+    }
+
+    #[test]
+    fn date_bounds_are_inclusive_whole_days() {
+        assert_eq!(days_within(Some("2026-10-15"), Some("2026-10-17")), [
+            15, 16, 17
+        ]);
+    }
+
+    #[test]
+    fn open_bounds_keep_the_other_side() {
+        assert_eq!(days_within(Some("2026-10-17"), None), [17, 18]);
+        assert_eq!(days_within(None, Some("2026-10-15")), [14, 15]);
+        assert_eq!(days_within(None, None), [14, 15, 16, 17, 18]);
+    }
+
+    #[test]
+    fn timestamp_bounds_are_exact() {
+        assert_eq!(
+            days_within(Some("2026-10-16T09:30:01Z"), Some("2026-10-18T09:30:00Z")),
+            [17, 18]
+        );
+        assert_eq!(
+            days_within(
+                Some("2026-10-16T11:30:00+02:00"),
+                Some("2026-10-16T11:30:00+02:00")
+            ),
+            [16]
+        );
+    }
+
+    #[test]
+    fn bare_dates_span_the_utc_day() {
+        assert_eq!(
+            parse_since("2026-10-16").unwrap().to_string(),
+            "2026-10-16T00:00:00Z"
+        );
+        assert_eq!(
+            parse_until("2026-10-16").unwrap().to_string(),
+            "2026-10-16T23:59:59.999999999Z"
+        );
+    }
+
+    #[test]
+    fn malformed_dates_are_rejected() {
+        for bad in [
+            "16/10/2026",
+            "2026-10-16T09:30:00",
+            "yesterday",
+            "2026-13-01",
+            "",
+        ] {
+            let err = parse_since(bad).unwrap_err();
+            assert!(err.contains("ISO 8601"), "{bad:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn label_filter_keeps_exact_matches_only() {
+        let entries = [
+            entry(Some("nightly")),
+            entry(None),
+            entry(Some("nightly-2")),
+        ];
+        let shown = filter_by_label(&entries, Some("nightly"));
+        assert_eq!(shown, [&entries[0]]);
+    }
+
+    #[test]
+    fn no_filter_keeps_everything() {
+        let entries = [entry(Some("a")), entry(None)];
+        assert_eq!(filter_by_label(&entries, None).len(), 2);
+    }
+
+    #[test]
+    fn rendered_line_shows_time_label_and_repo() {
+        let e = entry(Some("before-upgrade"));
+        let plain = console::strip_ansi_codes(&render_entries(&[&e])).into_owned();
+        assert_eq!(
+            plain,
+            "  ✓  2026-10-16T09:30:00Z  before-upgrade  /tmp/repo\n"
+        );
+    }
+}
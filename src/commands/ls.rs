@@ -0,0 +1,167 @@
+//! `backup ls <snapshot>` — list the files in a snapshot.
+//!
+//! | Invocation                           | Description                         |
+//! |--------------------------------------|-------------------------------------|
+//! | `backup ls latest`                   | Every path in the snapshot          |
+//! | `backup ls latest --source-index 1`  | Only the subtree of `sources[1]`    |
+//!
+//! A snapshot of several sources is one combined tree.  `--source-index`
+//! picks one entry of `[backup].sources` by position, resolves it to the
+//! absolute path rustic stored it under, and keeps only listing lines under
+//! that prefix.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_interactive, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// List `snapshot`, optionally restricted to the source at `source_index`.
+pub fn run(cli: &Cli, cfg: &Config, snapshot: &str, source_index: Option<usize>) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    let args = build_ls_args(cli, cfg, snapshot);
+    let Some(index) = source_index else {
+        return run_interactive(&args);
+    };
+
+    let prefix = source_prefix(cfg, index)?;
+    let listing = run_stage(ui, "List", &args, None);
+    if listing.failed() {
+        listing.print(ui);
+        anyhow::bail!("listing snapshot '{snapshot}' failed");
+    }
+    for line in filter_to_source(&listing.stdout, &prefix) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic ls <snapshot>`.
+pub fn build_ls_args(cli: &Cli, cfg: &Config, snapshot: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["ls".into(), snapshot.into()]);
+    cmd
+}
+
+// ─── Source filtering ─────────────────────────────────────────────────────────
+
+/// The absolute path of `[backup].sources[index]`, as rustic stores it.
+///
+/// An empty `sources` list means `["."]`, matching what the pipeline backs
+/// up.  An index past the end is an error naming the valid range.
+pub fn source_prefix(cfg: &Config, index: usize) -> Result<PathBuf> {
+    let default = [".".to_string()];
+    let sources = if cfg.backup.sources.is_empty() {
+        &default[..]
+    } else {
+        &cfg.backup.sources[..]
+    };
+    let source = sources.get(index).with_context(|| {
+        format!(
+            "--source-index {index} is out of range: [backup].sources has {} entr{} (0–{})",
+            sources.len(),
+            if sources.len() == 1 { "y" } else { "ies" },
+            sources.len() - 1
+        )
+    })?;
+    std::path::absolute(source).with_context(|| format!("resolving source '{source}'"))
+}
+
+/// Keep the lines of `listing` that name `prefix` or a path beneath it.
+///
+/// rustic may print paths with or without the leading `/`, so both sides are
+/// compared without it.  Matching is per path component: `/home/al` does not
+/// match `/home/alice`.
+pub fn filter_to_source<'a>(listing: &'a str, prefix: &Path) -> Vec<&'a str> {
+    let prefix = prefix.strip_prefix("/").unwrap_or(prefix);
+    listing
+        .lines()
+        .filter(|line| {
+            let path = Path::new(line.trim());
+            path.strip_prefix("/").unwrap_or(path).starts_with(prefix)
+        })
+        .collect()
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn cfg_with_sources(sources: &[&str]) -> Config {
+        let mut cfg = Config::default();
+        cfg.backup.sources = sources.iter().map(|s| (*s).to_string()).collect();
+        cfg
+    }
+
+    #[test]
+    fn ls_args_end_with_snapshot() {
+        let args = build_ls_args(&make_cli(&[]), &Config::default(), "latest");
+        assert_eq!(&args[args.len() - 2..], ["ls", "latest"]);
+    }
+
+    #[test]
+    fn source_index_picks_source_by_position() {
+        let cfg = cfg_with_sources(&["/etc", "/home/alice"]);
+        assert_eq!(source_prefix(&cfg, 1).unwrap(), Path::new("/home/alice"));
+    }
+
+    #[test]
+    fn out_of_range_source_index_errors() {
+        let cfg = cfg_with_sources(&["/etc", "/home/alice"]);
+        let err = source_prefix(&cfg, 2).unwrap_err().to_string();
+        assert!(err.contains("out of range"), "got: {err}");
+        assert!(err.contains("0–1"), "got: {err}");
+    }
+
+    #[test]
+    fn empty_sources_mean_current_directory() {
+        let cfg = cfg_with_sources(&[]);
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            source_prefix(&cfg, 0).unwrap(),
+            std::path::absolute(".").unwrap()
+        );
+        assert!(source_prefix(&cfg, 0).unwrap().starts_with(cwd));
+        assert!(source_prefix(&cfg, 1).is_err());
+    }
+
+    #[test]
+    fn negative_source_index_is_rejected_by_the_parser() {
+        assert!(Cli::try_parse_from(["backup", "ls", "latest", "--source-index", "-1"]).is_err());
+    }
+
+    #[test]
+    fn filter_keeps_only_the_source_subtree() {
+        let listing = "/etc\n/etc/hosts\n/home\n/home/alice\n/home/alice/notes.txt\n/home/alicia\n";
+        assert_eq!(filter_to_source(listing, Path::new("/home/alice")), [
+            "/home/alice",
+            "/home/alice/notes.txt"
+        ]);
+    }
+
+    #[test]
+    fn filter_ignores_leading_slash_differences() {
+        let listing = "home/alice\nhome/alice/a.txt\netc/hosts\n";
+        assert_eq!(filter_to_source(listing, Path::new("/home/alice")), [
+            "home/alice",
+            "home/alice/a.txt"
+        ]);
+    }
+}
@@ -0,0 +1,414 @@
+//! `backup mirror` — keep a replica of the repository at a second location.
+//!
+//! `main` first runs the full backup pipeline against `[repo]`; [`run`] then
+//! copies every snapshot newer than the last mirrored one to
+//! `[mirror].dest_repo`:
+//!
+//! ```text
+//! rustic -r <repo> -P <profile> copy <id>… --init
+//! ```
+//!
+//! `<profile>` is a temporary [`CopyProfile`] naming the replica and its
+//! password (`[mirror].dest_password`, else `[repo].password`), removed
+//! once the copy ends.
+//!
+//! # State file
+//!
+//! `$XDG_DATA_HOME/backup.rs/mirror.json` records, per (source, replica)
+//! pair, the time of the newest snapshot copied so far:
+//!
+//! ```text
+//! [{"source":"/mnt/nas/rustic/app","dest":"/mnt/offsite/rustic/app","last_snapshot_time":"2026-10-16T09:30:00Z"}]
+//! ```
+//!
+//! The record only moves forward after a successful copy, so a failed mirror
+//! is retried in full next time.  Dry runs pass `--dry-run` to rustic and
+//! leave the state file alone.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::Cli,
+    commands::snapshots::{SnapshotSummary, fetch_listing, parse_snapshots},
+    config::Config,
+    copy_profile::CopyProfile,
+    runner::{mask_url_password, repo_location, rustic_base},
+    ui::{UiContext, run_stage},
+};
+
+/// Newest snapshot mirrored from one repository to one replica.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorRecord {
+    /// Primary repository, as [`repo_location`] names it (URL passwords masked).
+    pub source: String,
+
+    /// Replica repository (`[mirror].dest_repo`).
+    pub dest: String,
+
+    /// Time of the newest snapshot copied to `dest`.
+    pub last_snapshot_time: jiff::Timestamp,
+}
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Copy the snapshots the replica is missing, then advance the state file.
+///
+/// Called after the backup pipeline, so the snapshot it just took is
+/// included.
+pub fn run(cli: &Cli, cfg: &Config) -> Result<()> {
+    let dest = dest_repo(cfg)?;
+    let ui = UiContext::from_cli(cli);
+    let state_path = default_state_path().context("no data directory for the mirror state")?;
+    let mut records = read_state(&state_path)?;
+    let source = mask_url_password(&repo_location(cfg)).into_owned();
+
+    let snapshots = parse_snapshots(&fetch_listing(cli, cfg)?)?;
+    let pending = pending_snapshots(&snapshots, last_mirrored(&records, &source, dest));
+    if pending.is_empty() {
+        println!("Mirror: {dest} is up to date");
+        return Ok(());
+    }
+
+    let ids: Vec<&str> = pending.iter().map(|s| s.id.as_str()).collect();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let label = format!("Mirror {} snapshot(s)", ids.len());
+    let password = cfg
+        .mirror
+        .dest_password
+        .as_deref()
+        .unwrap_or(&cfg.repo.password);
+    let profile = CopyProfile::create(dest, password)?;
+    let copy = run_stage(
+        ui,
+        &label,
+        &build_mirror_copy_args(cli, cfg, profile.name(), &ids),
+        timeout,
+    );
+    copy.print(ui);
+    if copy.failed() {
+        anyhow::bail!("mirror to {dest} failed");
+    }
+
+    if !cli.dry_run
+        && let Some(newest) = pending.iter().filter_map(|s| snapshot_time(s)).max()
+    {
+        record_mirrored(&mut records, &source, dest, newest);
+        write_state(&state_path, &records)?;
+    }
+    Ok(())
+}
+
+/// `[mirror].dest_repo`, or an error when it is unset or names the primary
+/// (as [`repo_location`] gives it, so a `[repo.rest]` URL counts).
+pub fn dest_repo(cfg: &Config) -> Result<&str> {
+    match cfg.mirror.dest_repo.as_deref() {
+        None | Some("") => anyhow::bail!("backup mirror needs [mirror].dest_repo in the config"),
+        Some(dest) if dest == repo_location(cfg) => {
+            anyhow::bail!("[mirror].dest_repo is the same repository as [repo]")
+        },
+        Some(dest) => Ok(dest),
+    }
+}
+
+// ─── Selection ────────────────────────────────────────────────────────────────
+
+/// The snapshots taken after `since`, or all of them when nothing has been
+/// mirrored yet.
+///
+/// A snapshot whose time does not parse is always included: copying it
+/// again is harmless, skipping it could lose it from the replica.
+pub fn pending_snapshots(
+    snapshots: &[SnapshotSummary],
+    since: Option<jiff::Timestamp>,
+) -> Vec<&SnapshotSummary> {
+    snapshots
+        .iter()
+        .filter(|s| match (since, snapshot_time(s)) {
+            (Some(since), Some(time)) => time > since,
+            _ => true,
+        })
+        .collect()
+}
+
+/// When `snapshot` was taken, if rustic's timestamp parses.
+fn snapshot_time(snapshot: &SnapshotSummary) -> Option<jiff::Timestamp> {
+    snapshot.time.parse().ok()
+}
+
+// ─── State file ───────────────────────────────────────────────────────────────
+
+/// Default state location, or `None` when the platform has no data directory.
+pub fn default_state_path() -> Option<PathBuf> {
+    dirs_next::data_dir().map(|d| d.join("backup.rs").join("mirror.json"))
+}
+
+/// Every record in the state file at `path`.  A missing file is an empty
+/// state.
+pub fn read_state(path: &Path) -> Result<Vec<MirrorRecord>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Replace the state file at `path` with `records`, creating its parent
+/// directory if needed.
+pub fn write_state(path: &Path, records: &[MirrorRecord]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("mkdir -p {}", dir.display()))?;
+    }
+    let text = serde_json::to_string_pretty(records).context("serialising mirror state")?;
+    std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Time of the newest snapshot mirrored from `source` to `dest`, if any.
+pub fn last_mirrored(
+    records: &[MirrorRecord],
+    source: &str,
+    dest: &str,
+) -> Option<jiff::Timestamp> {
+    records
+        .iter()
+        .find(|r| r.source == source && r.dest == dest)
+        .map(|r| r.last_snapshot_time)
+}
+
+/// Set the `source` → `dest` record to `time`, adding it if it is new.
+pub fn record_mirrored(
+    records: &mut Vec<MirrorRecord>,
+    source: &str,
+    dest: &str,
+    time: jiff::Timestamp,
+) {
+    match records
+        .iter_mut()
+        .find(|r| r.source == source && r.dest == dest)
+    {
+        Some(record) => record.last_snapshot_time = time,
+        None => records.push(MirrorRecord {
+            source: source.into(),
+            dest: dest.into(),
+            last_snapshot_time: time,
+        }),
+    }
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic -P <profile> copy <ids>… --init`.
+///
+/// `profile` is a [`CopyProfile`] naming the replica as the copy target;
+/// `--init` lets the first mirror create it.
+pub fn build_mirror_copy_args(cli: &Cli, cfg: &Config, profile: &str, ids: &[&str]) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["-P".into(), profile.into(), "copy".into()]);
+    cmd.extend(ids.iter().map(|&id| id.to_owned()));
+    cmd.push("--init".into());
+    if cli.dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::config::RestConfig;
+
+    const LISTING: &str = r#"[
+        {"id": "aaaa1111", "time": "2026-10-14T09:00:00Z", "paths": ["/a"]},
+        {"id": "bbbb2222", "time": "2026-10-15T09:00:00+02:00", "paths": ["/a"]},
+        {"id": "cccc3333", "time": "2026-10-16T09:00:00Z", "paths": ["/a"]}
+    ]"#;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn make_cfg() -> Config {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/tmp/repo".into();
+        cfg.mirror.dest_repo = Some("/tmp/replica".into());
+        cfg
+    }
+
+    fn ids(snapshots: &[&SnapshotSummary]) -> Vec<String> {
+        snapshots.iter().map(|s| s.id.clone()).collect()
+    }
+
+    fn ts(s: &str) -> jiff::Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn first_mirror_copies_every_snapshot() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        assert_eq!(ids(&pending_snapshots(&snapshots, None)), [
+            "aaaa1111", "bbbb2222", "cccc3333"
+        ]);
+    }
+
+    #[test]
+    fn only_snapshots_after_the_last_mirror_are_pending() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        // bbbb2222 is 07:00 UTC on the 15th — compared as an instant, not text.
+        let pending = pending_snapshots(&snapshots, Some(ts("2026-10-15T08:00:00Z")));
+        assert_eq!(ids(&pending), ["cccc3333"]);
+        let pending = pending_snapshots(&snapshots, Some(ts("2026-10-16T09:00:00Z")));
+        assert!(
+            pending.is_empty(),
+            "the last mirrored snapshot is not copied again"
+        );
+    }
+
+    #[test]
+    fn snapshot_with_unparsable_time_is_always_pending() {
+        let snapshots =
+            parse_snapshots(r#"[{"id": "dddd4444", "time": "yesterday", "paths": ["/a"]}]"#)
+                .unwrap();
+        let pending = pending_snapshots(&snapshots, Some(ts("2026-10-16T09:00:00Z")));
+        assert_eq!(ids(&pending), ["dddd4444"]);
+    }
+
+    #[test]
+    fn state_roundtrips_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("mirror.json");
+        assert!(
+            read_state(&path).unwrap().is_empty(),
+            "missing file is empty"
+        );
+
+        let mut records = vec![];
+        record_mirrored(
+            &mut records,
+            "/tmp/repo",
+            "/tmp/replica",
+            ts("2026-10-15T09:00:00Z"),
+        );
+        record_mirrored(
+            &mut records,
+            "/tmp/repo",
+            "/tmp/other",
+            ts("2026-10-14T09:00:00Z"),
+        );
+        write_state(&path, &records).unwrap();
+        assert_eq!(read_state(&path).unwrap(), records);
+    }
+
+    #[test]
+    fn recording_advances_only_the_matching_pair() {
+        let mut records = vec![];
+        record_mirrored(
+            &mut records,
+            "/tmp/repo",
+            "/tmp/replica",
+            ts("2026-10-15T09:00:00Z"),
+        );
+        record_mirrored(
+            &mut records,
+            "/tmp/repo",
+            "/tmp/other",
+            ts("2026-10-14T09:00:00Z"),
+        );
+        record_mirrored(
+            &mut records,
+            "/tmp/repo",
+            "/tmp/replica",
+            ts("2026-10-16T09:00:00Z"),
+        );
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            last_mirrored(&records, "/tmp/repo", "/tmp/replica"),
+            Some(ts("2026-10-16T09:00:00Z"))
+        );
+        assert_eq!(
+            last_mirrored(&records, "/tmp/repo", "/tmp/other"),
+            Some(ts("2026-10-14T09:00:00Z"))
+        );
+        assert_eq!(
+            last_mirrored(&records, "/tmp/elsewhere", "/tmp/replica"),
+            None
+        );
+    }
+
+    #[test]
+    fn corrupt_state_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mirror.json");
+        std::fs::write(&path, "not json").unwrap();
+        let err = format!("{:#}", read_state(&path).unwrap_err());
+        assert!(err.contains("parsing"), "got: {err}");
+    }
+
+    #[test]
+    fn dest_repo_must_be_set_and_separate() {
+        let mut cfg = make_cfg();
+        assert_eq!(dest_repo(&cfg).unwrap(), "/tmp/replica");
+
+        cfg.mirror.dest_repo = None;
+        let err = dest_repo(&cfg).unwrap_err().to_string();
+        assert!(err.contains("[mirror].dest_repo"), "got: {err}");
+
+        cfg.mirror.dest_repo = Some("/tmp/repo".into());
+        let err = dest_repo(&cfg).unwrap_err().to_string();
+        assert!(err.contains("same repository"), "got: {err}");
+    }
+
+    #[test]
+    fn dest_repo_is_compared_with_the_rest_url() {
+        let mut cfg = make_cfg();
+        cfg.repo.rest = Some(RestConfig {
+            url: "http://nas:8000/app".into(),
+            username: None,
+            password: None,
+        });
+        cfg.mirror.dest_repo = Some("/tmp/repo".into());
+        assert_eq!(
+            dest_repo(&cfg).unwrap(),
+            "/tmp/repo",
+            "[repo].path is unused"
+        );
+
+        cfg.mirror.dest_repo = Some("rest:http://nas:8000/app".into());
+        let err = dest_repo(&cfg).unwrap_err().to_string();
+        assert!(err.contains("same repository"), "got: {err}");
+    }
+
+    #[test]
+    fn copy_args_select_the_profile_then_list_ids() {
+        let args = build_mirror_copy_args(&make_cli(&[]), &make_cfg(), "backup-rs-copy-1", &[
+            "aaaa1111", "cccc3333",
+        ]);
+        let profile = args.iter().position(|a| a == "-P").unwrap();
+        assert_eq!(args[profile..], [
+            "-P",
+            "backup-rs-copy-1",
+            "copy",
+            "aaaa1111",
+            "cccc3333",
+            "--init"
+        ]);
+    }
+
+    #[test]
+    fn copy_args_keep_the_dest_password_off_the_command_line() {
+        let mut cfg = make_cfg();
+        cfg.mirror.dest_password = Some("t4rget".into());
+        let args = build_mirror_copy_args(&make_cli(&["--dry-run"]), &cfg, "backup-rs-copy-1", &[
+            "aaaa1111",
+        ]);
+        assert!(!args.iter().any(|a| a.contains("t4rget")), "{args:?}");
+        assert_eq!(args.last().map(String::as_str), Some("--dry-run"));
+    }
+}
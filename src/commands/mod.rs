@@ -2,10 +2,63 @@
 //!
 //! Each file in this module corresponds to one user-facing command:
 //!
-//! | File          | Invocation          | Description                        |
-//! |---------------|---------------------|------------------------------------|
-//! | `init.rs`     | `backup init`       | Scaffold a `backup.toml`           |
-//! | `run.rs`      | `backup` (default)  | Full backup pipeline               |
+//! | File               | Invocation                   | Description                        |
+//! |--------------------|------------------------------|------------------------------------|
+//! | `init.rs`          | `backup init`                | Scaffold a `backup.toml`           |
+//! | `run.rs`           | `backup` (default)           | Full backup pipeline               |
+//! | `dump.rs`          | `backup dump`/`cat`          | Print a file from a snapshot       |
+//! | `ls.rs`            | `backup ls`                  | List a snapshot, or one source     |
+//! | `snapshots.rs`     | `backup snapshots`           | List snapshots, optionally grouped |
+//! | `snapshot_id.rs`   | `backup snapshot-id`         | Print a snapshot ID for scripts    |
+//! | `snapshot_size.rs` | `backup snapshot-size`       | Print one snapshot's data size     |
+//! | `cat_tree.rs`      | `backup cat-tree <snap>`     | Print a snapshot's root tree       |
+//! | `config_cmd.rs`    | `backup config …`            | Inspect, validate, export config   |
+//! | `diff_config.rs`   | `backup diff-config <file>`  | Compare two config files           |
+//! | `mount_cmd.rs`     | `backup mount`               | Mount the NAS share / list shares  |
+//! | `mount_cmd.rs`     | `backup umount`              | Unmount the NAS share              |
+//! | `fusemount.rs`     | `backup mount <snap> <dir>`  | FUSE-mount a snapshot              |
+//! | `mount_test.rs`    | `backup mount-test`          | Check the NAS is reachable         |
+//! | `log.rs`           | `backup log`                 | Show past runs from the run log    |
+//! | `check.rs`         | `backup check [mode]`        | Verify repository integrity        |
+//! | `gc.rs`            | `backup gc`                  | Prune unreferenced data only       |
+//! | `repair_index.rs`  | `backup repair-index`        | Rebuild the index from packs       |
+//! | `forget.rs`        | `backup forget <id>...`      | Remove snapshots by ID             |
+//! | `import.rs`        | `backup import`              | Config for an existing rustic repo |
+//! | `env_cmd.rs`       | `backup env`                 | Show the runtime environment       |
+//! | `self_check.rs`    | `backup self-check`          | Verify the binary's checksum       |
+//! | `completions.rs`   | `backup completions`         | Shell completion scripts           |
+//! | `export_schema.rs` | `backup export-schema`       | JSON Schema for `backup.toml`      |
+//! | `show_excludes.rs` | `backup show-excludes`       | List exclusion rules by origin     |
+//! | `paths.rs`         | `backup paths`               | List (and check) configured paths  |
+//! | `stdin_backup.rs`  | `backup backup-stdin <name>` | Back up data piped to stdin        |
+//! | `key_change.rs`    | `backup key change-password` | Rotate the repository password     |
+//! | `mirror.rs`        | `backup mirror`              | Back up, then copy to a replica    |
 
+pub mod cat_tree;
+pub mod check;
+pub mod completions;
+pub mod config_cmd;
+pub mod diff_config;
+pub mod dump;
+pub mod env_cmd;
+pub mod export_schema;
+pub mod forget;
+pub mod fusemount;
+pub mod gc;
+pub mod import;
 pub mod init;
+pub mod key_change;
+pub mod log;
+pub mod ls;
+pub mod mirror;
+pub mod mount_cmd;
+pub mod mount_test;
+pub mod paths;
+pub mod repair_index;
 pub mod run;
+pub mod self_check;
+pub mod show_excludes;
+pub mod snapshot_id;
+pub mod snapshot_size;
+pub mod snapshots;
+pub mod stdin_backup;
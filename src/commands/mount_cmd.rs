@@ -0,0 +1,104 @@
+//! `backup mount` / `backup umount` — mount or unmount the configured NAS
+//! share on its own.
+//!
+//! | Invocation                   | Description                              |
+//! |------------------------------|------------------------------------------|
+//! | `backup mount`               | Mount `[mount].share` and exit           |
+//! | `backup mount --list-shares` | Print every share name `[mount]` accepts |
+//! | `backup umount`              | Unmount `[mount].share`                  |
+//!
+//! Mounting is the same step the default pipeline runs first; see
+//! [`crate::mount`] for how it works.  `backup mount <snapshot> <dir>` is a
+//! different operation, handled by [`crate::commands::fusemount`].
+
+use std::fmt::Write as _;
+
+use anyhow::Result;
+use console::style;
+
+use crate::{
+    config::{Config, MountedPolicy},
+    mount,
+    ui::UiContext,
+};
+
+/// Entry point for `backup mount`.
+///
+/// With `dry_run`, prints the mount command instead of running it;
+/// `mounted` is what to do about a share that is already mounted.
+pub fn run(
+    ui: UiContext,
+    cfg: &Config,
+    list_shares: bool,
+    dry_run: bool,
+    mounted: MountedPolicy,
+) -> Result<()> {
+    if list_shares {
+        print!(
+            "{}",
+            render_share_list(&mount::list_known_shares(&cfg.mount))
+        );
+        return Ok(());
+    }
+
+    println!();
+    let outcome = mount::mount_share(&cfg.mount, dry_run, mounted);
+    outcome.print(ui);
+    if dry_run {
+        outcome.print_stdout();
+    }
+    if outcome.failed() {
+        anyhow::bail!("mount failed");
+    }
+    Ok(())
+}
+
+/// Entry point for `backup umount`.
+///
+/// With `dry_run`, prints the unmount command instead of running it.
+pub fn unmount(ui: UiContext, cfg: &Config, dry_run: bool) -> Result<()> {
+    println!();
+    let outcome = mount::unmount_share(&cfg.mount, dry_run);
+    outcome.print(ui);
+    if dry_run {
+        outcome.print_stdout();
+    }
+    if outcome.failed() {
+        anyhow::bail!("unmount failed");
+    }
+    Ok(())
+}
+
+/// Render `shares` as an aligned two-column table of name and NFS source.
+pub fn render_share_list(shares: &[(&str, &str)]) -> String {
+    let width = shares.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (name, source) in shares {
+        let _ = writeln!(
+            out,
+            "  {}  {}",
+            style(format!("{name:<width$}")).bold(),
+            style(source).dim()
+        );
+    }
+    out
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_list_aligns_sources() {
+        let out = render_share_list(&[("a", "x:/a"), ("longer", "y:/b")]);
+        let plain = console::strip_ansi_codes(&out);
+        assert_eq!(plain, "  a       x:/a\n  longer  y:/b\n");
+    }
+
+    #[test]
+    fn empty_share_list_renders_nothing() {
+        assert_eq!(render_share_list(&[]), "");
+    }
+}
@@ -0,0 +1,222 @@
+//! `backup mount-test` — check the NAS is reachable without mounting it.
+//!
+//! Looks up the NFS server of `[mount].share` and runs two probes:
+//!
+//! 1. `ping -c 1 -W 2 <server>` — is the host up, and how far away?
+//! 2. A TCP connect to the NFS port (2049 unless `--port` says otherwise).
+//!
+//! ```text
+//! $ backup mount-test
+//!   Check     Result  Latency
+//!   ping      ✓ pass  0.4 ms
+//!   tcp/2049  ✓ pass  1.2 ms
+//! ```
+//!
+//! Nothing is mounted or created.  The command fails if either probe fails,
+//! so it can gate a backup on an unreliable network.
+
+use std::{
+    fmt::Write as _,
+    net::{TcpStream, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use console::style;
+
+use crate::{config::Config, mount, ui::run_captured};
+
+/// How long each probe may take before it counts as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Result of one reachability probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    /// What was checked, e.g. `"ping"` or `"tcp/2049"`.
+    pub name: String,
+    /// Whether the check passed.
+    pub passed: bool,
+    /// Round-trip time, when the check measured one.
+    pub latency: Option<Duration>,
+    /// Why the check failed; empty when it passed.
+    pub detail: String,
+}
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Entry point for `backup mount-test`.
+pub fn run(cfg: &Config, port: u16) -> Result<()> {
+    let host = mount::configured_nfs_host(&cfg.mount)?;
+    println!("\n  Probing {} …", style(&host).bold());
+    let probes = [ping(&host), tcp_connect(&host, port, PROBE_TIMEOUT)];
+    print!("{}", render_table(&probes));
+    if probes.iter().any(|p| !p.passed) {
+        anyhow::bail!("'{host}' is not reachable");
+    }
+    Ok(())
+}
+
+// ─── Probes ───────────────────────────────────────────────────────────────────
+
+/// Send one ICMP echo to `host` with the system `ping`.
+fn ping(host: &str) -> Probe {
+    let name = "ping".to_string();
+    match run_captured(&["ping", "-c", "1", "-W", "2", host].map(String::from)) {
+        Ok(out) if out.success => Probe {
+            name,
+            passed: true,
+            latency: parse_ping_latency(&out.stdout),
+            detail: String::new(),
+        },
+        Ok(out) => failed(name, out.stderr.trim().to_string()),
+        Err(e) => failed(name, format!("could not run ping: {}", e.root_cause())),
+    }
+}
+
+/// Open (and immediately close) a TCP connection to `host:port`.
+pub fn tcp_connect(host: &str, port: u16, timeout: Duration) -> Probe {
+    let name = format!("tcp/{port}");
+    let addr = match (host, port).to_socket_addrs().map(|mut a| a.next()) {
+        Ok(Some(addr)) => addr,
+        Ok(None) => return failed(name, format!("'{host}' has no address")),
+        Err(e) => return failed(name, format!("could not resolve '{host}': {e}")),
+    };
+    let start = Instant::now();
+    match TcpStream::connect_timeout(&addr, timeout) {
+        Ok(_) => Probe {
+            name,
+            passed: true,
+            latency: Some(start.elapsed()),
+            detail: String::new(),
+        },
+        Err(e) => failed(name, e.to_string()),
+    }
+}
+
+const fn failed(name: String, detail: String) -> Probe {
+    Probe {
+        name,
+        passed: false,
+        latency: None,
+        detail,
+    }
+}
+
+/// Round-trip time from `ping` output: the `time=0.42 ms` of the reply line.
+pub fn parse_ping_latency(stdout: &str) -> Option<Duration> {
+    let (_, rest) = stdout.split_once("time=")?;
+    let ms: f64 = rest
+        .split(|c: char| c != '.' && !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    Duration::try_from_secs_f64(ms / 1000.0).ok()
+}
+
+// ─── Rendering ────────────────────────────────────────────────────────────────
+
+/// Aligned pass/fail table, one row per probe, failure reasons underneath.
+pub fn render_table(probes: &[Probe]) -> String {
+    let width = probes
+        .iter()
+        .map(|p| p.name.len())
+        .chain(["Check".len()])
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "  {}  {}  {}",
+        style(format!("{:<width$}", "Check")).bold(),
+        style("Result").bold(),
+        style("Latency").bold()
+    );
+    for probe in probes {
+        let result = if probe.passed {
+            style("✓ pass").green()
+        } else {
+            style("✗ fail").red()
+        };
+        let latency = probe.latency.map_or_else(
+            || "—".to_string(),
+            |d| format!("{:.1} ms", d.as_secs_f64() * 1000.0),
+        );
+        let _ = writeln!(out, "  {:<width$}  {result}  {latency}", probe.name);
+        if !probe.detail.is_empty() {
+            let _ = writeln!(out, "  {:<width$}  {}", "", style(&probe.detail).dim());
+        }
+    }
+    out
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn ping_latency_from_linux_output() {
+        let out = "PING nas.lan (10.0.0.2) 56(84) bytes of data.\n\
+                   64 bytes from 10.0.0.2: icmp_seq=1 ttl=64 time=0.421 ms\n";
+        assert_eq!(parse_ping_latency(out), Some(Duration::from_micros(421)));
+    }
+
+    #[test]
+    fn ping_latency_missing_when_no_reply() {
+        assert_eq!(
+            parse_ping_latency("1 packets transmitted, 0 received"),
+            None
+        );
+    }
+
+    #[test]
+    fn tcp_probe_passes_for_listening_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let probe = tcp_connect("127.0.0.1", port, PROBE_TIMEOUT);
+        assert!(probe.passed, "{probe:?}");
+        assert!(probe.latency.is_some());
+        assert_eq!(probe.name, format!("tcp/{port}"));
+    }
+
+    #[test]
+    fn tcp_probe_fails_for_closed_port() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let probe = tcp_connect("127.0.0.1", port, PROBE_TIMEOUT);
+        assert!(!probe.passed);
+        assert!(!probe.detail.is_empty());
+    }
+
+    #[test]
+    fn table_lists_each_probe_with_its_result() {
+        let probes = [
+            Probe {
+                name: "ping".into(),
+                passed: true,
+                latency: Some(Duration::from_micros(400)),
+                detail: String::new(),
+            },
+            failed("tcp/2049".into(), "connection refused".into()),
+        ];
+        let plain = console::strip_ansi_codes(&render_table(&probes)).into_owned();
+        assert_eq!(
+            plain,
+            "  Check     Result  Latency\n\
+             \x20 ping      ✓ pass  0.4 ms\n\
+             \x20 tcp/2049  ✗ fail  —\n\
+             \x20           connection refused\n"
+        );
+    }
+
+    #[test]
+    fn run_requires_an_nfs_share() {
+        assert!(run(&Config::default(), 2049).is_err());
+    }
+}
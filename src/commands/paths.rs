@@ -0,0 +1,383 @@
+//! `backup paths` — list every filesystem path the config refers to.
+//!
+//! | Invocation             | Description                                 |
+//! |------------------------|---------------------------------------------|
+//! | `backup paths`         | The resolved paths, one per line            |
+//! | `backup paths --check` | The same, each marked ✓, ⚠ or ✗ (see below) |
+//!
+//! ```text
+//! $ backup paths --check
+//!   ✓  config   /home/alice/app/backup.toml
+//!   ✓  source   /home/alice/app
+//!   ⚠  exclude  /home/alice/app/.backupignore  not readable
+//!   ✗  repo     /mnt/nas/rustic/app            /mnt/nas/rustic does not exist
+//! ```
+//!
+//! `--check` wants each path to exist and be readable (✓); one that exists
+//! but cannot be read is ⚠, a missing one ✗.  The repository is checked
+//! through its parent directory, which must be writable for `--init-if-missing`
+//! to create it; a remote repository (`sftp:…`, `rclone:…`) is not checked.
+//!
+//! Nothing is left behind: the repository check creates one empty file in
+//! the parent directory and removes it again.  With `--check`, the command
+//! fails if any source is missing, since a backup of it would fail too.
+
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use console::style;
+
+use crate::{
+    cli::Cli,
+    commands::{env_cmd::backend_kind, run::backup_sources},
+    config::Config,
+    runner::repo_location,
+};
+
+/// What a listed path is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathRole {
+    /// The `backup.toml` in use.
+    Config,
+    /// A path the backup reads.
+    Source,
+    /// A `[backup].exclude_files` entry.
+    ExcludeFile,
+    /// A local repository.
+    Repo,
+}
+
+impl PathRole {
+    /// The label shown in the first column.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Source => "source",
+            Self::ExcludeFile => "exclude",
+            Self::Repo => "repo",
+        }
+    }
+}
+
+/// What `--check` found at a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathState {
+    /// Nothing is there.
+    Missing,
+    /// It exists but cannot be read (for the repository: its parent cannot
+    /// be written).
+    Inaccessible,
+    /// It exists and can be used.
+    Usable,
+}
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Entry point for `backup paths`.
+pub fn run(cli: &Cli, cfg: &Config, check: bool) -> Result<()> {
+    let paths = collect_paths(cli, cfg);
+    if !check {
+        print!("{}", render_paths(&paths));
+        return Ok(());
+    }
+
+    let checked: Vec<_> = paths
+        .into_iter()
+        .map(|(role, path)| {
+            let state = probe(role, &path);
+            (role, path, state)
+        })
+        .collect();
+    print!("{}", render_checked(&checked));
+    if has_missing_source(&checked) {
+        anyhow::bail!("a source path does not exist");
+    }
+    Ok(())
+}
+
+// ─── Collection ───────────────────────────────────────────────────────────────
+
+/// Every path the config refers to: the config file, the sources, the
+/// exclude files and, for a local repository, the repository.
+pub fn collect_paths(cli: &Cli, cfg: &Config) -> Vec<(PathRole, PathBuf)> {
+    let mut paths = vec![(PathRole::Config, cli.effective_config_path())];
+    paths.extend(
+        backup_sources(cli, cfg)
+            .into_iter()
+            .map(|s| (PathRole::Source, PathBuf::from(s))),
+    );
+    paths.extend(
+        cfg.backup
+            .exclude_files
+            .iter()
+            .map(|f| (PathRole::ExcludeFile, f.clone())),
+    );
+    if backend_kind(&repo_location(cfg)) == "local" {
+        paths.push((PathRole::Repo, PathBuf::from(&cfg.repo.path)));
+    }
+    paths
+}
+
+// ─── Checks ───────────────────────────────────────────────────────────────────
+
+/// Look at `path` on disk.  The repository is judged by its parent
+/// directory, which must be writable.
+fn probe(role: PathRole, path: &Path) -> PathState {
+    if role == PathRole::Repo {
+        return probe_writable_dir(repo_parent(path));
+    }
+    if !path.exists() {
+        PathState::Missing
+    } else if readable(path) {
+        PathState::Usable
+    } else {
+        PathState::Inaccessible
+    }
+}
+
+/// The directory a repository at `repo` is created in.
+pub fn repo_parent(repo: &Path) -> &Path {
+    repo.parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+}
+
+/// Whether `path` can be opened (a file) or listed (a directory).
+fn readable(path: &Path) -> bool {
+    if path.is_dir() {
+        std::fs::read_dir(path).is_ok()
+    } else {
+        std::fs::File::open(path).is_ok()
+    }
+}
+
+/// Whether a file can be created in `dir`, found by creating and removing
+/// one: permission bits alone miss ACLs and read-only mounts.
+pub fn probe_writable_dir(dir: &Path) -> PathState {
+    if !dir.is_dir() {
+        return PathState::Missing;
+    }
+    let probe = dir.join(format!(".backup-paths-check-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            PathState::Usable
+        },
+        Err(_) => PathState::Inaccessible,
+    }
+}
+
+/// `true` when any source is [`PathState::Missing`].
+pub fn has_missing_source(checked: &[(PathRole, PathBuf, PathState)]) -> bool {
+    checked
+        .iter()
+        .any(|(role, _, state)| *role == PathRole::Source && *state == PathState::Missing)
+}
+
+// ─── Rendering ────────────────────────────────────────────────────────────────
+
+/// An aligned `role  path` list.
+pub fn render_paths(paths: &[(PathRole, PathBuf)]) -> String {
+    let width = label_width(paths.iter().map(|(role, _)| *role));
+    paths.iter().fold(String::new(), |mut out, (role, path)| {
+        let _ = writeln!(
+            out,
+            "  {}  {}",
+            style(format!("{:<width$}", role.label())).bold(),
+            path.display()
+        );
+        out
+    })
+}
+
+/// Like [`render_paths`], each line led by ✓, ⚠ or ✗ and followed by the
+/// problem, if any.
+pub fn render_checked(checked: &[(PathRole, PathBuf, PathState)]) -> String {
+    let width = label_width(checked.iter().map(|(role, ..)| *role));
+    let path_width = checked
+        .iter()
+        .map(|(_, path, _)| path.display().to_string().chars().count())
+        .max()
+        .unwrap_or(0);
+    checked
+        .iter()
+        .fold(String::new(), |mut out, (role, path, state)| {
+            let icon = match state {
+                PathState::Usable => style("✓").green(),
+                PathState::Inaccessible => style("⚠").yellow(),
+                PathState::Missing => style("✗").red(),
+            };
+            let label = style(format!("{:<width$}", role.label())).bold();
+            let shown = path.display().to_string();
+            match problem(*role, path, *state) {
+                None => {
+                    let _ = writeln!(out, "  {icon}  {label}  {shown}");
+                },
+                Some(problem) => {
+                    let _ = writeln!(
+                        out,
+                        "  {icon}  {label}  {shown:<path_width$}  {}",
+                        style(problem).dim()
+                    );
+                },
+            }
+            out
+        })
+}
+
+/// Why a path did not pass, in words; `None` when it did.
+fn problem(role: PathRole, path: &Path, state: PathState) -> Option<String> {
+    let parent = || repo_parent(path).display().to_string();
+    match (role, state) {
+        (_, PathState::Usable) => None,
+        (PathRole::Repo, PathState::Missing) => Some(format!("{} does not exist", parent())),
+        (PathRole::Repo, PathState::Inaccessible) => Some(format!("{} is not writable", parent())),
+        (_, PathState::Missing) => Some("does not exist".into()),
+        (_, PathState::Inaccessible) => Some("not readable".into()),
+    }
+}
+
+/// Width of the widest role label among `roles`.
+fn label_width(roles: impl Iterator<Item = PathRole>) -> usize {
+    roles.map(|role| role.label().len()).max().unwrap_or(0)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn plain(s: &str) -> String {
+        console::strip_ansi_codes(s).into_owned()
+    }
+
+    fn checked(rows: &[(PathRole, &str, PathState)]) -> Vec<(PathRole, PathBuf, PathState)> {
+        rows.iter()
+            .map(|(role, path, state)| (*role, PathBuf::from(path), *state))
+            .collect()
+    }
+
+    #[test]
+    fn paths_cover_config_sources_excludes_and_repo() {
+        let mut cfg = Config::default();
+        cfg.backup.sources = vec!["/srv/app".into(), "/etc/app".into()];
+        cfg.backup.exclude_files = vec![".backupignore".into()];
+        cfg.repo.path = "/mnt/nas/rustic/app".into();
+        let cli = make_cli(&["--config", "/home/alice/backup.toml"]);
+        let roles: Vec<_> = collect_paths(&cli, &cfg)
+            .into_iter()
+            .map(|(role, path)| (role.label(), path.display().to_string()))
+            .collect();
+        assert_eq!(roles, [
+            ("config", "/home/alice/backup.toml".to_string()),
+            ("source", "/srv/app".into()),
+            ("source", "/etc/app".into()),
+            ("exclude", ".backupignore".into()),
+            ("repo", "/mnt/nas/rustic/app".into()),
+        ]);
+    }
+
+    #[test]
+    fn cli_sources_replace_config_sources() {
+        let mut cfg = Config::default();
+        cfg.backup.sources = vec!["/srv/app".into()];
+        let paths = collect_paths(&make_cli(&["--source", "/tmp/x"]), &cfg);
+        let sources: Vec<_> = paths
+            .iter()
+            .filter(|(role, _)| *role == PathRole::Source)
+            .map(|(_, p)| p.clone())
+            .collect();
+        assert_eq!(sources, [PathBuf::from("/tmp/x")]);
+    }
+
+    #[test]
+    fn remote_repo_is_not_listed() {
+        let mut cfg = Config::default();
+        cfg.repo.path = "sftp:alice@nas:/backups".into();
+        let paths = collect_paths(&make_cli(&[]), &cfg);
+        assert!(paths.iter().all(|(role, _)| *role != PathRole::Repo));
+
+        let mut cfg = Config::default();
+        cfg.repo.rest = Some(crate::config::RestConfig {
+            url: "http://nas.lan:8000/".into(),
+            username: None,
+            password: None,
+        });
+        let paths = collect_paths(&make_cli(&[]), &cfg);
+        assert!(paths.iter().all(|(role, _)| *role != PathRole::Repo));
+    }
+
+    #[test]
+    fn checked_table_marks_each_state() {
+        let rows = checked(&[
+            (PathRole::Config, "/a/backup.toml", PathState::Usable),
+            (PathRole::ExcludeFile, "/a/.ignore", PathState::Inaccessible),
+            (PathRole::Source, "/gone", PathState::Missing),
+        ]);
+        assert_eq!(
+            plain(&render_checked(&rows)),
+            "  ✓  config   /a/backup.toml\n\
+             \x20 ⚠  exclude  /a/.ignore      not readable\n\
+             \x20 ✗  source   /gone           does not exist\n"
+        );
+    }
+
+    #[test]
+    fn repo_problems_name_the_parent() {
+        let rows = checked(&[
+            (PathRole::Repo, "/mnt/nas/app", PathState::Missing),
+            (PathRole::Repo, "/srv/app", PathState::Inaccessible),
+        ]);
+        let out = plain(&render_checked(&rows));
+        assert!(out.contains("/mnt/nas does not exist"), "got:\n{out}");
+        assert!(out.contains("/srv is not writable"), "got:\n{out}");
+    }
+
+    #[test]
+    fn only_a_missing_source_fails_the_check() {
+        let unreadable_source = checked(&[(PathRole::Source, "/s", PathState::Inaccessible)]);
+        assert!(!has_missing_source(&unreadable_source));
+        let missing_exclude = checked(&[(PathRole::ExcludeFile, "/e", PathState::Missing)]);
+        assert!(!has_missing_source(&missing_exclude));
+        let missing_source = checked(&[
+            (PathRole::Config, "/c", PathState::Usable),
+            (PathRole::Source, "/s", PathState::Missing),
+        ]);
+        assert!(has_missing_source(&missing_source));
+    }
+
+    #[test]
+    fn probe_sees_existing_and_missing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert_eq!(probe(PathRole::Source, &file), PathState::Usable);
+        assert_eq!(probe(PathRole::Source, dir.path()), PathState::Usable);
+        assert_eq!(
+            probe(PathRole::Source, &dir.path().join("nope")),
+            PathState::Missing
+        );
+    }
+
+    #[test]
+    fn repo_probe_checks_the_parent_and_leaves_no_trace() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        assert_eq!(probe(PathRole::Repo, &repo), PathState::Usable);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert_eq!(
+            probe(PathRole::Repo, &dir.path().join("no/such/repo")),
+            PathState::Missing
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! `backup repair-index` — rebuild the repository index from its pack files.
+//!
+//! Runs `rustic repair index`, which reads every pack header and writes a
+//! fresh index.  It never rewrites snapshots or pack data, so unlike
+//! `rustic repair snapshots` it is safe to run without a second thought —
+//! typically after a backup was killed mid-way and `check` complains about
+//! missing or stale index entries:
+//!
+//! ```text
+//! backup repair-index
+//! ```
+//!
+//! `--dry-run` reports what would change without writing a new index.
+
+use anyhow::Result;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run `rustic repair index` as a single stage.
+pub fn run(cli: &Cli, cfg: &Config) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    println!();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let repair = run_stage(
+        ui,
+        "Repair index",
+        &build_repair_index_args(cli, cfg),
+        timeout,
+    );
+    repair.print(ui);
+    if cli.dry_run {
+        repair.print_stdout();
+    }
+    if repair.failed() {
+        anyhow::bail!("repair-index failed");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic repair index [--dry-run]`.
+pub fn build_repair_index_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["repair".into(), "index".into()]);
+    if cli.dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn make_cfg() -> Config {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/tmp/repo".into();
+        cfg
+    }
+
+    #[test]
+    fn repair_index_args_touch_the_index_only() {
+        let args = build_repair_index_args(&make_cli(&[]), &make_cfg());
+        assert_eq!(&args[args.len() - 2..], ["repair", "index"]);
+        assert!(!args.iter().any(|a| a == "snapshots" || a == "packs"));
+    }
+
+    #[test]
+    fn repair_index_args_pass_dry_run() {
+        let args = build_repair_index_args(&make_cli(&["--dry-run"]), &make_cfg());
+        assert_eq!(args.last().map(String::as_str), Some("--dry-run"));
+    }
+
+    #[test]
+    fn repair_index_args_start_with_rustic_base() {
+        let cli = make_cli(&["--sudo"]);
+        let cfg = make_cfg();
+        assert!(build_repair_index_args(&cli, &cfg).starts_with(&rustic_base(&cli, &cfg)));
+    }
+}
@@ -2,14 +2,30 @@
 //!
 //! # Pipeline stages (in order)
 //!
-//! | # | Stage    | Flag to skip   | Description                              |
-//! |---|----------|----------------|------------------------------------------|
-//! | 1 | Mount    | `--no-mount`   | Mount the NAS share                      |
-//! | 2 | Init     | —              | Create repo on first run                 |
-//! | 3 | Check    | `--no-check`   | Verify repository integrity              |
-//! | 4 | Backup   | —              | Snapshot sources → repo                  |
-//! | 5 | Forget   | `--no-prune`   | Apply retention policy, prune dead packs |
-//! | 6 | Compact  | `--no-prune`   | Final `rustic prune` for disk reclaim    |
+//! | # | Stage        | Flag to skip   | Description                              |
+//! |---|--------------|----------------|------------------------------------------|
+//! | 1 | Mount        | `--no-mount`   | Mount the NAS share                      |
+//! | 2 | Init         | —              | Create repo (`--init-if-missing` only)   |
+//! | 3 | Check        | `--no-check`   | Verify repository integrity              |
+//! | 4 | Backup       | —              | Snapshot sources → repo                  |
+//! | 5 | Verify       | `--no-verify`  | Confirm a snapshot was recorded          |
+//! | 6 | Forget       | `--no-prune`   | Apply retention policy, prune dead packs |
+//! | 7 | Compact      | `--no-prune`   | Final `rustic prune` for disk reclaim    |
+//! | 8 | Cold archive | —              | Copy latest snapshot to `[cold_repo]`    |
+//!
+//! Each stage also has a [`StageMeta`] constant (`STAGE_MOUNT`, …) whose `id`
+//! is recorded in its [`StageOutcome`], so output can be matched on a stable
+//! ID rather than the label.
+//!
+//! ## Pre-flight checks
+//!
+//! Before the Mount stage, [`pre_flight_checks`] confirms that `rustic` is on
+//! `PATH`, that every source exists, that a repository `--init-if-missing`
+//! is about to create has a writable parent, and that `[mount].share` names
+//! a known share.  Paths below the share's mountpoint are left to the
+//! stages, since they only appear once it is mounted.  Failed checks are
+//! printed (all of them with `--verbose`) and abort the run before anything
+//! is mounted or written.
 //!
 //! Each stage runs behind a spinner.  Raw rustic output is captured and hidden
 //! unless the stage fails, in which case stdout + stderr are replayed so the
@@ -18,17 +34,128 @@
 //! ## Sources default
 //!
 //! If `[backup].sources` is empty the current directory (`"."`) is used.
+//!
+//! ## Missing repository
+//!
+//! A repository that does not exist is only created when `--init-if-missing`
+//! is passed.  Otherwise the Init stage fails with a hint, so pointing
+//! `[repo].path` at the wrong place never silently starts a fresh repository.
+//!
+//! ## Dry run
+//!
+//! With `--dry-run`, Backup, Forget and Compact are passed rustic's own
+//! `--dry-run`, and their reports (what would be saved, which snapshots would
+//! be forgotten, how much space would be reclaimed) are printed.  Nothing in
+//! the repository is modified, and a missing repository is reported rather
+//! than created.
+//!
+//! ## Verify
+//!
+//! rustic can exit zero without having written a snapshot (e.g. a wrapper
+//! script swallowing an error).  Verify lists the newest snapshot and fails
+//! the pipeline unless it was taken after the run started.  It is skipped
+//! with `--no-verify` and in dry runs.
+//!
+//! ## Retention safety
+//!
+//! If no `[retention]` option keeps anything — every window is zero and no
+//! tag or `keep_*within*` duration is set — Forget and Compact are skipped
+//! with a warning rather than asking rustic to keep nothing.
+//!
+//! ## Cold archive
+//!
+//! Runs only when `[repo].cold_storage` is on, and only on the first day of
+//! the month (local time), so the archive receives one snapshot a month.
+//! rustic reads the archive's location and password from a temporary
+//! [`CopyProfile`], which is removed once the stage ends.
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 
 use crate::{
-    cli::Cli,
-    config::Config,
+    cli::{CheckMode, Cli},
+    commands::{
+        env_cmd::{backend_kind, find_rustic},
+        import::collect_snapshots,
+        paths,
+    },
+    config::{Config, EffectiveConfig, MountConfig},
+    copy_profile::CopyProfile,
     mount,
-    runner::{prefix, rustic_base},
-    ui::{StageOutcome, print_summary, run_stage, skipped_stage},
+    runner::{prefix, repo_location, rustic_base},
+    ui::{
+        StageMeta, StageOutcome, UiContext, print_stage_tree, print_summary, print_warning,
+        run_stage_meta, skipped_stage,
+    },
+};
+
+// ─── Stages ───────────────────────────────────────────────────────────────────
+
+/// Mount the NAS share.
+pub const STAGE_MOUNT: StageMeta = StageMeta {
+    id: "mount",
+    description: "Mount",
+    skippable: true,
+};
+
+/// Create the repository directory (`--init-if-missing`).
+pub const STAGE_INIT_DIR: StageMeta = StageMeta {
+    id: "init-dir",
+    description: "Init (mkdir)",
+    skippable: true,
+};
+
+/// Initialise the repository (`--init-if-missing`).
+pub const STAGE_INIT: StageMeta = StageMeta {
+    id: "init",
+    description: "Init (repo)",
+    skippable: true,
+};
+
+/// Verify repository integrity.
+pub const STAGE_CHECK: StageMeta = StageMeta {
+    id: "check",
+    description: "Check",
+    skippable: true,
+};
+
+/// Snapshot the sources.
+pub const STAGE_BACKUP: StageMeta = StageMeta {
+    id: "backup",
+    description: "Backup",
+    skippable: false,
+};
+
+/// Confirm Backup recorded a snapshot.
+pub const STAGE_VERIFY: StageMeta = StageMeta {
+    id: "verify",
+    description: "Verify",
+    skippable: true,
+};
+
+/// Apply the retention policy.
+pub const STAGE_FORGET: StageMeta = StageMeta {
+    id: "forget",
+    description: "Forget",
+    skippable: true,
+};
+
+/// Reclaim space with `rustic prune`.
+pub const STAGE_COMPACT: StageMeta = StageMeta {
+    id: "compact",
+    description: "Compact",
+    skippable: true,
+};
+
+/// Copy the latest snapshot to `[cold_repo]`.
+pub const STAGE_COLD_ARCHIVE: StageMeta = StageMeta {
+    id: "cold-archive",
+    description: "Cold archive",
+    skippable: true,
 };
 
 // ─── Entry point ──────────────────────────────────────────────────────────────
@@ -37,95 +164,443 @@ use crate::{
 ///
 /// Stages are run sequentially.  Every stage always runs (so the summary shows
 /// every result), but if any stage fails the function returns an error after
-/// printing the summary.
+/// printing the summary.  Which stages run is decided by
+/// [`Config::merge_cli_overrides`], not by reading CLI flags here.
 pub fn run(cli: &Cli, cfg: &Config) -> Result<()> {
     println!();
+    let started_at = jiff::Timestamp::now();
+
+    let ui = UiContext::from_cli(cli);
+    let eff = cfg.merge_cli_overrides(cli);
+    let timeout = eff.stage_timeout;
+    if cli.verbose {
+        print_stage_tree(&planned_stages(cfg, &eff, jiff::Zoned::now().date()));
+    }
+
+    let checks = pre_flight_checks(cli, cfg);
+    for check in checks.iter().filter(|c| cli.verbose || c.failed()) {
+        check.print(ui);
+    }
+    if checks.iter().any(StageOutcome::failed) {
+        anyhow::bail!("pipeline aborted: pre-flight checks failed");
+    }
 
     let mut outcomes: Vec<StageOutcome> = Vec::new();
 
     // 1. Mount
-    let mount = if !cli.no_mount && cfg.mount.share.is_some() {
-        mount::mount_share(&cfg.mount)
+    let mount = if eff.mount {
+        mount::mount_share(&cfg.mount, eff.dry_run, eff.mounted)
     } else {
-        skipped_stage("Mount")
-    };
-    mount.print();
+        skipped_stage(STAGE_MOUNT.description)
+    }
+    .with_meta(&STAGE_MOUNT);
+    mount.print(ui);
+    if eff.dry_run {
+        mount.print_stdout();
+    }
     let mount_failed = mount.failed();
     outcomes.push(mount);
 
     // Abort early on mount failure — nothing else can proceed.
     if mount_failed {
-        print_summary(&outcomes);
+        print_summary(ui, &outcomes);
         anyhow::bail!("pipeline aborted: mount failed");
     }
 
-    // 2. Init (only when repo does not yet exist)
-    if !Path::new(&cfg.repo.path).exists() {
-        // mkdir -p
-        let mkdir = run_stage("Init (mkdir)", &build_mkdir_args(cli, cfg));
-        mkdir.print();
-        let failed = mkdir.failed();
-        outcomes.push(mkdir);
-        if failed {
-            print_summary(&outcomes);
-            anyhow::bail!("pipeline aborted: could not create repo directory");
+    // 2. Init (only when repo does not yet exist, and only when asked)
+    if repo_missing(cfg) {
+        if !eff.init_if_missing {
+            let init = repo_not_found(cfg);
+            init.print(ui);
+            outcomes.push(init);
+            print_summary(ui, &outcomes);
+            anyhow::bail!("pipeline aborted: repo not found");
         }
-
-        // rustic init
-        let init = run_stage("Init (repo)", &build_init_args(cli, cfg));
-        init.print();
-        let failed = init.failed();
-        outcomes.push(init);
-        if failed {
-            print_summary(&outcomes);
-            anyhow::bail!("pipeline aborted: rustic init failed");
+        if eff.dry_run {
+            // Creating the repo is a change; there is nothing else to preview.
+            print_warning(&format!(
+                "repository '{}' does not exist — a real run would create it",
+                cfg.repo.path
+            ));
+            print_summary(ui, &outcomes);
+            return Ok(());
         }
+        init_repo(ui, cli, cfg, timeout, &mut outcomes)?;
     }
 
     // 3. Check
-    if !cli.no_check {
-        let check = run_stage("Check", &build_check_args(cli, cfg));
-        check.print();
+    if eff.check {
+        let check = run_stage_meta(ui, &STAGE_CHECK, &build_check_args(cli, cfg, None), timeout);
+        check.print(ui);
         let failed = check.failed();
         outcomes.push(check);
         if failed {
-            print_summary(&outcomes);
+            print_summary(ui, &outcomes);
             anyhow::bail!("pipeline aborted: check failed");
         }
+    } else {
+        tracing::debug!("check skipped (--no-check)");
     }
 
     // 4. Backup
-    let backup = run_stage("Backup", &build_backup_args(cli, cfg));
-    backup.print();
+    let backup = run_stage_meta(
+        ui,
+        &STAGE_BACKUP,
+        &build_backup_args(cli, cfg, eff.dry_run),
+        timeout,
+    );
+    backup.print(ui);
+    if eff.dry_run {
+        backup.print_stdout();
+    }
     let backup_failed = backup.failed();
     outcomes.push(backup);
     if backup_failed {
-        print_summary(&outcomes);
+        print_summary(ui, &outcomes);
         anyhow::bail!("pipeline aborted: backup failed");
     }
 
-    // 5 & 6. Forget + Compact
-    if !cli.no_prune {
-        let forget = run_stage("Forget", &build_forget_args(cli, cfg));
-        forget.print();
-        let failed = forget.failed();
-        outcomes.push(forget);
-        if failed {
-            print_summary(&outcomes);
-            anyhow::bail!("pipeline aborted: forget failed");
-        }
+    // 5. Verify
+    if eff.verify {
+        verify(ui, cli, cfg, started_at, timeout, &mut outcomes)?;
+    } else {
+        tracing::debug!("verify skipped (--no-verify or --dry-run)");
+    }
 
-        let compact = run_stage("Compact", &build_compact_args(cli, cfg));
-        compact.print();
-        let failed = compact.failed();
-        outcomes.push(compact);
-        if failed {
-            print_summary(&outcomes);
-            anyhow::bail!("pipeline aborted: compact failed");
-        }
+    // 6 & 7. Forget + Compact
+    if !eff.prune {
+        tracing::debug!("forget and compact skipped (--no-prune)");
+    } else if cfg.retention.is_effectively_disabled() {
+        // Keeping zero snapshots of every kind would delete the whole repo.
+        print_warning("no retention option keeps anything — skipping Forget and Compact");
+    } else {
+        forget_and_compact(ui, cli, cfg, eff.dry_run, timeout, &mut outcomes)?;
+    }
+
+    // 8. Cold archive
+    if cold_archive_due(cfg, jiff::Zoned::now().date()) {
+        cold_archive(ui, cli, cfg, eff.dry_run, timeout, &mut outcomes)?;
+    }
+
+    print_summary(ui, &outcomes);
+    Ok(())
+}
+
+/// Every pipeline stage paired with whether this run will skip it, in
+/// execution order.  Shown by `--verbose` before anything runs.
+///
+/// `today` decides whether the monthly Cold archive stage is due.
+pub fn planned_stages(
+    cfg: &Config,
+    eff: &EffectiveConfig,
+    today: jiff::civil::Date,
+) -> Vec<(&'static str, bool)> {
+    let init = eff.init_if_missing && repo_missing(cfg);
+    let prune = eff.prune && !cfg.retention.is_effectively_disabled();
+    vec![
+        ("Mount", !eff.mount),
+        ("Init", !init),
+        ("Check", !eff.check),
+        ("Backup", false),
+        ("Verify", !eff.verify),
+        ("Forget", !prune),
+        ("Compact", !prune),
+        ("Cold archive", !cold_archive_due(cfg, today)),
+    ]
+}
+
+// ─── Pre-flight checks ────────────────────────────────────────────────────────
+
+/// Everything that can be checked before the first stage runs, one outcome
+/// per check, labelled with what was checked.
+///
+/// The repository's parent is only checked when the run would create the
+/// repository, and paths below the mountpoint only when nothing is mounted
+/// first.
+pub fn pre_flight_checks(cli: &Cli, cfg: &Config) -> Vec<StageOutcome> {
+    let eff = cfg.merge_cli_overrides(cli);
+    let pending_mount = if eff.mount {
+        mount::configured_mountpoint(&cfg.mount)
+    } else {
+        None
+    };
+    let checkable = |path: &Path| pending_mount.as_ref().is_none_or(|m| !path.starts_with(m));
+
+    let mut checks = vec![check_rustic(find_rustic())];
+    checks.extend(
+        backup_sources(cli, cfg)
+            .iter()
+            .map(Path::new)
+            .filter(|path| checkable(path))
+            .map(check_source),
+    );
+    let repo = Path::new(&cfg.repo.path);
+    if repo_missing(cfg) && eff.init_if_missing && !eff.dry_run && checkable(repo) {
+        checks.push(check_repo_parent(repo));
+    }
+    if eff.mount {
+        checks.push(check_mount_share(&cfg.mount));
+    }
+    checks
+}
+
+/// `rustic` was found on `PATH` (`found` is where).
+pub fn check_rustic(found: Option<PathBuf>) -> StageOutcome {
+    found.map_or_else(
+        || {
+            check_outcome(
+                "rustic found on PATH".into(),
+                Some("rustic is not on PATH — install it or add its directory to PATH".into()),
+            )
+        },
+        |path| check_outcome(format!("rustic found at {}", path.display()), None),
+    )
+}
+
+/// The source `path` exists.
+pub fn check_source(path: &Path) -> StageOutcome {
+    let problem = (!path.exists()).then(|| format!("{} does not exist", path.display()));
+    check_outcome(format!("Source {}", path.display()), problem)
+}
+
+/// The directory a new repository at `repo` would be created in is writable.
+pub fn check_repo_parent(repo: &Path) -> StageOutcome {
+    let parent = paths::repo_parent(repo);
+    let problem = match paths::probe_writable_dir(parent) {
+        paths::PathState::Usable => None,
+        paths::PathState::Missing => Some(format!("{} does not exist", parent.display())),
+        paths::PathState::Inaccessible => Some(format!("{} is not writable", parent.display())),
+    };
+    check_outcome(format!("Repository parent {}", parent.display()), problem)
+}
+
+/// `[mount].share` can be turned into a mount command: it is set and, for
+/// NFS, names a built-in or `[mount.shares]` share.
+pub fn check_mount_share(cfg: &MountConfig) -> StageOutcome {
+    let label = format!("Mount share '{}'", cfg.share.as_deref().unwrap_or_default());
+    check_outcome(
+        label,
+        mount::build_mount_command(cfg).err().map(|e| e.to_string()),
+    )
+}
+
+/// A check's outcome: passed when there is no `problem`.
+const fn check_outcome(label: String, problem: Option<String>) -> StageOutcome {
+    StageOutcome {
+        label,
+        stage_id: None,
+        success: problem.is_none(),
+        stdout: String::new(),
+        stderr: String::new(),
+        error: problem,
+        error_kind: None,
+    }
+}
+
+/// `true` when the local repository directory does not exist yet.  A
+/// remote repository — `[repo.rest]`, or a path such as `sftp:…` or
+/// `rclone:…` — lives elsewhere and is never missing here.
+fn repo_missing(cfg: &Config) -> bool {
+    backend_kind(&repo_location(cfg)) == "local" && !Path::new(&cfg.repo.path).exists()
+}
+
+/// `true` on the day the monthly Cold archive stage runs: the first.
+pub fn is_cold_archive_day(date: jiff::civil::Date) -> bool {
+    date.day() == 1
+}
+
+/// Whether this run should copy to `[cold_repo]` on `today`.
+fn cold_archive_due(cfg: &Config, today: jiff::civil::Date) -> bool {
+    cfg.repo.cold_storage && is_cold_archive_day(today)
+}
+
+/// Run the Forget and Compact stages, pushing each outcome and printing the
+/// summary before bailing if either fails.
+fn forget_and_compact(
+    ui: UiContext,
+    cli: &Cli,
+    cfg: &Config,
+    dry_run: bool,
+    timeout: Option<Duration>,
+    outcomes: &mut Vec<StageOutcome>,
+) -> Result<()> {
+    let forget = run_stage_meta(
+        ui,
+        &STAGE_FORGET,
+        &build_forget_args(cli, cfg, dry_run),
+        timeout,
+    );
+    forget.print(ui);
+    if dry_run {
+        forget.print_stdout();
+    }
+    let failed = forget.failed();
+    outcomes.push(forget);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: forget failed");
+    }
+
+    let compact = run_stage_meta(
+        ui,
+        &STAGE_COMPACT,
+        &build_compact_args(cli, cfg, dry_run),
+        timeout,
+    );
+    compact.print(ui);
+    if dry_run {
+        compact.print_stdout();
+    }
+    let failed = compact.failed();
+    outcomes.push(compact);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: compact failed");
+    }
+    Ok(())
+}
+
+/// Run the Cold archive stage, pushing its outcome and printing the summary
+/// before bailing if it fails.
+fn cold_archive(
+    ui: UiContext,
+    cli: &Cli,
+    cfg: &Config,
+    dry_run: bool,
+    timeout: Option<Duration>,
+    outcomes: &mut Vec<StageOutcome>,
+) -> Result<()> {
+    let target = cfg.cold_repo.path.as_deref().unwrap_or_default();
+    let password = cfg
+        .cold_repo
+        .password
+        .as_deref()
+        .unwrap_or(&cfg.repo.password);
+    // The profile must outlive the stage: rustic reads it while copying.
+    let copy = match CopyProfile::create(target, password) {
+        Ok(profile) => run_stage_meta(
+            ui,
+            &STAGE_COLD_ARCHIVE,
+            &build_cold_copy_args(cli, cfg, profile.name(), dry_run),
+            timeout,
+        ),
+        Err(e) => check_outcome(
+            STAGE_COLD_ARCHIVE.description.into(),
+            Some(format!("{e:#}")),
+        )
+        .with_meta(&STAGE_COLD_ARCHIVE),
+    };
+    copy.print(ui);
+    if dry_run {
+        copy.print_stdout();
+    }
+    let failed = copy.failed();
+    outcomes.push(copy);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: cold archive copy failed");
+    }
+    Ok(())
+}
+
+/// Run the Verify stage — the newest snapshot must exist and be no older
+/// than `started_at` — pushing its outcome and printing the summary before
+/// bailing if it fails.
+fn verify(
+    ui: UiContext,
+    cli: &Cli,
+    cfg: &Config,
+    started_at: jiff::Timestamp,
+    timeout: Option<Duration>,
+    outcomes: &mut Vec<StageOutcome>,
+) -> Result<()> {
+    let mut verify = run_stage_meta(ui, &STAGE_VERIFY, &build_verify_args(cli, cfg), timeout);
+    if verify.success
+        && let Err(e) = check_new_snapshot(&verify.stdout, started_at)
+    {
+        verify.success = false;
+        verify.error = Some(format!("{e:#}"));
     }
+    verify.print(ui);
+    let failed = verify.failed();
+    outcomes.push(verify);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: no snapshot was recorded");
+    }
+    Ok(())
+}
 
-    print_summary(&outcomes);
+/// Check that `rustic snapshots latest --json` output holds a snapshot taken
+/// at or after `started_at`, compared to the whole second since rustic may
+/// drop sub-second precision.
+pub fn check_new_snapshot(json: &str, started_at: jiff::Timestamp) -> Result<()> {
+    let value: serde_json::Value = if json.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(json).context("parsing `rustic snapshots --json` output")?
+    };
+    let mut snapshots = Vec::new();
+    collect_snapshots(&value, &mut snapshots);
+    let newest = snapshots
+        .iter()
+        .filter_map(|s| s.get("time")?.as_str()?.parse::<jiff::Timestamp>().ok())
+        .max()
+        .context("backup reported success but the repository has no snapshots")?;
+    let started_at = jiff::Timestamp::from_second(started_at.as_second())?;
+    if newest < started_at {
+        anyhow::bail!(
+            "backup reported success but the newest snapshot ({newest}) predates this run"
+        );
+    }
+    Ok(())
+}
+
+/// Failed Init outcome for a missing repo when `--init-if-missing` is unset.
+fn repo_not_found(cfg: &Config) -> StageOutcome {
+    StageOutcome {
+        label: "Init".into(),
+        stage_id: Some(STAGE_INIT.id),
+        success: false,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: Some(format!(
+            "repo not found at '{}', run with --init-if-missing to create it",
+            cfg.repo.path
+        )),
+        error_kind: None,
+    }
+}
+
+/// Run the two Init stages (`mkdir -p`, then `rustic init`), pushing each
+/// outcome and printing the summary before bailing if either fails.
+fn init_repo(
+    ui: UiContext,
+    cli: &Cli,
+    cfg: &Config,
+    timeout: Option<Duration>,
+    outcomes: &mut Vec<StageOutcome>,
+) -> Result<()> {
+    tracing::info!(repo = %cfg.repo.path, "repository not found; initialising");
+    // mkdir -p
+    let mkdir = run_stage_meta(ui, &STAGE_INIT_DIR, &build_mkdir_args(cli, cfg), timeout);
+    mkdir.print(ui);
+    let failed = mkdir.failed();
+    outcomes.push(mkdir);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: could not create repo directory");
+    }
+
+    // rustic init
+    let init = run_stage_meta(ui, &STAGE_INIT, &build_init_args(cli, cfg), timeout);
+    init.print(ui);
+    let failed = init.failed();
+    outcomes.push(init);
+    if failed {
+        print_summary(ui, outcomes);
+        anyhow::bail!("pipeline aborted: rustic init failed");
+    }
     Ok(())
 }
 
@@ -134,6 +609,10 @@ pub fn run(cli: &Cli, cfg: &Config) -> Result<()> {
 // Each function returns the full `Vec<String>` that will be passed to
 // `run_stage`.  They are `pub` so that unit tests (and the snapshot tests
 // below) can call them directly without needing `rustic` installed.
+//
+// The builders for mutating stages take `dry_run` as an argument — the
+// pipeline passes `EffectiveConfig::dry_run` — instead of reading
+// `cli.dry_run`, so the flag has a single source of truth.
 
 /// Arguments for `mkdir -p <repo>`.
 pub fn build_mkdir_args(cli: &Cli, cfg: &Config) -> Vec<String> {
@@ -143,64 +622,147 @@ pub fn build_mkdir_args(cli: &Cli, cfg: &Config) -> Vec<String> {
 }
 
 /// Arguments for `rustic init`.
+///
+/// Adds `--set-packsize <N>m` and `--set-treepack-size <N>m` when
+/// `[repo].pack_size_mb` / `[repo].tree_pack_size_mb` are set.
 pub fn build_init_args(cli: &Cli, cfg: &Config) -> Vec<String> {
     let mut cmd = rustic_base(cli, cfg);
     cmd.push("init".into());
+    if let Some(mb) = cfg.repo.pack_size_mb {
+        cmd.extend(["--set-packsize".into(), format!("{mb}m")]);
+    }
+    if let Some(mb) = cfg.repo.tree_pack_size_mb {
+        cmd.extend(["--set-treepack-size".into(), format!("{mb}m")]);
+    }
     cmd
 }
 
 /// Arguments for `rustic check`.
-pub fn build_check_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+///
+/// The pipeline passes no `mode`.  `backup check data` adds `--read-data`
+/// and `backup check snapshots` adds `--check-unused`; `index` is the plain
+/// check.
+pub fn build_check_args(cli: &Cli, cfg: &Config, mode: Option<CheckMode>) -> Vec<String> {
     let mut cmd = rustic_base(cli, cfg);
     cmd.push("check".into());
+    match mode {
+        None | Some(CheckMode::Index) => {},
+        Some(CheckMode::Data) => cmd.push("--read-data".into()),
+        Some(CheckMode::Snapshots) => cmd.push("--check-unused".into()),
+    }
     cmd
 }
 
 /// Arguments for `rustic backup …`.
 ///
-/// Falls back to `"."` when `[backup].sources` is empty.
-pub fn build_backup_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+/// Sources come from `--source` when given, otherwise from `[backup].sources`,
+/// falling back to `"."` when both are empty.  Adds `--exclude-caches` after
+/// `--exclude-if-present` when `[backup].exclude_caches` or `--exclude-caches`
+/// is set, and
+/// `--exclude-larger-than <N>M` when `[backup].max_file_size_mb` is set,
+/// `--ignore-inaccessible` when `[backup].ignore_inaccessible` is on,
+/// `--no-scan` when `[backup].no_scan` or `--no-scan` is set, and
+/// one `--exclude-file <path>` per `[backup].exclude_files` entry.
+/// `--compress-level` replaces `[backup].compression` for this run.
+/// Each `[backup].sources_exclude` path becomes `--glob=!<path>` ahead of
+/// the user's `globs`, each `--exclude` flag becomes `--glob=!<glob>` after
+/// them, and every `--glob` precedes every `--iglob`.  `--label` is passed through so
+/// the snapshot carries the same label as the run log entry.
+pub fn build_backup_args(cli: &Cli, cfg: &Config, dry_run: bool) -> Vec<String> {
+    let mut backup = cfg.backup.clone();
+    if let Some(level) = cli.compress_level {
+        backup.compression = level;
+    }
+    backup.exclude_caches |= cli.exclude_caches;
+    backup.no_scan |= cli.no_scan;
+    backup
+        .globs
+        .extend(cli.excludes.iter().map(|glob| format!("!{glob}")));
+
     let mut cmd = rustic_base(cli, cfg);
     cmd.push("backup".into());
-    cmd.extend([
-        "--set-compression".into(),
-        cfg.backup.compression.to_string(),
-        "--exclude-if-present".into(),
-        cfg.backup.exclude_if_present.clone(),
-    ]);
-    for glob in &cfg.backup.globs {
-        cmd.push(format!("--glob={glob}"));
+    cmd.extend(backup.as_rustic_backup_flags());
+    if let Some(ref label) = cli.label {
+        cmd.extend(["--label".into(), label.clone()]);
     }
-    let sources: Vec<String> = if cfg.backup.sources.is_empty() {
+    if dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd.extend(backup_sources(cli, cfg));
+    cmd
+}
+
+/// The paths a backup reads: `--source` when given, else
+/// `[backup].sources`, else the working directory.
+pub fn backup_sources(cli: &Cli, cfg: &Config) -> Vec<String> {
+    if !cli.sources.is_empty() {
+        cli.sources.clone()
+    } else if cfg.backup.sources.is_empty() {
         vec![".".into()]
     } else {
         cfg.backup.sources.clone()
-    };
-    cmd.extend(sources);
-    cmd
+    }
 }
 
 /// Arguments for `rustic forget --prune …`.
-pub fn build_forget_args(cli: &Cli, cfg: &Config) -> Vec<String> {
-    let r = &cfg.retention;
+///
+/// The retention flags come from
+/// [`RetentionConfig::as_rustic_flags`](crate::config::RetentionConfig::as_rustic_flags):
+/// the non-zero `--keep-*` counts, then `--keep-all-within` and the
+/// `--keep-within-*` windows, then one `--keep-tag` per tag.
+///
+/// With `dry_run`, rustic lists the snapshots it would remove instead of
+/// removing them.
+pub fn build_forget_args(cli: &Cli, cfg: &Config, dry_run: bool) -> Vec<String> {
     let mut cmd = rustic_base(cli, cfg);
-    cmd.extend([
-        "forget".into(),
-        "--prune".into(),
-        "--keep-daily".into(),
-        r.daily.to_string(),
-        "--keep-weekly".into(),
-        r.weekly.to_string(),
-        "--keep-monthly".into(),
-        r.monthly.to_string(),
-    ]);
+    cmd.extend(["forget".into(), "--prune".into()]);
+    cmd.extend(cfg.retention.as_rustic_flags());
+    if dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd
+}
+
+/// Arguments for `rustic snapshots latest --json`.
+pub fn build_verify_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend(["snapshots".into(), "latest".into(), "--json".into()]);
     cmd
 }
 
 /// Arguments for `rustic prune`.
-pub fn build_compact_args(cli: &Cli, cfg: &Config) -> Vec<String> {
+///
+/// `[retention].max_repack_size_mb` adds `--max-repack <n>M`, capping how
+/// much data one run repacks.
+pub fn build_compact_args(cli: &Cli, cfg: &Config, dry_run: bool) -> Vec<String> {
     let mut cmd = rustic_base(cli, cfg);
     cmd.push("prune".into());
+    if let Some(mb) = cfg.retention.max_repack_size_mb {
+        cmd.extend(["--max-repack".into(), format!("{mb}M")]);
+    }
+    if dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd
+}
+
+/// Arguments for `rustic -P <profile> copy latest --init`.
+///
+/// `profile` is a [`CopyProfile`] naming `[cold_repo]` as the copy target;
+/// `--init` lets the first copy create the archive repository.  `dry_run`
+/// adds rustic's `--dry-run`, as for the other mutating stages.
+pub fn build_cold_copy_args(cli: &Cli, cfg: &Config, profile: &str, dry_run: bool) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend([
+        "-P".into(),
+        profile.into(),
+        "copy".into(),
+        "latest".into(),
+        "--init".into(),
+    ]);
+    if dry_run {
+        cmd.push("--dry-run".into());
+    }
     cmd
 }
 
@@ -211,7 +773,10 @@ mod tests {
     use clap::Parser;
 
     use super::*;
-    use crate::config::{BackupConfig, MountConfig, RepoConfig, RetentionConfig};
+    use crate::config::{
+        BackupConfig, ColdRepoConfig, MirrorConfig, MountConfig, MountMethod, RepoConfig,
+        RetentionConfig,
+    };
 
     fn make_cli(extra: &[&str]) -> Cli {
         Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
@@ -219,12 +784,20 @@ mod tests {
 
     fn make_cfg() -> Config {
         Config {
+            stage_timeout_secs: None,
             repo: RepoConfig {
                 path: "/tmp/repo".into(),
                 password: "pw".into(),
+                password_env: None,
+                pack_size_mb: None,
+                tree_pack_size_mb: None,
+                extra_args: vec![],
+                cold_storage: false,
+                rest: None,
             },
             backup: BackupConfig {
                 sources: vec!["/home/alice/project".into()],
+                sources_exclude: vec![],
                 compression: 3,
                 globs: vec![
                     "!**/.git".into(),
@@ -232,17 +805,39 @@ mod tests {
                     "!**/target/".into(),
                     "!**/node_modules/".into(),
                 ],
+                iglobs: vec![],
+                exclude_files: vec![],
                 exclude_if_present: "ignore".into(),
+                exclude_caches: false,
+                max_file_size_mb: None,
+                ignore_inaccessible: false,
+                no_scan: false,
+                custom_metadata: std::collections::BTreeMap::new(),
             },
             retention: RetentionConfig {
                 daily: 2,
                 weekly: 1,
                 monthly: 1,
+                keep_last: 0,
+                keep_tags: vec![],
+                keep_all_within: None,
+                keep_within_daily: None,
+                keep_within_weekly: None,
+                keep_within_monthly: None,
+                group_by: None,
+                max_repack_size_mb: None,
             },
             mount: MountConfig {
                 share: Some("new-backups".into()),
                 user: None,
+                mountpoint: None,
+                method: MountMethod::Nfs,
+                timeout_secs: None,
+                shares: std::collections::BTreeMap::new(),
+                force_recheck: false,
             },
+            cold_repo: ColdRepoConfig::default(),
+            mirror: MirrorConfig::default(),
         }
     }
 
@@ -250,14 +845,35 @@ mod tests {
 
     #[test]
     fn backup_args_contain_compression() {
-        let args = build_backup_args(&make_cli(&[]), &make_cfg());
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
         let idx = args.iter().position(|a| a == "--set-compression").unwrap();
         assert_eq!(args[idx + 1], "3");
     }
 
+    #[test]
+    fn compress_level_flag_overrides_config_compression() {
+        let args = build_backup_args(&make_cli(&["--compress-level", "19"]), &make_cfg(), false);
+        let idx = args.iter().position(|a| a == "--set-compression").unwrap();
+        assert_eq!(args[idx + 1], "19");
+        assert_eq!(args.iter().filter(|a| *a == "--set-compression").count(), 1);
+    }
+
+    #[test]
+    fn compress_level_flag_accepts_only_zstd_levels() {
+        for level in ["1", "22"] {
+            assert!(Cli::try_parse_from(["backup", "--compress-level", level]).is_ok());
+        }
+        for level in ["0", "23", "fast"] {
+            assert!(
+                Cli::try_parse_from(["backup", "--compress-level", level]).is_err(),
+                "{level} should be rejected"
+            );
+        }
+    }
+
     #[test]
     fn backup_args_contain_exclude_marker() {
-        let args = build_backup_args(&make_cli(&[]), &make_cfg());
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
         let idx = args
             .iter()
             .position(|a| a == "--exclude-if-present")
@@ -267,28 +883,205 @@ mod tests {
 
     #[test]
     fn backup_args_globs_in_order() {
-        let args = build_backup_args(&make_cli(&[]), &make_cfg());
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
         let globs: Vec<_> = args.iter().filter(|a| a.starts_with("--glob=")).collect();
         assert_eq!(globs[0], "--glob=!**/.git");
         assert_eq!(globs[1], "--glob=!tmp/");
     }
 
+    #[test]
+    fn backup_args_sources_exclude_precede_globs() {
+        let mut cfg = make_cfg();
+        cfg.backup.sources_exclude = vec![
+            "/home/alice/project/Downloads".into(),
+            "/home/alice/project/scratch".into(),
+        ];
+        let args = build_backup_args(&make_cli(&[]), &cfg, false);
+        let globs: Vec<_> = args.iter().filter(|a| a.starts_with("--glob=")).collect();
+        assert_eq!(globs[0], "--glob=!/home/alice/project/Downloads");
+        assert_eq!(globs[1], "--glob=!/home/alice/project/scratch");
+        assert_eq!(globs[2], "--glob=!**/.git");
+        assert_eq!(globs.len(), 2 + cfg.backup.globs.len());
+    }
+
+    #[test]
+    fn backup_args_without_sources_exclude_start_with_user_globs() {
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
+        let first = args.iter().find(|a| a.starts_with("--glob=")).unwrap();
+        assert_eq!(first, "--glob=!**/.git");
+    }
+
+    #[test]
+    fn backup_args_exclude_file_per_entry() {
+        let mut cfg = make_cfg();
+        cfg.backup.exclude_files = vec!["a.ignore".into(), "b.ignore".into()];
+        let args = build_backup_args(&make_cli(&[]), &cfg, false);
+        let files: Vec<_> = args
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| *a == "--exclude-file")
+            .map(|(i, _)| args[i + 1].as_str())
+            .collect();
+        assert_eq!(files, ["a.ignore", "b.ignore"]);
+    }
+
+    #[test]
+    fn backup_args_iglobs_follow_globs() {
+        let mut cfg = make_cfg();
+        cfg.backup.iglobs = vec!["!**/*.TMP".into()];
+        let args = build_backup_args(&make_cli(&[]), &cfg, false);
+        let last_glob = args.iter().rposition(|a| a.starts_with("--glob=")).unwrap();
+        let iglob = args.iter().position(|a| a == "--iglob=!**/*.TMP").unwrap();
+        assert!(iglob > last_glob);
+    }
+
+    #[test]
+    fn backup_args_omit_exclude_caches_by_default() {
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
+        assert!(!args.iter().any(|a| a == "--exclude-caches"));
+    }
+
+    #[test]
+    fn backup_args_exclude_caches_from_config_or_flag() {
+        let mut cfg = make_cfg();
+        cfg.backup.exclude_caches = true;
+        let from_config = build_backup_args(&make_cli(&[]), &cfg, false);
+        let from_flag = build_backup_args(&make_cli(&["--exclude-caches"]), &make_cfg(), false);
+        for args in [from_config, from_flag] {
+            let marker = args
+                .iter()
+                .position(|a| a == "--exclude-if-present")
+                .unwrap();
+            assert_eq!(args[marker + 2], "--exclude-caches");
+            assert_eq!(args.iter().filter(|a| *a == "--exclude-caches").count(), 1);
+        }
+    }
+
+    #[test]
+    fn backup_args_ignore_inaccessible_only_when_enabled() {
+        let flag = |args: Vec<String>| args.iter().any(|a| a == "--ignore-inaccessible");
+        assert!(!flag(build_backup_args(&make_cli(&[]), &make_cfg(), false)));
+
+        let mut cfg = make_cfg();
+        cfg.backup.ignore_inaccessible = true;
+        assert!(flag(build_backup_args(&make_cli(&[]), &cfg, false)));
+    }
+
+    #[test]
+    fn backup_args_no_scan_from_config_or_flag() {
+        let mut cfg = make_cfg();
+        assert!(!build_backup_args(&make_cli(&[]), &cfg, false).contains(&"--no-scan".to_string()));
+
+        let from_flag = build_backup_args(&make_cli(&["--no-scan"]), &cfg, false);
+        cfg.backup.no_scan = true;
+        let from_config = build_backup_args(&make_cli(&["--no-scan"]), &cfg, false);
+        assert_eq!(from_flag, from_config);
+        assert_eq!(from_flag.iter().filter(|a| *a == "--no-scan").count(), 1);
+    }
+
+    #[test]
+    fn backup_args_cli_excludes_follow_config_globs() {
+        let mut cfg = make_cfg();
+        cfg.backup.iglobs = vec!["!**/*.TMP".into()];
+        let args = build_backup_args(&make_cli(&["--exclude", "*.iso"]), &cfg, false);
+        let exclude = args.iter().position(|a| a == "--glob=!*.iso").unwrap();
+        let last_config_glob = args
+            .iter()
+            .rposition(|a| a == "--glob=!**/node_modules/")
+            .unwrap();
+        let iglob = args.iter().position(|a| a.starts_with("--iglob=")).unwrap();
+        assert!(last_config_glob < exclude && exclude < iglob);
+    }
+
+    #[test]
+    fn backup_args_include_label() {
+        let args = build_backup_args(
+            &make_cli(&["--label", "before-upgrade"]),
+            &make_cfg(),
+            false,
+        );
+        let idx = args.iter().position(|a| a == "--label").unwrap();
+        assert_eq!(args[idx + 1], "before-upgrade");
+    }
+
+    #[test]
+    fn backup_args_omit_label_by_default() {
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
+        assert!(!args.contains(&"--label".to_string()));
+    }
+
     #[test]
     fn backup_args_default_source_dot_when_empty() {
         let mut cfg = make_cfg();
         cfg.backup.sources.clear();
-        let args = build_backup_args(&make_cli(&[]), &cfg);
+        let args = build_backup_args(&make_cli(&[]), &cfg, false);
         assert!(args.contains(&".".to_string()));
     }
 
+    #[test]
+    fn backup_args_cli_sources_replace_config_sources() {
+        let cli = make_cli(&["--source", "/srv/a", "--source", "/srv/b"]);
+        let args = build_backup_args(&cli, &make_cfg(), false);
+        assert!(args.ends_with(&["/srv/a".to_string(), "/srv/b".to_string()]));
+        assert!(!args.contains(&"/home/alice/project".to_string()));
+    }
+
+    #[test]
+    fn backup_args_cli_source_used_when_config_sources_empty() {
+        let mut cfg = make_cfg();
+        cfg.backup.sources.clear();
+        let args = build_backup_args(&make_cli(&["--source", "/srv/a"]), &cfg, false);
+        assert_eq!(args.last().unwrap(), "/srv/a");
+        assert!(!args.contains(&".".to_string()));
+    }
+
+    #[test]
+    fn backup_args_exclude_larger_than_when_max_size_set() {
+        let mut cfg = make_cfg();
+        cfg.backup.max_file_size_mb = Some(250);
+        let args = build_backup_args(&make_cli(&[]), &cfg, false);
+        let idx = args
+            .iter()
+            .position(|a| a == "--exclude-larger-than")
+            .unwrap();
+        assert_eq!(args[idx + 1], "250M");
+    }
+
+    #[test]
+    fn backup_args_omit_exclude_larger_than_by_default() {
+        let args = build_backup_args(&make_cli(&[]), &make_cfg(), false);
+        assert!(!args.contains(&"--exclude-larger-than".to_string()));
+    }
+
     #[test]
     fn forget_args_have_all_retention_flags() {
-        let args = build_forget_args(&make_cli(&[]), &make_cfg());
+        let args = build_forget_args(&make_cli(&[]), &make_cfg(), false);
         assert!(args.contains(&"--prune".to_string()));
         let d = args.iter().position(|a| a == "--keep-daily").unwrap();
         assert_eq!(args[d + 1], "2");
     }
 
+    #[test]
+    fn dry_run_reaches_mutating_stages() {
+        let cli = make_cli(&[]);
+        let cfg = make_cfg();
+        for args in [
+            build_backup_args(&cli, &cfg, true),
+            build_forget_args(&cli, &cfg, true),
+            build_compact_args(&cli, &cfg, true),
+        ] {
+            assert!(args.contains(&"--dry-run".to_string()), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn dry_run_absent_by_default() {
+        let cli = make_cli(&[]);
+        let cfg = make_cfg();
+        assert!(!build_forget_args(&cli, &cfg, false).contains(&"--dry-run".to_string()));
+        assert!(!build_compact_args(&cli, &cfg, false).contains(&"--dry-run".to_string()));
+    }
+
     #[test]
     fn mkdir_args_contain_repo_path() {
         let args = build_mkdir_args(&make_cli(&[]), &make_cfg());
@@ -296,49 +1089,509 @@ mod tests {
         assert!(args.contains(&"/tmp/repo".to_string()));
     }
 
+    #[test]
+    fn init_args_end_with_init_by_default() {
+        let args = build_init_args(&make_cli(&[]), &make_cfg());
+        assert_eq!(args.last().unwrap(), "init");
+    }
+
+    #[test]
+    fn init_args_set_pack_sizes() {
+        let mut cfg = make_cfg();
+        cfg.repo.pack_size_mb = Some(128);
+        cfg.repo.tree_pack_size_mb = Some(8);
+        let args = build_init_args(&make_cli(&[]), &cfg);
+        assert_eq!(&args[args.len() - 5..], [
+            "init",
+            "--set-packsize",
+            "128m",
+            "--set-treepack-size",
+            "8m"
+        ]);
+    }
+
+    #[test]
+    fn init_args_set_only_configured_pack_size() {
+        let mut cfg = make_cfg();
+        cfg.repo.tree_pack_size_mb = Some(4);
+        let args = build_init_args(&make_cli(&[]), &cfg);
+        assert!(!args.contains(&"--set-packsize".to_string()));
+        assert_eq!(&args[args.len() - 2..], ["--set-treepack-size", "4m"]);
+    }
+
     #[test]
     fn check_args_end_with_check() {
-        let args = build_check_args(&make_cli(&[]), &make_cfg());
+        let args = build_check_args(&make_cli(&[]), &make_cfg(), None);
         assert_eq!(args.last().unwrap(), "check");
     }
 
+    #[test]
+    fn check_args_index_mode_adds_nothing() {
+        let cli = make_cli(&[]);
+        let cfg = make_cfg();
+        assert_eq!(
+            build_check_args(&cli, &cfg, Some(CheckMode::Index)),
+            build_check_args(&cli, &cfg, None)
+        );
+    }
+
+    #[test]
+    fn check_args_data_mode_reads_data() {
+        let args = build_check_args(&make_cli(&[]), &make_cfg(), Some(CheckMode::Data));
+        assert_eq!(&args[args.len() - 2..], ["check", "--read-data"]);
+    }
+
+    #[test]
+    fn check_args_snapshots_mode_checks_unused() {
+        let args = build_check_args(&make_cli(&[]), &make_cfg(), Some(CheckMode::Snapshots));
+        assert_eq!(&args[args.len() - 2..], ["check", "--check-unused"]);
+    }
+
     #[test]
     fn compact_args_end_with_prune() {
-        let args = build_compact_args(&make_cli(&[]), &make_cfg());
+        let args = build_compact_args(&make_cli(&[]), &make_cfg(), false);
         assert_eq!(args.last().unwrap(), "prune");
     }
 
+    #[test]
+    fn compact_args_cap_repack_size_when_set() {
+        let mut cfg = make_cfg();
+        cfg.retention.max_repack_size_mb = Some(512);
+        let args = build_compact_args(&make_cli(&[]), &cfg, false);
+        let idx = args.iter().position(|a| a == "--max-repack").unwrap();
+        assert_eq!(args[idx - 1], "prune");
+        assert_eq!(args[idx + 1], "512M");
+    }
+
+    #[test]
+    fn compact_args_omit_repack_cap_by_default() {
+        let args = build_compact_args(&make_cli(&[]), &make_cfg(), false);
+        assert!(!args.iter().any(|a| a == "--max-repack"));
+    }
+
+    #[test]
+    fn timeout_flag_rejects_zero() {
+        let res = Cli::try_parse_from(["backup", "--timeout", "0"]);
+        assert!(res.is_err());
+    }
+
+    // ── planned_stages ────────────────────────────────────────────────────────
+
+    fn skipped(cli: &Cli, cfg: &Config) -> Vec<&'static str> {
+        planned_stages(cfg, &cfg.merge_cli_overrides(cli), date(2026, 10, 16))
+            .into_iter()
+            .filter_map(|(label, skipped)| skipped.then_some(label))
+            .collect()
+    }
+
+    #[test]
+    fn planned_stages_follow_pipeline_order() {
+        let cfg = make_cfg();
+        let labels: Vec<_> = planned_stages(
+            &cfg,
+            &cfg.merge_cli_overrides(&make_cli(&[])),
+            date(2026, 10, 1),
+        )
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect();
+        assert_eq!(labels, [
+            "Mount",
+            "Init",
+            "Check",
+            "Backup",
+            "Verify",
+            "Forget",
+            "Compact",
+            "Cold archive"
+        ]);
+    }
+
+    #[test]
+    fn planned_stages_mark_no_prune_and_no_check() {
+        let cli = make_cli(&["--no-prune", "--no-check", "--no-mount"]);
+        assert_eq!(skipped(&cli, &make_cfg()), [
+            "Mount",
+            "Init",
+            "Check",
+            "Forget",
+            "Compact",
+            "Cold archive"
+        ]);
+    }
+
+    #[test]
+    fn planned_stages_mark_no_verify_and_dry_run() {
+        for flag in ["--no-verify", "--dry-run"] {
+            let skipped = skipped(&make_cli(&[flag]), &make_cfg());
+            assert!(skipped.contains(&"Verify"), "{flag}: {skipped:?}");
+        }
+        assert!(!skipped(&make_cli(&[]), &make_cfg()).contains(&"Verify"));
+    }
+
+    // ── verify ────────────────────────────────────────────────────────────────
+
+    fn ts(s: &str) -> jiff::Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn verify_args_list_latest_snapshot_as_json() {
+        let args = build_verify_args(&make_cli(&[]), &make_cfg());
+        assert_eq!(&args[args.len() - 3..], ["snapshots", "latest", "--json"]);
+    }
+
+    #[test]
+    fn verify_accepts_snapshot_from_this_run() {
+        let json = r#"[{"id": "a", "paths": ["/src"], "time": "2026-10-16T11:00:05.5+02:00"}]"#;
+        assert!(check_new_snapshot(json, ts("2026-10-16T09:00:00Z")).is_ok());
+    }
+
+    #[test]
+    fn verify_ignores_sub_second_precision() {
+        let json = r#"[{"id": "a", "paths": ["/src"], "time": "2026-10-16T09:00:00Z"}]"#;
+        assert!(check_new_snapshot(json, ts("2026-10-16T09:00:00.7Z")).is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_missing_snapshot() {
+        for json in ["", "[]"] {
+            let err = check_new_snapshot(json, ts("2026-10-16T09:00:00Z")).unwrap_err();
+            assert!(err.to_string().contains("no snapshots"), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn verify_rejects_snapshot_older_than_run() {
+        let json = r#"[{"id": "a", "paths": ["/src"], "time": "2026-10-15T09:00:00Z"}]"#;
+        let err = check_new_snapshot(json, ts("2026-10-16T09:00:00Z")).unwrap_err();
+        assert!(err.to_string().contains("predates this run"), "got: {err}");
+    }
+
+    #[test]
+    fn planned_stages_skip_prune_when_retention_is_disabled() {
+        let mut cfg = make_cfg();
+        cfg.retention.daily = 0;
+        cfg.retention.weekly = 0;
+        cfg.retention.monthly = 0;
+        let skipped = skipped(&make_cli(&[]), &cfg);
+        assert!(skipped.contains(&"Forget") && skipped.contains(&"Compact"));
+        assert!(!skipped.contains(&"Check"));
+    }
+
+    #[test]
+    fn planned_stages_include_init_for_missing_repo() {
+        let mut cfg = make_cfg();
+        cfg.repo.path = "/nonexistent/backup-rs-plan-test".into();
+        assert!(!skipped(&make_cli(&["--init-if-missing"]), &cfg).contains(&"Init"));
+        assert!(skipped(&make_cli(&[]), &cfg).contains(&"Init"));
+    }
+
+    #[test]
+    fn remote_repo_is_never_missing() {
+        let mut cfg = make_cfg();
+        for path in ["sftp:alice@nas:/backups", "rclone:gdrive:backups"] {
+            cfg.repo.path = path.into();
+            assert!(!repo_missing(&cfg), "{path}");
+            assert!(
+                skipped(&make_cli(&["--init-if-missing"]), &cfg).contains(&"Init"),
+                "{path}"
+            );
+        }
+    }
+
+    // ── stage metadata ────────────────────────────────────────────────────────
+
+    /// Every pipeline stage, in execution order.
+    const STAGES: [StageMeta; 8] = [
+        STAGE_MOUNT,
+        STAGE_INIT_DIR,
+        STAGE_INIT,
+        STAGE_CHECK,
+        STAGE_BACKUP,
+        STAGE_FORGET,
+        STAGE_COMPACT,
+        STAGE_COLD_ARCHIVE,
+    ];
+
+    #[test]
+    fn stage_ids_are_unique_and_lowercase() {
+        let ids: std::collections::BTreeSet<_> = STAGES.iter().map(|m| m.id).collect();
+        assert_eq!(ids.len(), STAGES.len());
+        for id in ids {
+            assert!(
+                id.chars().all(|c| c.is_ascii_lowercase() || c == '-'),
+                "{id}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_backup_is_unskippable() {
+        let fixed: Vec<_> = STAGES.iter().filter(|m| !m.skippable).collect();
+        assert_eq!(fixed, [&STAGE_BACKUP]);
+    }
+
+    #[test]
+    fn repo_not_found_carries_init_id() {
+        assert_eq!(repo_not_found(&make_cfg()).stage_id, Some("init"));
+    }
+
+    // ── cold archive ──────────────────────────────────────────────────────────
+
+    fn date(year: i16, month: i8, day: i8) -> jiff::civil::Date {
+        jiff::civil::date(year, month, day)
+    }
+
+    #[test]
+    fn cold_archive_day_is_the_first_of_the_month() {
+        assert!(is_cold_archive_day(date(2026, 11, 1)));
+        assert!(is_cold_archive_day(date(2027, 1, 1)));
+        assert!(!is_cold_archive_day(date(2026, 10, 2)));
+        assert!(!is_cold_archive_day(date(2026, 10, 31)));
+    }
+
+    #[test]
+    fn cold_archive_needs_cold_storage() {
+        let mut cfg = make_cfg();
+        assert!(!cold_archive_due(&cfg, date(2026, 11, 1)));
+        cfg.repo.cold_storage = true;
+        assert!(cold_archive_due(&cfg, date(2026, 11, 1)));
+        assert!(!cold_archive_due(&cfg, date(2026, 11, 2)));
+    }
+
+    #[test]
+    fn planned_stages_run_cold_archive_on_the_first() {
+        let mut cfg = make_cfg();
+        cfg.repo.cold_storage = true;
+        let eff = cfg.merge_cli_overrides(&make_cli(&[]));
+        let cold = |today| planned_stages(&cfg, &eff, today).last().copied();
+        assert_eq!(cold(date(2026, 11, 1)), Some(("Cold archive", false)));
+        assert_eq!(cold(date(2026, 11, 15)), Some(("Cold archive", true)));
+    }
+
+    #[test]
+    fn cold_copy_args_select_the_copy_profile() {
+        let mut cfg = make_cfg();
+        cfg.cold_repo.path = Some("/mnt/archive/rustic/app".into());
+        let args = build_cold_copy_args(&make_cli(&[]), &cfg, "backup-rs-copy-1", true);
+        assert_eq!(&args[args.len() - 6..], [
+            "-P",
+            "backup-rs-copy-1",
+            "copy",
+            "latest",
+            "--init",
+            "--dry-run"
+        ]);
+    }
+
+    // ── pre-flight checks ─────────────────────────────────────────────────────
+
+    #[test]
+    fn rustic_check_names_where_it_was_found() {
+        let found = check_rustic(Some("/usr/bin/rustic".into()));
+        assert!(!found.failed());
+        assert_eq!(found.label, "rustic found at /usr/bin/rustic");
+
+        let missing = check_rustic(None);
+        assert!(missing.failed());
+        assert!(missing.error.as_deref().unwrap().contains("not on PATH"));
+    }
+
+    #[test]
+    fn source_check_fails_for_missing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!check_source(dir.path()).failed());
+
+        let gone = check_source(&dir.path().join("gone"));
+        assert!(gone.failed());
+        assert!(
+            gone.error
+                .as_deref()
+                .unwrap()
+                .ends_with("gone does not exist")
+        );
+    }
+
+    #[test]
+    fn repo_parent_check_wants_an_existing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!check_repo_parent(&dir.path().join("repo")).failed());
+
+        let nested = check_repo_parent(&dir.path().join("missing").join("repo"));
+        assert!(nested.failed());
+        assert!(nested.error.as_deref().unwrap().contains("does not exist"));
+    }
+
+    #[test]
+    fn mount_share_check_rejects_unknown_names() {
+        let mut cfg = make_cfg();
+        assert!(!check_mount_share(&cfg.mount).failed());
+
+        cfg.mount.share = Some("no-such-share".into());
+        let unknown = check_mount_share(&cfg.mount);
+        assert!(unknown.failed());
+        assert_eq!(unknown.label, "Mount share 'no-such-share'");
+        assert!(
+            unknown
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("unknown share name")
+        );
+
+        cfg.mount.method = MountMethod::Rclone {
+            remote: "gdrive".into(),
+        };
+        assert!(
+            !check_mount_share(&cfg.mount).failed(),
+            "rclone needs no share map entry"
+        );
+    }
+
+    /// The labels of `pre_flight_checks`, minus the rustic lookup, which
+    /// depends on the machine running the tests.
+    fn check_labels(cli: &Cli, cfg: &Config) -> Vec<String> {
+        pre_flight_checks(cli, cfg)
+            .into_iter()
+            .skip(1)
+            .map(|c| c.label)
+            .collect()
+    }
+
+    #[test]
+    fn pre_flight_covers_sources_and_mount_share() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = make_cfg();
+        cfg.backup.sources = vec![dir.path().display().to_string()];
+        assert_eq!(check_labels(&make_cli(&[]), &cfg), [
+            format!("Source {}", dir.path().display()),
+            "Mount share 'new-backups'".into(),
+        ]);
+    }
+
+    #[test]
+    fn pre_flight_checks_repo_parent_only_when_creating_the_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = make_cfg();
+        cfg.repo.path = dir.path().join("repo").display().to_string();
+        cfg.backup.sources = vec![dir.path().display().to_string()];
+        let parent = format!("Repository parent {}", dir.path().display());
+
+        let creating = check_labels(&make_cli(&["--no-mount", "--init-if-missing"]), &cfg);
+        assert!(creating.contains(&parent), "{creating:?}");
+        for flags in [&["--no-mount"][..], &[
+            "--no-mount",
+            "--init-if-missing",
+            "--dry-run",
+        ]] {
+            let labels = check_labels(&make_cli(flags), &cfg);
+            assert!(!labels.contains(&parent), "{flags:?}: {labels:?}");
+        }
+    }
+
+    #[test]
+    fn pre_flight_leaves_paths_under_the_mountpoint_to_the_mount() {
+        let mut cfg = make_cfg();
+        cfg.mount.mountpoint = Some("/mnt/nas-for-test".into());
+        cfg.repo.path = "/mnt/nas-for-test/rustic/app".into();
+        cfg.backup.sources = vec!["/mnt/nas-for-test/shared".into()];
+
+        let mounting = check_labels(&make_cli(&["--init-if-missing"]), &cfg);
+        assert_eq!(mounting, ["Mount share 'new-backups'"]);
+
+        let not_mounting = check_labels(&make_cli(&["--init-if-missing", "--no-mount"]), &cfg);
+        assert_eq!(not_mounting, [
+            "Source /mnt/nas-for-test/shared",
+            "Repository parent /mnt/nas-for-test/rustic",
+        ]);
+    }
+
     // ── insta snapshot tests ──────────────────────────────────────────────────
     // These lock down the exact argument vectors so any unintended change is
     // immediately visible in the diff.
 
     #[test]
     fn snapshot_backup_args_default() {
-        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &make_cfg()));
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &make_cfg(), false));
     }
 
     #[test]
     fn snapshot_backup_args_sudo() {
-        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&["--sudo"]), &make_cfg()));
+        insta::assert_debug_snapshot!(build_backup_args(
+            &make_cli(&["--sudo"]),
+            &make_cfg(),
+            false
+        ));
     }
 
     #[test]
     fn snapshot_backup_args_empty_sources() {
         let mut cfg = make_cfg();
         cfg.backup.sources.clear();
-        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg));
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
     }
 
     #[test]
     fn snapshot_backup_args_multiple_sources() {
         let mut cfg = make_cfg();
         cfg.backup.sources = vec!["/a".into(), "/b".into(), "/c".into()];
-        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg));
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_backup_args_globs_and_iglobs() {
+        let mut cfg = make_cfg();
+        cfg.backup.iglobs = vec!["!**/*.tmp".into(), "!**/thumbs.db".into()];
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_backup_args_exclude_files() {
+        let mut cfg = make_cfg();
+        cfg.backup.exclude_files = vec![".backupignore".into(), "/etc/backup.ignore".into()];
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_backup_args_label() {
+        insta::assert_debug_snapshot!(build_backup_args(
+            &make_cli(&["--label", "before-major-upgrade"]),
+            &make_cfg(),
+            false
+        ));
+    }
+
+    #[test]
+    fn snapshot_backup_args_exclude_caches() {
+        let mut cfg = make_cfg();
+        cfg.backup.exclude_caches = true;
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_backup_args_no_scan() {
+        let mut cfg = make_cfg();
+        cfg.backup.exclude_caches = true;
+        cfg.backup.ignore_inaccessible = true;
+        cfg.backup.no_scan = true;
+        cfg.backup.exclude_files = vec![".backupignore".into()];
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_backup_args_custom_metadata() {
+        let mut cfg = make_cfg();
+        cfg.backup.custom_metadata = std::collections::BTreeMap::from([
+            ("project".into(), "myapp".into()),
+            ("owner".into(), "alice".into()),
+        ]);
+        insta::assert_debug_snapshot!(build_backup_args(&make_cli(&[]), &cfg, false));
     }
 
     #[test]
     fn snapshot_forget_args_default() {
-        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &make_cfg()));
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &make_cfg(), false));
     }
 
     #[test]
@@ -347,7 +1600,88 @@ mod tests {
         cfg.retention.daily = 7;
         cfg.retention.weekly = 4;
         cfg.retention.monthly = 12;
-        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg));
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_keep_tags() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_tags = vec!["release".into(), "pre-upgrade".into()];
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_keep_all_within() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_all_within = Some("24h".into());
+        cfg.retention.keep_tags = vec!["release".into()];
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_keep_within_daily() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_within_daily = Some("14d".into());
+        cfg.retention.keep_tags = vec!["release".into()];
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_keep_within_weekly() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_within_weekly = Some("1y".into());
+        cfg.retention.keep_tags = vec!["release".into()];
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_keep_within_monthly() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_within_monthly = Some("5y".into());
+        cfg.retention.keep_tags = vec!["release".into()];
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_all_keep_within_flags_follow_keep_all_within() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_all_within = Some("24h".into());
+        cfg.retention.keep_within_daily = Some("14d".into());
+        cfg.retention.keep_within_weekly = Some("1y".into());
+        cfg.retention.keep_within_monthly = Some("5y".into());
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_group_by_host() {
+        let mut cfg = make_cfg();
+        cfg.retention.group_by = Some("host".into());
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_forget_args_group_by_follows_keep_tags() {
+        let mut cfg = make_cfg();
+        cfg.retention.keep_tags = vec!["release".into()];
+        cfg.retention.group_by = Some("host,paths".into());
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &cfg, false));
+    }
+
+    #[test]
+    fn snapshot_compact_args_max_repack_size() {
+        let mut cfg = make_cfg();
+        cfg.retention.max_repack_size_mb = Some(10_240);
+        insta::assert_debug_snapshot!(build_compact_args(&make_cli(&[]), &cfg, true));
+    }
+
+    #[test]
+    fn snapshot_forget_args_dry_run() {
+        insta::assert_debug_snapshot!(build_forget_args(&make_cli(&[]), &make_cfg(), true));
+    }
+
+    #[test]
+    fn snapshot_compact_args_dry_run() {
+        insta::assert_debug_snapshot!(build_compact_args(&make_cli(&[]), &make_cfg(), true));
     }
 
     #[test]
@@ -357,11 +1691,11 @@ mod tests {
 
     #[test]
     fn snapshot_check_args() {
-        insta::assert_debug_snapshot!(build_check_args(&make_cli(&[]), &make_cfg()));
+        insta::assert_debug_snapshot!(build_check_args(&make_cli(&[]), &make_cfg(), None));
     }
 
     #[test]
     fn snapshot_compact_args() {
-        insta::assert_debug_snapshot!(build_compact_args(&make_cli(&[]), &make_cfg()));
+        insta::assert_debug_snapshot!(build_compact_args(&make_cli(&[]), &make_cfg(), false));
     }
 }
@@ -0,0 +1,194 @@
+//! `backup self-check` — verify this binary against its release checksum.
+//!
+//! Hashes the running executable with SHA-256 and compares the digest with a
+//! checksum file shipped alongside the release.  The file is taken from
+//! `--checksum-file`, falling back to `$BACKUP_CHECKSUM_FILE`, and may be
+//! either a bare hex digest or `sha256sum` output:
+//!
+//! ```text
+//! 3f1c…9ab2  backup-rs
+//! ```
+//!
+//! When `sha256sum` output lists several files, the line naming this binary
+//! is used.  A missing checksum file is only a warning, so `self-check` can
+//! sit in CI pipelines that do not ship one; a mismatch always fails.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use console::style;
+use sha2::{Digest as _, Sha256};
+
+use crate::ui::print_warning;
+
+/// Environment variable naming the checksum file when `--checksum-file` is
+/// not given.
+pub const CHECKSUM_FILE_ENV: &str = "BACKUP_CHECKSUM_FILE";
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Run `backup self-check`.
+pub fn run(checksum_file: Option<&Path>) -> Result<()> {
+    let Some(path) = checksum_path(checksum_file, std::env::var_os(CHECKSUM_FILE_ENV)) else {
+        print_warning(&format!(
+            "no checksum file given (--checksum-file or ${CHECKSUM_FILE_ENV}) — skipping self-check"
+        ));
+        return Ok(());
+    };
+    if !path.exists() {
+        print_warning(&format!(
+            "checksum file '{}' not found — skipping self-check",
+            path.display()
+        ));
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().context("locating the running binary")?;
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("reading checksum file '{}'", path.display()))?;
+    let bin_name = exe.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let expected = expected_checksum(&text, bin_name).with_context(|| {
+        format!(
+            "'{}' has no SHA-256 checksum for '{bin_name}'",
+            path.display()
+        )
+    })?;
+    let actual = sha256_file(&exe)?;
+
+    if !checksums_match(&actual, &expected) {
+        anyhow::bail!(
+            "'{}' does not match its release checksum\n  expected {expected}\n  actual   {actual}",
+            exe.display()
+        );
+    }
+    println!(
+        "  {}  '{}' matches its release checksum.",
+        style("✓").green().bold(),
+        exe.display()
+    );
+    Ok(())
+}
+
+// ─── Verification ─────────────────────────────────────────────────────────────
+
+/// The checksum file to use: the flag, else a non-empty `env` value.
+pub fn checksum_path(flag: Option<&Path>, env: Option<OsString>) -> Option<PathBuf> {
+    flag.map(Path::to_path_buf)
+        .or_else(|| env.filter(|v| !v.is_empty()).map(PathBuf::from))
+}
+
+/// Hex SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("opening '{}'", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("reading '{}'", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The digest for `bin_name` in a checksum file.
+///
+/// A line is `<hex>` or `<hex>  <name>` (with an optional `*` binary-mode
+/// marker before the name).  Lines without a name apply to any binary; named
+/// lines only to `bin_name`.  Returns `None` when no line applies or the
+/// digest is not 64 hex digits.
+pub fn expected_checksum(text: &str, bin_name: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let digest = fields.next()?;
+        let applies = fields.next().is_none_or(|name| {
+            let name = name.trim_start_matches('*');
+            Path::new(name).file_name().is_some_and(|n| n == bin_name)
+        });
+        let valid = digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit());
+        (applies && valid).then(|| digest.to_ascii_lowercase())
+    })
+}
+
+/// Compare two hex digests, ignoring case.
+pub const fn checksums_match(actual: &str, expected: &str) -> bool {
+    actual.eq_ignore_ascii_case(expected)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SHA-256 of the bytes `hello\n`.
+    const HELLO: &str = "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+
+    #[test]
+    fn sha256_of_a_known_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello");
+        std::fs::write(&path, "hello\n").unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), HELLO);
+    }
+
+    #[test]
+    fn bare_digest_applies_to_any_binary() {
+        assert_eq!(
+            expected_checksum(&format!("{HELLO}\n"), "backup-rs").as_deref(),
+            Some(HELLO)
+        );
+    }
+
+    #[test]
+    fn sha256sum_output_is_matched_by_binary_name() {
+        let other = "0".repeat(64);
+        let text = format!("{other}  backup-rs.sig\n{HELLO} *dist/backup-rs\n");
+        assert_eq!(
+            expected_checksum(&text, "backup-rs").as_deref(),
+            Some(HELLO)
+        );
+    }
+
+    #[test]
+    fn digest_for_another_binary_does_not_apply() {
+        assert_eq!(
+            expected_checksum(&format!("{HELLO}  rustic"), "backup-rs"),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_digest_is_rejected() {
+        assert_eq!(
+            expected_checksum("not-a-digest  backup-rs", "backup-rs"),
+            None
+        );
+        assert_eq!(expected_checksum("", "backup-rs"), None);
+    }
+
+    #[test]
+    fn digests_compare_case_insensitively() {
+        assert!(checksums_match(HELLO, &HELLO.to_ascii_uppercase()));
+        assert!(!checksums_match(HELLO, &"0".repeat(64)));
+    }
+
+    #[test]
+    fn flag_beats_env_and_empty_env_is_ignored() {
+        let flag = Path::new("/flag.sha256");
+        assert_eq!(
+            checksum_path(Some(flag), Some("/env.sha256".into())).as_deref(),
+            Some(flag)
+        );
+        assert_eq!(
+            checksum_path(None, Some("/env.sha256".into())),
+            Some(PathBuf::from("/env.sha256"))
+        );
+        assert_eq!(checksum_path(None, Some(OsString::new())), None);
+    }
+
+    #[test]
+    fn missing_checksum_file_only_warns() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(run(Some(&dir.path().join("absent.sha256"))).is_ok());
+    }
+}
@@ -0,0 +1,172 @@
+//! `backup show-excludes` — list every exclusion rule a backup would apply.
+//!
+//! When a file is unexpectedly missing from a snapshot, this shows which
+//! rules were in play.  Rules are printed in the order [`build_backup_args`]
+//! passes them to rustic, each tagged with its origin:
+//!
+//! ```text
+//!   [config:exclude_if_present]  ignore
+//!   [config:exclude_files]       .backupignore
+//!   [config:sources_exclude]     !/home/alice/app/scratch
+//!   [config:globs]               !**/.git
+//!   [cli:--exclude]              !*.iso
+//! ```
+//!
+//! Glob values are shown as rustic receives them, so an exclusion carries its
+//! leading `!`.
+//!
+//! [`build_backup_args`]: crate::commands::run::build_backup_args
+
+use std::fmt::Write as _;
+
+use console::style;
+
+use crate::{cli::Cli, config::Config};
+
+/// One exclusion rule and where it came from, e.g. `config:globs`.
+pub type ExcludeRule = (&'static str, String);
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Entry point for `backup show-excludes`.
+pub fn run(cli: &Cli, cfg: &Config) {
+    let rules = collect_excludes(cli, cfg);
+    if rules.is_empty() {
+        println!("  No exclusion rules.");
+        return;
+    }
+    print!("{}", render_rules(&rules));
+}
+
+// ─── Collection ───────────────────────────────────────────────────────────────
+
+/// Every active exclusion rule, in the order rustic evaluates them.
+pub fn collect_excludes(cli: &Cli, cfg: &Config) -> Vec<ExcludeRule> {
+    let b = &cfg.backup;
+    let mut rules = Vec::new();
+    if !b.exclude_if_present.is_empty() {
+        rules.push(("config:exclude_if_present", b.exclude_if_present.clone()));
+    }
+    if b.exclude_caches {
+        rules.push(("config:exclude_caches", "CACHEDIR.TAG".into()));
+    } else if cli.exclude_caches {
+        rules.push(("cli:--exclude-caches", "CACHEDIR.TAG".into()));
+    }
+    if let Some(mb) = b.max_file_size_mb {
+        rules.push(("config:max_file_size_mb", format!("larger than {mb}M")));
+    }
+    rules.extend(
+        b.exclude_files
+            .iter()
+            .map(|f| ("config:exclude_files", f.display().to_string())),
+    );
+    rules.extend(
+        b.sources_exclude
+            .iter()
+            .map(|p| ("config:sources_exclude", format!("!{p}"))),
+    );
+    rules.extend(b.globs.iter().map(|g| ("config:globs", g.clone())));
+    rules.extend(
+        cli.excludes
+            .iter()
+            .map(|g| ("cli:--exclude", format!("!{g}"))),
+    );
+    rules.extend(b.iglobs.iter().map(|g| ("config:iglobs", g.clone())));
+    rules
+}
+
+/// Render rules as an aligned `[origin]  rule` list.
+pub fn render_rules(rules: &[ExcludeRule]) -> String {
+    let tags: Vec<String> = rules
+        .iter()
+        .map(|(origin, _)| format!("[{origin}]"))
+        .collect();
+    let width = tags.iter().map(String::len).max().unwrap_or(0);
+    let mut out = String::new();
+    for (tag, (_, rule)) in tags.iter().zip(rules) {
+        let _ = writeln!(out, "  {}  {rule}", style(format!("{tag:<width$}")).dim());
+    }
+    out
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn cfg_with_every_exclusion() -> Config {
+        let mut cfg = Config::default();
+        cfg.backup.exclude_if_present = "ignore".into();
+        cfg.backup.exclude_caches = true;
+        cfg.backup.max_file_size_mb = Some(512);
+        cfg.backup.exclude_files = vec![".backupignore".into()];
+        cfg.backup.sources_exclude = vec!["/app/scratch".into()];
+        cfg.backup.globs = vec!["!**/.git".into()];
+        cfg.backup.iglobs = vec!["!**/*.TMP".into()];
+        cfg
+    }
+
+    #[test]
+    fn every_exclusion_source_is_listed() {
+        let cli = make_cli(&["--exclude", "*.iso"]);
+        let out = render_rules(&collect_excludes(&cli, &cfg_with_every_exclusion()));
+        let plain = console::strip_ansi_codes(&out);
+        for expected in [
+            "[config:exclude_if_present]  ignore",
+            "[config:exclude_caches]      CACHEDIR.TAG",
+            "[config:max_file_size_mb]    larger than 512M",
+            "[config:exclude_files]       .backupignore",
+            "[config:sources_exclude]     !/app/scratch",
+            "[config:globs]               !**/.git",
+            "[config:iglobs]              !**/*.TMP",
+            "[cli:--exclude]              !*.iso",
+        ] {
+            assert!(
+                plain.contains(expected),
+                "missing {expected:?} in:\n{plain}"
+            );
+        }
+    }
+
+    #[test]
+    fn rules_follow_backup_argument_order() {
+        let origins: Vec<&str> = collect_excludes(
+            &make_cli(&["--exclude", "*.iso"]),
+            &cfg_with_every_exclusion(),
+        )
+        .into_iter()
+        .map(|(origin, _)| origin)
+        .collect();
+        assert_eq!(origins, [
+            "config:exclude_if_present",
+            "config:exclude_caches",
+            "config:max_file_size_mb",
+            "config:exclude_files",
+            "config:sources_exclude",
+            "config:globs",
+            "cli:--exclude",
+            "config:iglobs",
+        ]);
+    }
+
+    #[test]
+    fn exclude_caches_flag_is_attributed_to_the_cli() {
+        let rules = collect_excludes(&make_cli(&["--exclude-caches"]), &Config::default());
+        assert!(rules.contains(&("cli:--exclude-caches", "CACHEDIR.TAG".into())));
+    }
+
+    #[test]
+    fn empty_sentinel_is_not_a_rule() {
+        let mut cfg = Config::default();
+        cfg.backup.exclude_if_present = String::new();
+        cfg.backup.globs.clear();
+        assert!(collect_excludes(&make_cli(&[]), &cfg).is_empty());
+    }
+}
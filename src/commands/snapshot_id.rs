@@ -0,0 +1,133 @@
+//! `backup snapshot-id` — print one snapshot's ID and nothing else.
+//!
+//! | Invocation                     | Prints                               |
+//! |--------------------------------|--------------------------------------|
+//! | `backup snapshot-id`           | ID of the newest snapshot            |
+//! | `backup snapshot-id --latest`  | Same, spelled out                    |
+//! | `backup snapshot-id --index 1` | ID of the snapshot before the newest |
+//!
+//! The ID goes to stdout with no label and no trailing newline, so it can be
+//! interpolated directly:
+//!
+//! ```text
+//! rustic -r /mnt/nas/rustic/app restore "$(backup snapshot-id)" /tmp/restore
+//! ```
+//!
+//! Exits non-zero when the repository has no snapshot at that position.
+
+use std::io::Write as _;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    cli::Cli,
+    commands::import::{build_snapshots_args, collect_snapshots},
+    config::Config,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Print the ID of the snapshot `index` places back from the newest.
+///
+/// `--latest` is index 0; it is the default when neither flag is given.
+pub fn run(cli: &Cli, cfg: &Config, index: Option<usize>) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let listing = run_stage(ui, "Snapshots", &build_snapshots_args(cli, cfg), timeout);
+    if listing.failed() {
+        anyhow::bail!(
+            "listing snapshots failed: {}",
+            listing.error.as_deref().unwrap_or_default()
+        );
+    }
+
+    let id = snapshot_id_from_newest(&listing.stdout, index.unwrap_or(0))?;
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(id.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+// ─── Snapshot selection ───────────────────────────────────────────────────────
+
+/// The ID of the snapshot `index` places back from the newest in
+/// `rustic snapshots --json` output.
+///
+/// Snapshots are ordered by their `time` field.  Errors when the repository
+/// has no snapshots or fewer than `index + 1`.
+pub fn snapshot_id_from_newest(json: &str, index: usize) -> Result<String> {
+    let value: serde_json::Value = if json.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(json).context("parsing `rustic snapshots --json` output")?
+    };
+
+    let mut snapshots = Vec::new();
+    collect_snapshots(&value, &mut snapshots);
+    if snapshots.is_empty() {
+        anyhow::bail!("the repository has no snapshots");
+    }
+
+    snapshots.sort_by_key(|s| {
+        std::cmp::Reverse(
+            s.get("time")
+                .and_then(|t| t.as_str()?.parse::<jiff::Timestamp>().ok()),
+        )
+    });
+    let count = snapshots.len();
+    let snapshot = snapshots.get(index).with_context(|| {
+        format!("--index {index} is out of range: the repository has {count} snapshot(s)")
+    })?;
+    snapshot
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .map(String::from)
+        .context("snapshot has no `id` field")
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    const THREE: &str = r#"[
+        {"id": "bbbb", "time": "2026-10-15T09:00:00Z", "paths": ["/a"]},
+        {"id": "cccc", "time": "2026-10-16T09:00:00Z", "paths": ["/a"]},
+        {"id": "aaaa", "time": "2026-10-14T09:00:00Z", "paths": ["/a"]}
+    ]"#;
+
+    #[test]
+    fn index_zero_is_the_newest() {
+        assert_eq!(snapshot_id_from_newest(THREE, 0).unwrap(), "cccc");
+    }
+
+    #[test]
+    fn index_counts_back_from_the_newest() {
+        assert_eq!(snapshot_id_from_newest(THREE, 1).unwrap(), "bbbb");
+        assert_eq!(snapshot_id_from_newest(THREE, 2).unwrap(), "aaaa");
+    }
+
+    #[test]
+    fn index_past_the_oldest_errors() {
+        let err = snapshot_id_from_newest(THREE, 3).unwrap_err().to_string();
+        assert!(err.contains("3 snapshot(s)"), "got: {err}");
+    }
+
+    #[test]
+    fn empty_repository_errors() {
+        for json in ["[]", "null", ""] {
+            assert!(snapshot_id_from_newest(json, 0).is_err(), "{json:?}");
+        }
+    }
+
+    #[test]
+    fn latest_conflicts_with_index() {
+        assert!(
+            Cli::try_parse_from(["backup", "snapshot-id", "--latest", "--index", "1"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! `backup snapshot-size` — how much data one snapshot holds.
+//!
+//! | Invocation                                 | Prints                     |
+//! |--------------------------------------------|----------------------------|
+//! | `backup snapshot-size latest`              | e.g. `1.4 GiB`             |
+//! | `backup snapshot-size 3f2a9c1e`            | Same, for that snapshot    |
+//! | `backup snapshot-size latest --raw-bytes`  | e.g. `1503238553`, no `\n` |
+//!
+//! The size is what rustic recorded in the snapshot's summary
+//! (`total_bytes_processed`): the bytes of the source files the backup read,
+//! before deduplication and compression.  A snapshot ID may be abbreviated
+//! to any unique prefix.
+
+use std::io::Write as _;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    cli::Cli,
+    commands::snapshots::{SnapshotSummary, fetch_listing, parse_snapshots},
+    config::Config,
+};
+
+/// Binary units used by [`format_size`], smallest first.
+const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Print the size of `snapshot` (`latest` or an ID prefix), human-readable
+/// or, with `raw_bytes`, as a bare byte count.
+pub fn run(cli: &Cli, cfg: &Config, snapshot: &str, raw_bytes: bool) -> Result<()> {
+    let snapshots = parse_snapshots(&fetch_listing(cli, cfg)?)?;
+    let found = select_snapshot(&snapshots, snapshot)?;
+    let bytes = found
+        .size
+        .with_context(|| format!("snapshot {} has no recorded size", found.id))?;
+
+    let mut stdout = std::io::stdout().lock();
+    if raw_bytes {
+        write!(stdout, "{bytes}")?;
+    } else {
+        writeln!(stdout, "{}", format_size(bytes))?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+// ─── Selection and formatting ─────────────────────────────────────────────────
+
+/// The snapshot `wanted` names in `snapshots` (oldest first, as
+/// [`parse_snapshots`] returns them): the newest for `latest`, otherwise the
+/// only one whose ID starts with `wanted`.
+pub fn select_snapshot<'a>(
+    snapshots: &'a [SnapshotSummary],
+    wanted: &str,
+) -> Result<&'a SnapshotSummary> {
+    if wanted == "latest" {
+        return snapshots.last().context("the repository has no snapshots");
+    }
+    let mut matches = snapshots.iter().filter(|s| s.id.starts_with(wanted));
+    match (matches.next(), matches.next()) {
+        (Some(found), None) => Ok(found),
+        (None, _) => anyhow::bail!("no snapshot with ID '{wanted}'"),
+        (Some(_), Some(_)) => {
+            anyhow::bail!("'{wanted}' matches several snapshots; give more of the ID")
+        },
+    }
+}
+
+/// `bytes` in the largest binary unit that keeps the number at least 1, with
+/// one decimal, e.g. `1.5 KiB`.  Plain bytes have no decimal.
+pub fn format_size(bytes: u64) -> String {
+    let mut unit = 0;
+    let mut scale = 1_u128;
+    while unit + 1 < UNITS.len() && u128::from(bytes) >= scale * 1024 {
+        unit += 1;
+        scale *= 1024;
+    }
+    if unit == 0 {
+        return format!("{bytes} B");
+    }
+    let tenths = (u128::from(bytes) * 10 + scale / 2) / scale;
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"[
+        {"id": "aaaa1111", "time": "2026-10-14T09:00:00Z", "paths": ["/a"],
+         "summary": {"total_bytes_processed": 2048}},
+        {"id": "aaaa2222", "time": "2026-10-16T09:00:00Z", "paths": ["/a"],
+         "summary": {"total_bytes_processed": 4096}},
+        {"id": "bbbb3333", "time": "2026-10-15T09:00:00Z", "paths": ["/a"]}
+    ]"#;
+
+    fn listing() -> Vec<SnapshotSummary> {
+        parse_snapshots(LISTING).unwrap()
+    }
+
+    #[test]
+    fn latest_is_the_newest_snapshot() {
+        let snapshots = listing();
+        let found = select_snapshot(&snapshots, "latest").unwrap();
+        assert_eq!(found.id, "aaaa2222");
+        assert_eq!(found.size, Some(4096));
+    }
+
+    #[test]
+    fn unique_prefix_selects_a_snapshot() {
+        let snapshots = listing();
+        assert_eq!(select_snapshot(&snapshots, "aaaa1").unwrap().id, "aaaa1111");
+        assert_eq!(select_snapshot(&snapshots, "bbbb3333").unwrap().size, None);
+    }
+
+    #[test]
+    fn ambiguous_or_unknown_prefix_is_an_error() {
+        let snapshots = listing();
+        let err = select_snapshot(&snapshots, "aaaa").unwrap_err().to_string();
+        assert!(err.contains("several snapshots"), "got: {err}");
+        let err = select_snapshot(&snapshots, "cccc").unwrap_err().to_string();
+        assert!(err.contains("no snapshot with ID 'cccc'"), "got: {err}");
+    }
+
+    #[test]
+    fn latest_in_an_empty_repository_is_an_error() {
+        assert!(select_snapshot(&[], "latest").is_err());
+    }
+
+    #[test]
+    fn sizes_use_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_size(1_503_238_553), "1.4 GiB");
+        assert_eq!(format_size(3 << 40), "3.0 TiB");
+    }
+
+    #[test]
+    fn huge_sizes_stay_in_tebibytes() {
+        assert_eq!(format_size(u64::MAX), "16777216.0 TiB");
+    }
+}
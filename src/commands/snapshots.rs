@@ -0,0 +1,435 @@
+//! `backup snapshots` — list the snapshots in the repository.
+//!
+//! | Invocation                              | Description                  |
+//! |-----------------------------------------|------------------------------|
+//! | `backup snapshots`                      | Every snapshot, oldest first |
+//! | `backup snapshots --group-by host`      | One section per host         |
+//! | `backup snapshots --group-by tag`       | One section per tag          |
+//! | `backup snapshots --count`              | The number of snapshots      |
+//! | `backup snapshots --count --json`       | `{"count": N}`               |
+//! | `backup snapshots --output-format json` | rustic's JSON, unchanged     |
+//! | `backup snapshots --output-format csv`  | `id,time,host,size` rows     |
+//!
+//! In the default table, each snapshot is one line: short ID, time and
+//! paths.  With `--group-by tag`, a snapshot carrying several tags is listed
+//! under each of them, and snapshots without tags are collected under
+//! `(untagged)`.  `--group-by` applies to the table only.
+//!
+//! `backup snapshot-count` prints the same number as `--count` with no
+//! trailing newline, so `[ "$(backup snapshot-count)" -gt 0 ]` needs no
+//! trimming.
+//!
+//! The CSV starts with a header row; `id` is the full snapshot ID and `size`
+//! the bytes the backup read, empty when rustic did not record it.
+
+use std::{collections::BTreeMap, fmt::Write as _, io::Write as _};
+
+use anyhow::{Context, Result};
+use console::style;
+
+use crate::{
+    cli::{Cli, GroupBy, OutputFormat},
+    commands::import::{build_snapshots_args, collect_snapshots},
+    config::Config,
+    ui::{UiContext, run_stage},
+};
+
+/// Group name for snapshots without a tag under `--group-by tag`.
+const UNTAGGED: &str = "(untagged)";
+
+/// The fields of a `rustic snapshots --json` entry this command shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub time: String,
+    pub hostname: String,
+    pub tags: Vec<String>,
+    pub paths: Vec<String>,
+    /// Bytes the backup read (`summary.total_bytes_processed`), if recorded.
+    pub size: Option<u64>,
+}
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Entry point for `backup snapshots`.
+pub fn run(cli: &Cli, cfg: &Config, group_by: Option<GroupBy>, format: OutputFormat) -> Result<()> {
+    if group_by.is_some() && format != OutputFormat::Table {
+        anyhow::bail!("--group-by only applies to --output-format table");
+    }
+    let listing = fetch_listing(cli, cfg)?;
+    match format {
+        OutputFormat::Json => {
+            println!("{}", render_json(&listing));
+            return Ok(());
+        },
+        OutputFormat::Csv => {
+            print!("{}", render_csv(&parse_snapshots(&listing)?));
+            return Ok(());
+        },
+        OutputFormat::Table => {},
+    }
+
+    let snapshots = parse_snapshots(&listing)?;
+    if snapshots.is_empty() {
+        println!("  No snapshots in '{}'.", cfg.repo.path);
+        return Ok(());
+    }
+    match group_by {
+        Some(field) => print!("{}", render_groups(&group_snapshots(&snapshots, field))),
+        None => print!(
+            "{}",
+            render_snapshots(&snapshots.iter().collect::<Vec<_>>())
+        ),
+    }
+    Ok(())
+}
+
+/// Entry point for `backup snapshots --count`: the number of snapshots and
+/// nothing else, or `{"count": N}` with `json`.
+pub fn run_count(cli: &Cli, cfg: &Config, json: bool) -> Result<()> {
+    let count = parse_snapshots(&fetch_listing(cli, cfg)?)?.len();
+    println!("{}", render_count(count, json));
+    Ok(())
+}
+
+/// Entry point for `backup snapshot-count`: the bare number of snapshots,
+/// without even a newline.
+pub fn run_snapshot_count(cli: &Cli, cfg: &Config) -> Result<()> {
+    let count = parse_snapshots(&fetch_listing(cli, cfg)?)?.len();
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(render_count(count, false).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// The repository's `rustic snapshots --json` output.
+pub fn fetch_listing(cli: &Cli, cfg: &Config) -> Result<String> {
+    let ui = UiContext::from_cli(cli);
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let listing = run_stage(ui, "Snapshots", &build_snapshots_args(cli, cfg), timeout);
+    if listing.failed() {
+        anyhow::bail!(
+            "listing snapshots failed: {}",
+            listing.error.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(listing.stdout)
+}
+
+// ─── Parsing and grouping ─────────────────────────────────────────────────────
+
+/// Every snapshot in `rustic snapshots --json` output, oldest first.
+pub fn parse_snapshots(json: &str) -> Result<Vec<SnapshotSummary>> {
+    let value: serde_json::Value = if json.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(json).context("parsing `rustic snapshots --json` output")?
+    };
+    let mut found = Vec::new();
+    collect_snapshots(&value, &mut found);
+
+    let text = |s: &serde_json::Value, key: &str| {
+        s.get(key)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let list = |s: &serde_json::Value, key: &str| -> Vec<String> {
+        s.get(key)
+            .and_then(serde_json::Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut snapshots: Vec<SnapshotSummary> = found
+        .into_iter()
+        .map(|s| SnapshotSummary {
+            id: text(s, "id"),
+            time: text(s, "time"),
+            hostname: text(s, "hostname"),
+            tags: list(s, "tags"),
+            paths: list(s, "paths"),
+            size: s
+                .pointer("/summary/total_bytes_processed")
+                .and_then(serde_json::Value::as_u64),
+        })
+        .collect();
+    snapshots.sort_by_key(|s| s.time.parse::<jiff::Timestamp>().ok());
+    Ok(snapshots)
+}
+
+/// Snapshots grouped by `field`, groups in name order, each group keeping
+/// the input order.
+pub fn group_snapshots(
+    snapshots: &[SnapshotSummary],
+    field: GroupBy,
+) -> Vec<(String, Vec<&SnapshotSummary>)> {
+    let mut groups: BTreeMap<String, Vec<&SnapshotSummary>> = BTreeMap::new();
+    for s in snapshots {
+        match field {
+            GroupBy::Host => groups.entry(s.hostname.clone()).or_default().push(s),
+            GroupBy::Tag if s.tags.is_empty() => groups.entry(UNTAGGED.into()).or_default().push(s),
+            GroupBy::Tag => {
+                for tag in &s.tags {
+                    groups.entry(tag.clone()).or_default().push(s);
+                }
+            },
+        }
+    }
+    groups.into_iter().collect()
+}
+
+// ─── Rendering ────────────────────────────────────────────────────────────────
+
+/// One line per snapshot: short ID, time, paths.
+pub fn render_snapshots(snapshots: &[&SnapshotSummary]) -> String {
+    let mut out = String::new();
+    for s in snapshots {
+        let short = s.id.get(..8).unwrap_or(&s.id);
+        let _ = writeln!(
+            out,
+            "  {}  {}  {}",
+            style(short).bold(),
+            s.time,
+            style(s.paths.join(", ")).dim()
+        );
+    }
+    out
+}
+
+/// `--output-format json`: rustic's listing as is, `[]` for an empty
+/// repository.
+pub fn render_json(listing: &str) -> &str {
+    let listing = listing.trim();
+    if listing.is_empty() { "[]" } else { listing }
+}
+
+/// `--output-format csv`: a header row, then `id,time,host,size` per
+/// snapshot.  Fields holding a comma, quote or newline are quoted.
+pub fn render_csv(snapshots: &[SnapshotSummary]) -> String {
+    snapshots
+        .iter()
+        .fold(String::from("id,time,host,size\n"), |mut out, s| {
+            let size = s.size.map(|n| n.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{},{},{},{size}",
+                csv_field(&s.id),
+                csv_field(&s.time),
+                csv_field(&s.hostname)
+            );
+            out
+        })
+}
+
+/// `field`, double-quoted (with inner quotes doubled) when CSV requires it.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// What `--count` prints: the bare number, or `{"count": N}` with `json`.
+pub fn render_count(count: usize, json: bool) -> String {
+    if json {
+        format!(r#"{{"count": {count}}}"#)
+    } else {
+        count.to_string()
+    }
+}
+
+/// A bold header per group, followed by its snapshots.
+pub fn render_groups(groups: &[(String, Vec<&SnapshotSummary>)]) -> String {
+    let mut out = String::new();
+    for (i, (name, snapshots)) in groups.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(
+            out,
+            "{} ({})",
+            style(name).bold().underlined(),
+            snapshots.len()
+        );
+        out.push_str(&render_snapshots(snapshots));
+    }
+    out
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"[
+        {"id": "cccccccc11", "time": "2026-10-16T09:00:00Z", "hostname": "laptop",
+         "tags": ["nightly"], "paths": ["/home/alice"]},
+        {"id": "aaaaaaaa11", "time": "2026-10-14T09:00:00Z", "hostname": "nas",
+         "tags": ["nightly", "release"], "paths": ["/srv"]},
+        {"id": "bbbbbbbb11", "time": "2026-10-15T09:00:00Z", "hostname": "laptop",
+         "paths": ["/home/alice"]}
+    ]"#;
+
+    fn names<'a>(groups: &'a [(String, Vec<&SnapshotSummary>)]) -> Vec<&'a str> {
+        groups.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    fn ids(group: &[&SnapshotSummary]) -> Vec<String> {
+        group.iter().map(|s| s.id.clone()).collect()
+    }
+
+    #[test]
+    fn snapshots_are_sorted_oldest_first() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        let order: Vec<_> = snapshots.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, ["aaaaaaaa11", "bbbbbbbb11", "cccccccc11"]);
+    }
+
+    #[test]
+    fn same_host_shares_one_group() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        let groups = group_snapshots(&snapshots, GroupBy::Host);
+        assert_eq!(names(&groups), ["laptop", "nas"]);
+        assert_eq!(ids(&groups[0].1), ["bbbbbbbb11", "cccccccc11"]);
+    }
+
+    #[test]
+    fn tag_groups_repeat_multi_tagged_snapshots() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        let groups = group_snapshots(&snapshots, GroupBy::Tag);
+        assert_eq!(names(&groups), ["(untagged)", "nightly", "release"]);
+        assert_eq!(ids(&groups[1].1), ["aaaaaaaa11", "cccccccc11"]);
+        assert_eq!(ids(&groups[2].1), ["aaaaaaaa11"]);
+    }
+
+    #[test]
+    fn rendered_host_groups_list_their_snapshots_under_one_header() {
+        let snapshots = parse_snapshots(LISTING).unwrap();
+        let out = render_groups(&group_snapshots(&snapshots, GroupBy::Host));
+        let plain = console::strip_ansi_codes(&out);
+        let laptop = plain.find("laptop (2)").expect("laptop header");
+        let nas = plain.find("nas (1)").expect("nas header");
+        for id in ["bbbbbbbb", "cccccccc"] {
+            let at = plain.find(id).unwrap();
+            assert!(
+                laptop < at && at < nas,
+                "{id} should sit under laptop:\n{plain}"
+            );
+        }
+        assert!(plain.find("aaaaaaaa").unwrap() > nas);
+    }
+
+    #[test]
+    fn group_by_parses_host_and_tag_only() {
+        use clap::Parser;
+
+        use crate::cli::Subcommand;
+
+        let cli = Cli::parse_from(["backup", "snapshots", "--group-by", "tag"]);
+        assert_eq!(
+            cli.command,
+            Some(Subcommand::Snapshots {
+                group_by: Some(GroupBy::Tag),
+                count: false,
+                json: false,
+                output_format: OutputFormat::Table,
+            })
+        );
+        assert!(Cli::try_parse_from(["backup", "snapshots", "--group-by", "path"]).is_err());
+    }
+
+    #[test]
+    fn snapshot_size_comes_from_the_summary() {
+        let snapshots = parse_snapshots(
+            r#"[{"id": "a", "time": "2026-10-16T09:00:00Z", "paths": ["/srv"],
+                 "summary": {"total_bytes_processed": 4096}}]"#,
+        )
+        .unwrap();
+        assert_eq!(snapshots[0].size, Some(4096));
+        assert_eq!(parse_snapshots(LISTING).unwrap()[0].size, None);
+    }
+
+    #[test]
+    fn csv_has_a_header_and_one_row_per_snapshot() {
+        let mut snapshots = parse_snapshots(LISTING).unwrap();
+        snapshots[0].size = Some(1024);
+        assert_eq!(
+            render_csv(&snapshots),
+            "id,time,host,size\n\
+             aaaaaaaa11,2026-10-14T09:00:00Z,nas,1024\n\
+             bbbbbbbb11,2026-10-15T09:00:00Z,laptop,\n\
+             cccccccc11,2026-10-16T09:00:00Z,laptop,\n"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_commas_and_quotes() {
+        assert_eq!(csv_field("nas"), "nas");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn csv_of_an_empty_repo_is_just_the_header() {
+        assert_eq!(render_csv(&[]), "id,time,host,size\n");
+    }
+
+    #[test]
+    fn json_format_passes_rustic_output_through() {
+        assert_eq!(render_json(LISTING), LISTING.trim());
+        let parsed: serde_json::Value = serde_json::from_str(render_json(LISTING)).unwrap();
+        assert_eq!(parsed.as_array().map(Vec::len), Some(3));
+        assert_eq!(render_json("\n"), "[]");
+    }
+
+    #[test]
+    fn output_format_parses_and_excludes_count() {
+        use clap::Parser;
+
+        use crate::cli::Subcommand;
+
+        let cli = Cli::parse_from(["backup", "snapshots", "--output-format", "csv"]);
+        assert!(matches!(
+            cli.command,
+            Some(Subcommand::Snapshots {
+                output_format: OutputFormat::Csv,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["backup", "snapshots", "--output-format", "xml"]).is_err());
+        assert!(
+            Cli::try_parse_from(["backup", "snapshots", "--count", "--output-format", "csv"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn count_is_a_bare_number_or_a_json_object() {
+        assert_eq!(render_count(3, false), "3");
+        assert_eq!(render_count(3, true), r#"{"count": 3}"#);
+    }
+
+    #[test]
+    fn json_needs_count_and_count_excludes_group_by() {
+        use clap::Parser;
+
+        assert!(Cli::try_parse_from(["backup", "snapshots", "--json"]).is_err());
+        assert!(
+            Cli::try_parse_from(["backup", "snapshots", "--count", "--group-by", "host"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["backup", "snapshots", "--count", "--json"]).is_ok());
+    }
+
+    #[test]
+    fn empty_listing_has_no_snapshots() {
+        assert!(parse_snapshots("").unwrap().is_empty());
+        assert!(parse_snapshots("[]").unwrap().is_empty());
+    }
+}
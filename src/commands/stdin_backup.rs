@@ -0,0 +1,141 @@
+//! `backup backup-stdin <name>` — snapshot whatever is piped into `backup`.
+//!
+//! rustic reads the data from its own stdin, which it inherits from this
+//! process, and stores it as a single file called `<name>`:
+//!
+//! ```text
+//! pg_dump app | backup backup-stdin app.sql
+//! tar -c /etc | backup backup-stdin etc.tar --label before-upgrade
+//! ```
+//!
+//! `[backup]` compression applies; sources and exclusion rules do not, since
+//! there is nothing on disk to walk.  Refuses to run when stdin is a
+//! terminal, so a forgotten pipe does not leave rustic waiting for input.
+
+use std::io::IsTerminal as _;
+
+use anyhow::Result;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    runner::rustic_base,
+    ui::{UiContext, run_stage},
+};
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+/// Back up this process's stdin as `filename`.
+pub fn run(cli: &Cli, cfg: &Config, filename: &str) -> Result<()> {
+    let ui = UiContext::from_cli(cli);
+    if std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "stdin is a terminal — pipe data in, e.g. `pg_dump app | backup backup-stdin {filename}`"
+        );
+    }
+
+    println!();
+    let timeout = cfg.merge_cli_overrides(cli).stage_timeout;
+    let backup = run_stage(
+        ui,
+        "Backup (stdin)",
+        &build_stdin_backup_args(cli, cfg, filename),
+        timeout,
+    );
+    backup.print(ui);
+    if cli.dry_run {
+        backup.print_stdout();
+    }
+    if backup.failed() {
+        anyhow::bail!("backup from stdin failed");
+    }
+    Ok(())
+}
+
+// ─── Argument builders ────────────────────────────────────────────────────────
+
+/// Arguments for `rustic backup --stdin-filename <name> -`.
+///
+/// rustic reads stdin when the source is `-`; `--stdin-filename` names the
+/// file inside the snapshot.
+pub fn build_stdin_backup_args(cli: &Cli, cfg: &Config, filename: &str) -> Vec<String> {
+    let mut cmd = rustic_base(cli, cfg);
+    cmd.extend([
+        "backup".into(),
+        "--set-compression".into(),
+        cfg.backup.compression.to_string(),
+        "--stdin-filename".into(),
+        filename.into(),
+    ]);
+    if let Some(ref label) = cli.label {
+        cmd.extend(["--label".into(), label.clone()]);
+    }
+    if cli.dry_run {
+        cmd.push("--dry-run".into());
+    }
+    cmd.push("-".into());
+    cmd
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn make_cli(extra: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    fn make_cfg() -> Config {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/tmp/repo".into();
+        cfg
+    }
+
+    #[test]
+    fn stdin_args_name_the_file_and_read_dash() {
+        let args = build_stdin_backup_args(&make_cli(&[]), &make_cfg(), "db.sql");
+        let idx = args.iter().position(|a| a == "--stdin-filename").unwrap();
+        assert_eq!(args[idx + 1], "db.sql");
+        assert_eq!(args.last().map(String::as_str), Some("-"));
+    }
+
+    #[test]
+    fn stdin_args_ignore_configured_sources() {
+        let mut cfg = make_cfg();
+        cfg.backup.sources = vec!["/home/alice".into()];
+        let args = build_stdin_backup_args(&make_cli(&[]), &cfg, "db.sql");
+        assert!(
+            !args
+                .iter()
+                .any(|a| a == "/home/alice" || a.starts_with("--glob"))
+        );
+    }
+
+    #[test]
+    fn stdin_args_pass_label_and_dry_run_before_the_source() {
+        let args = build_stdin_backup_args(
+            &make_cli(&["--label", "nightly", "--dry-run"]),
+            &make_cfg(),
+            "db.sql",
+        );
+        assert_eq!(&args[args.len() - 4..], [
+            "--label",
+            "nightly",
+            "--dry-run",
+            "-"
+        ]);
+    }
+
+    #[test]
+    fn snapshot_stdin_backup_args() {
+        insta::assert_debug_snapshot!(build_stdin_backup_args(
+            &make_cli(&[]),
+            &make_cfg(),
+            "db.sql"
+        ));
+    }
+}
@@ -13,39 +13,83 @@
 //! # File format
 //!
 //! ```toml
+//! stage_timeout_secs = 3600  # optional; kill a stage that runs longer
+//!
 //! [repo]
 //! path     = "/home/alice/nfs/new-backups/rustic/my-project"
 //! password = ""          # empty = no encryption
+//! password_env = "BACKUP_PASSWORD"  # optional; read the password from $BACKUP_PASSWORD
+//! pack_size_mb = 128      # optional; data pack size for a new repo
+//!
+//! [repo.rest]                # optional; use a rustic REST server instead of path
+//! url      = "http://nas.lan:8000/"
+//! username = "alice"
 //!
 //! [mount]
 //! share = "new-backups"  # NFS share name
 //! user  = "alice"        # optional; defaults to $USER
+//! timeout_secs = 30      # optional; give up on a mount that hangs
+//! force_recheck = true   # optional; detect a mount without `doas mount`
 //!
 //! [backup]
 //! sources            = ["/home/alice/my-project"]
+//! sources_exclude    = ["/home/alice/my-project/scratch"]  # paths to leave out
 //! compression        = 3        # zstd level 1–22
 //! exclude_if_present = "ignore" # skip dirs containing this sentinel file
+//! exclude_caches     = true     # also skip dirs with a valid CACHEDIR.TAG
+//! ignore_inaccessible = true    # skip unreadable files instead of failing
+//! no_scan            = true     # skip the pre-backup size scan
 //! globs              = ["!**/.git", "!tmp/", "!**/target/", "!**/node_modules/"]
+//! iglobs             = ["!**/*.tmp"]   # case-insensitive globs
+//! exclude_files      = [".backupignore"]  # files of globs, one per line
+//!
+//! [backup.custom_metadata]   # optional; stored with every snapshot
+//! project = "my-project"
 //!
 //! [retention]
 //! daily   = 2
 //! weekly  = 1
 //! monthly = 1
+//! keep_last = 0            # optional; also keep the N newest snapshots
+//! keep_tags = ["release"]  # always keep snapshots tagged "release"
+//! keep_all_within = "24h"  # optional; keep every snapshot this recent
+//! keep_within_weekly = "1y"  # optional; one snapshot per week for a year
+//! group_by = "host"       # optional; apply retention per host
+//! max_repack_size_mb = 10240  # optional; repack at most 10 GiB per prune
+//!
+//! [mirror]                   # optional; replica kept by `backup mirror`
+//! dest_repo = "/mnt/offsite/rustic/my-project"
 //! ```
 
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
+use console::style;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::cli::Cli;
+
 // ─── Top-level ────────────────────────────────────────────────────────────────
 
 /// Root configuration object, deserialised from `backup.toml`.
 ///
-/// All four sections are optional; missing sections fall back to their
+/// Every section is optional; missing sections fall back to their
 /// `Default` implementations.
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default)]
 pub struct Config {
+    /// Kill any single pipeline stage that runs longer than this many seconds.
+    ///
+    /// Guards against rustic hanging forever on an unresponsive NFS server.
+    /// Omit for no limit.  `--timeout` overrides this for a single run.
+    #[serde(default)]
+    pub stage_timeout_secs: Option<u64>,
+
     /// rustic repository settings.
     #[serde(default)]
     pub repo: RepoConfig,
@@ -61,17 +105,26 @@ pub struct Config {
     /// Optional NAS mount step that runs before everything else.
     #[serde(default)]
     pub mount: MountConfig,
+
+    /// Second repository that receives a monthly copy of the latest snapshot.
+    #[serde(default)]
+    pub cold_repo: ColdRepoConfig,
+
+    /// Replica repository that `backup mirror` keeps in step with this one.
+    #[serde(default)]
+    pub mirror: MirrorConfig,
 }
 
 // ─── [repo] ───────────────────────────────────────────────────────────────────
 
 /// Settings for the rustic repository itself.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct RepoConfig {
     /// Filesystem path (or `sftp:…` / `rclone:…` URI) for the repository.
     ///
-    /// rustic will read and write pack files here.  The directory is created
-    /// automatically on the first run if it does not exist.
+    /// rustic will read and write pack files here.  If it does not exist,
+    /// the pipeline creates it only when run with `--init-if-missing`.
+    #[schemars(default = "default_repo_path")]
     pub path: String,
 
     /// Encryption password.
@@ -80,7 +133,93 @@ pub struct RepoConfig {
     /// **Do not store real passwords in plain-text config files that are
     /// committed to version control.**  Consider using an environment
     /// variable or a secrets manager instead.
+    #[schemars(default)]
     pub password: String,
+
+    /// Name of an environment variable holding the password.
+    ///
+    /// When set, the variable's value replaces `password`.  A `.env` file
+    /// next to `backup.toml` is loaded first, so the secret can live there
+    /// instead of in the config.
+    #[serde(default)]
+    pub password_env: Option<String>,
+
+    /// Target size of data pack files, in megabytes.
+    ///
+    /// Forwarded to `rustic init` as `--set-packsize <N>m`.  Only affects a
+    /// repository created by `--init-if-missing`; an existing repository
+    /// keeps the sizes it was initialised with.  Must be 1–2048.
+    #[serde(default)]
+    pub pack_size_mb: Option<u32>,
+
+    /// Target size of tree pack files, in megabytes.
+    ///
+    /// Forwarded to `rustic init` as `--set-treepack-size <N>m`, with the
+    /// same caveats as `pack_size_mb`.
+    #[serde(default)]
+    pub tree_pack_size_mb: Option<u32>,
+
+    /// Extra flags appended to every rustic invocation, e.g. `["--no-cache"]`.
+    ///
+    /// They go right after `-r`/`--password` and before the subcommand, so
+    /// only rustic's global options belong here; subcommand flags such as
+    /// `--dry-run` would be rejected there.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+
+    /// Copy the latest snapshot to `[cold_repo]` on the first of each month.
+    ///
+    /// Adds a Cold archive stage after Compact that runs `rustic copy`.
+    /// Requires `[cold_repo].path`.
+    #[serde(default)]
+    pub cold_storage: bool,
+
+    /// A REST server to use instead of `path`; see [`RestConfig`].
+    #[serde(default)]
+    pub rest: Option<RestConfig>,
+}
+
+/// `[repo.rest]`: a rustic REST server (`rest-server`) holding the
+/// repository.
+///
+/// When present, rustic is pointed at `rest:<url>` with the credentials
+/// written into the URL, and `[repo].path` is not used for the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct RestConfig {
+    /// Server URL, e.g. `"http://nas.lan:8000/"` or
+    /// `"https://backup.example.com/alice"`.
+    pub url: String,
+
+    /// HTTP basic-auth user, if the server requires one.
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// HTTP basic-auth password; only used together with `username`.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl RestConfig {
+    /// Problems with `[repo.rest]`: the URL must be `http://` or
+    /// `https://`, and a password needs a username.
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            issues.push(ValidationIssue::error(
+                "repo.rest.url",
+                format!("'{}' is not an http:// or https:// URL", self.url),
+                "use the server's full URL, e.g. \"http://nas.lan:8000/\"",
+            ));
+        }
+        if self.password.is_some() && self.username.is_none() {
+            issues.push(ValidationIssue::error(
+                "repo.rest.password",
+                "is set without a username",
+                "set [repo.rest].username as well",
+            ));
+        }
+        issues
+    }
 }
 
 impl Default for RepoConfig {
@@ -88,6 +227,12 @@ impl Default for RepoConfig {
         Self {
             path: default_repo_path(),
             password: String::new(),
+            password_env: None,
+            pack_size_mb: None,
+            tree_pack_size_mb: None,
+            extra_args: vec![],
+            cold_storage: false,
+            rest: None,
         }
     }
 }
@@ -95,15 +240,25 @@ impl Default for RepoConfig {
 // ─── [backup] ─────────────────────────────────────────────────────────────────
 
 /// What to back up and what to exclude.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct BackupConfig {
     /// Paths to include in the snapshot.
     ///
     /// When empty (or omitted entirely), `backup` defaults to the current
     /// working directory (`.`), making it safe to run `backup` anywhere
     /// without editing the config.
+    #[schemars(default)]
     pub sources: Vec<String>,
 
+    /// Paths under `sources` to leave out of the snapshot.
+    ///
+    /// Each entry becomes `--glob=!<path>`, emitted *before* every `globs`
+    /// entry.  rustic lets the last matching glob win, so a `globs` pattern
+    /// such as `"/home/alice/Downloads/keep"` can still re-include something
+    /// beneath an excluded path.
+    #[serde(default)]
+    pub sources_exclude: Vec<String>,
+
     /// zstd compression level, 1 (fastest) – 22 (smallest).
     ///
     /// Level 3 is a good balance between speed and space; higher levels give
@@ -118,25 +273,142 @@ pub struct BackupConfig {
     #[serde(default = "default_globs")]
     pub globs: Vec<String>,
 
+    /// Case-insensitive glob patterns forwarded to rustic's `--iglob` flag.
+    ///
+    /// Same syntax as `globs`; use these where filename casing is
+    /// inconsistent, e.g. `"!**/*.tmp"` to also catch `FOO.TMP`.  Emitted
+    /// after all `globs`.
+    #[serde(default)]
+    pub iglobs: Vec<String>,
+
+    /// Files of exclusion globs, one pattern per line (like `.gitignore`).
+    ///
+    /// Each is forwarded as `--exclude-file <path>`; relative paths resolve
+    /// against the directory `backup` runs in.  A missing file is reported
+    /// by `Config::validate` as a warning.
+    #[serde(default)]
+    pub exclude_files: Vec<PathBuf>,
+
     /// If a directory contains a file with this name it is skipped entirely.
     ///
     /// Create an empty file called `ignore` (the default) inside any
     /// directory you never want backed up — build caches, scratch space, etc.
     #[serde(default = "default_exclude_marker")]
     pub exclude_if_present: String,
+
+    /// Skip directories tagged as caches under the `CACHEDIR.TAG` standard.
+    ///
+    /// Forwarded to rustic as `--exclude-caches`, which only honours a tag
+    /// file that starts with the standard signature.  This is in addition to
+    /// `exclude_if_present`: a directory is skipped if it has either the
+    /// custom marker or a valid `CACHEDIR.TAG`.  `--exclude-caches` on the
+    /// command line turns it on for a single run.
+    #[serde(default)]
+    pub exclude_caches: bool,
+
+    /// Skip files larger than this many megabytes.
+    ///
+    /// Forwarded to rustic as `--exclude-larger-than <N>M`.  Handy for keeping
+    /// VM images and database dumps out of a source-tree backup.  Omit to back
+    /// up files of any size.
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+
+    /// Skip files rustic cannot read instead of failing the backup.
+    ///
+    /// Forwarded to rustic as `--ignore-inaccessible`.  Useful for system
+    /// directories where even `--sudo` meets unreadable files, but the
+    /// trade-off is silent: the backup succeeds while leaving those files
+    /// out, so the snapshot may be incomplete.  Off by default.
+    #[serde(default)]
+    pub ignore_inaccessible: bool,
+
+    /// Skip rustic's scan of the sources before the backup starts.
+    ///
+    /// Forwarded to rustic as `--no-scan`.  The scan only sizes the job for
+    /// the progress display and ETA, so leaving it out gets an incremental
+    /// backup of a large, mostly unchanged tree going sooner; the price is
+    /// a progress bar without a total.  `--no-scan` on the command line
+    /// turns it on for a single run.
+    #[serde(default)]
+    pub no_scan: bool,
+
+    /// Free-form key/value annotations stored with every snapshot, e.g.
+    /// `project = "myapp"`.  Forwarded to rustic as one JSON object,
+    /// `--custom-metadata '{"project":"myapp"}'`; left out when empty.
+    #[serde(default)]
+    pub custom_metadata: BTreeMap<String, String>,
 }
 
 impl Default for BackupConfig {
     fn default() -> Self {
         Self {
             sources: vec![],
+            sources_exclude: vec![],
             compression: default_compression(),
             globs: default_globs(),
+            iglobs: vec![],
+            exclude_files: vec![],
             exclude_if_present: default_exclude_marker(),
+            exclude_caches: false,
+            max_file_size_mb: None,
+            ignore_inaccessible: false,
+            no_scan: false,
+            custom_metadata: BTreeMap::new(),
         }
     }
 }
 
+impl BackupConfig {
+    /// The `rustic backup` flags this section implies, sources excluded.
+    ///
+    /// `--set-compression` and `--exclude-if-present` always come first,
+    /// followed by `--exclude-caches`, `--exclude-larger-than <N>M`,
+    /// `--ignore-inaccessible`, `--no-scan`, `--custom-metadata <json>` and one
+    /// `--exclude-file` per entry where set.  Glob flags close the list:
+    /// `sources_exclude` as `--glob=!<path>`, then `globs`, then `iglobs`.
+    pub fn as_rustic_backup_flags(&self) -> Vec<String> {
+        let mut flags = vec![
+            "--set-compression".into(),
+            self.compression.to_string(),
+            "--exclude-if-present".into(),
+            self.exclude_if_present.clone(),
+        ];
+        if self.exclude_caches {
+            flags.push("--exclude-caches".into());
+        }
+        if let Some(mb) = self.max_file_size_mb {
+            flags.extend(["--exclude-larger-than".into(), format!("{mb}M")]);
+        }
+        if self.ignore_inaccessible {
+            flags.push("--ignore-inaccessible".into());
+        }
+        if self.no_scan {
+            flags.push("--no-scan".into());
+        }
+        if !self.custom_metadata.is_empty() {
+            flags.extend(["--custom-metadata".into(), self.custom_metadata_json()]);
+        }
+        for file in &self.exclude_files {
+            flags.extend(["--exclude-file".into(), file.display().to_string()]);
+        }
+        flags.extend(self.sources_exclude.iter().map(|p| format!("--glob=!{p}")));
+        flags.extend(self.globs.iter().map(|g| format!("--glob={g}")));
+        flags.extend(self.iglobs.iter().map(|g| format!("--iglob={g}")));
+        flags
+    }
+
+    /// `custom_metadata` as a compact JSON object, keys sorted.
+    pub fn custom_metadata_json(&self) -> String {
+        let object: serde_json::Map<String, serde_json::Value> = self
+            .custom_metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), value.as_str().into()))
+            .collect();
+        serde_json::Value::Object(object).to_string()
+    }
+}
+
 // ─── [retention] ──────────────────────────────────────────────────────────────
 
 /// How many snapshots to keep when pruning.
@@ -144,7 +416,7 @@ impl Default for BackupConfig {
 /// Passed directly to `rustic forget --prune`.  rustic selects the most
 /// recent snapshot within each window, so `daily = 2` keeps one
 /// snapshot from each of the last two calendar days that had a backup.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct RetentionConfig {
     /// Number of daily snapshots to retain.
     #[serde(default = "default_keep_daily")]
@@ -157,6 +429,52 @@ pub struct RetentionConfig {
     /// Number of monthly snapshots to retain.
     #[serde(default = "default_keep_monthly")]
     pub monthly: u32,
+
+    /// Number of most recent snapshots to retain regardless of when they
+    /// were taken.  Forwarded as `--keep-last`; `0` (the default) leaves the
+    /// flag out.
+    #[serde(default)]
+    pub keep_last: u32,
+
+    /// Snapshots carrying any of these tags are always kept, whatever their
+    /// age.  Forwarded to rustic as `--keep-tag <tag>`.
+    #[serde(default)]
+    pub keep_tags: Vec<String>,
+
+    /// Keep every snapshot younger than this duration, e.g. `"24h"` or
+    /// `"7d"`, on top of the windows above.  Forwarded to rustic as
+    /// `--keep-all-within <duration>`.
+    #[serde(default)]
+    pub keep_all_within: Option<String>,
+
+    /// Keep the last snapshot of each day younger than this duration,
+    /// whatever `daily` says.  Forwarded as `--keep-within-daily`.
+    #[serde(default)]
+    pub keep_within_daily: Option<String>,
+
+    /// Keep the last snapshot of each week younger than this duration, e.g.
+    /// `"1y"` for a rolling year of weeklies.  Forwarded as
+    /// `--keep-within-weekly`.
+    #[serde(default)]
+    pub keep_within_weekly: Option<String>,
+
+    /// Keep the last snapshot of each month younger than this duration.
+    /// Forwarded as `--keep-within-monthly`.
+    #[serde(default)]
+    pub keep_within_monthly: Option<String>,
+
+    /// Apply the policy separately to each group of snapshots sharing these
+    /// criteria, e.g. `"host"` or `"host,paths"`, so a repo shared by several
+    /// machines keeps every machine's newest snapshots.  Forwarded as
+    /// `--group-by <criteria>`; rustic's own grouping applies when unset.
+    #[serde(default)]
+    pub group_by: Option<String>,
+
+    /// Repack at most this many MiB per Compact stage, spreading a large
+    /// prune over several runs.  Forwarded to `rustic prune` as
+    /// `--max-repack <n>M`.
+    #[serde(default)]
+    pub max_repack_size_mb: Option<u64>,
 }
 
 impl Default for RetentionConfig {
@@ -165,7 +483,72 @@ impl Default for RetentionConfig {
             daily: default_keep_daily(),
             weekly: default_keep_weekly(),
             monthly: default_keep_monthly(),
+            keep_last: 0,
+            keep_tags: vec![],
+            keep_all_within: None,
+            keep_within_daily: None,
+            keep_within_weekly: None,
+            keep_within_monthly: None,
+            group_by: None,
+            max_repack_size_mb: None,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Returns `true` when no option keeps anything: every retention window
+    /// and `keep_last` are zero, and no `keep_tags` or `keep_*within*`
+    /// duration is set.
+    ///
+    /// Such a policy would tell `rustic forget` to keep *nothing*, deleting
+    /// every snapshot in the repository.  The pipeline treats it as "retention
+    /// disabled" and skips Forget/Compact instead.
+    pub const fn is_effectively_disabled(&self) -> bool {
+        self.keep_last == 0
+            && self.daily == 0
+            && self.weekly == 0
+            && self.monthly == 0
+            && self.keep_tags.is_empty()
+            && self.keep_all_within.is_none()
+            && self.keep_within_daily.is_none()
+            && self.keep_within_weekly.is_none()
+            && self.keep_within_monthly.is_none()
+    }
+
+    /// The `rustic forget` flags for this policy.
+    ///
+    /// `--keep-last`, `--keep-daily`, `--keep-weekly` and `--keep-monthly`
+    /// are left out when zero, then come `--keep-all-within`, the `--keep-within-*`
+    /// flags that are set, one `--keep-tag` per tag and finally `--group-by`.
+    pub fn as_rustic_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        for (flag, count) in [
+            ("--keep-last", self.keep_last),
+            ("--keep-daily", self.daily),
+            ("--keep-weekly", self.weekly),
+            ("--keep-monthly", self.monthly),
+        ] {
+            if count > 0 {
+                flags.extend([flag.into(), count.to_string()]);
+            }
         }
+        for (flag, within) in [
+            ("--keep-all-within", &self.keep_all_within),
+            ("--keep-within-daily", &self.keep_within_daily),
+            ("--keep-within-weekly", &self.keep_within_weekly),
+            ("--keep-within-monthly", &self.keep_within_monthly),
+        ] {
+            if let Some(within) = within {
+                flags.extend([flag.into(), within.clone()]);
+            }
+        }
+        for tag in &self.keep_tags {
+            flags.extend(["--keep-tag".into(), tag.clone()]);
+        }
+        if let Some(group_by) = &self.group_by {
+            flags.extend(["--group-by".into(), group_by.clone()]);
+        }
+        flags
     }
 }
 
@@ -174,25 +557,139 @@ impl Default for RetentionConfig {
 /// Optional NAS share mount step.
 ///
 /// When `share` is set, `backup` will mount the named NFS share before doing
-/// anything else.  The server and export path are resolved from the built-in
-/// share map in [`crate::mount`].  Omit the entire `[mount]` section (or
-/// omit `share`) to skip mounting.
+/// anything else.  The server and export path are resolved from `shares`,
+/// then the built-in share map in [`crate::mount`].  Omit the entire
+/// `[mount]` section (or omit `share`) to skip mounting.
 ///
 /// ```toml
 /// [mount]
 /// share = "new-backups"   # name of the NFS share to mount
 /// user  = "alice"         # optional; defaults to $USER / $LOGNAME
+/// mountpoint = "/Volumes/new-backups"  # optional; overrides /home/<user>/nfs/<share>
+/// timeout_secs = 30       # optional; kill a mount that has not finished
+///
+/// [mount.shares]          # optional; adds to (or replaces) built-in shares
+/// media = "media.lan:/export/media"
 /// ```
-#[derive(Debug, Deserialize, Serialize, Default)]
+///
+/// Cloud storage can be mounted with `rclone mount` instead; `share` then
+/// only names the mountpoint directory:
+///
+/// ```toml
+/// [mount]
+/// share  = "cloud-backups"
+/// method = { rclone = { remote = "gdrive" } }
+/// ```
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default)]
 pub struct MountConfig {
     /// Name of the NFS share to mount, e.g. `"new-backups"`.
     #[serde(default)]
     pub share: Option<String>,
 
     /// Username used to build the mountpoint path (`/home/<user>/nfs/<share>`).
-    /// Defaults to the `$USER` or `$LOGNAME` environment variable.
+    /// Defaults to the `$USER` or `$LOGNAME` environment variable.  Only
+    /// informational when `mountpoint` is set.
     #[serde(default)]
     pub user: Option<String>,
+
+    /// Mount here instead of `/home/<user>/nfs/<share>`, e.g. on macOS or
+    /// where home directories live elsewhere.
+    #[serde(default)]
+    pub mountpoint: Option<PathBuf>,
+
+    /// How the share is mounted.  Defaults to kernel NFS.
+    #[serde(default)]
+    pub method: MountMethod,
+
+    /// Kill the mount command if it has not finished after this many
+    /// seconds.  A hard NFS mount of an unreachable server otherwise hangs
+    /// the whole run.  Omit to wait indefinitely.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Extra share names and their NFS sources (`server:/export/path`).
+    /// Looked up before the built-in share map, so an entry here can also
+    /// point a built-in name at a different server.
+    #[serde(default)]
+    pub shares: BTreeMap<String, String>,
+
+    /// Check for an existing mount by comparing the mountpoint's device with
+    /// its parent's, instead of listing mounts with `doas mount`, which
+    /// some `doas.conf` rules do not permit.
+    #[serde(default)]
+    pub force_recheck: bool,
+}
+
+/// Mechanism used to mount `[mount].share`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MountMethod {
+    /// `doas mount -t nfs` with the source from the built-in share map.
+    #[default]
+    Nfs,
+
+    /// `rclone mount <remote>: <mountpoint> --daemon`.
+    Rclone {
+        /// rclone remote name, with or without the trailing `:`.
+        remote: String,
+    },
+}
+
+// ─── [cold_repo] ──────────────────────────────────────────────────────────────
+
+/// Write-seldom archive repository (e.g. on tape or Glacier-backed storage).
+///
+/// Only used when `[repo].cold_storage` is on.  On the first day of each
+/// month the pipeline copies the latest snapshot there with `rustic copy`,
+/// so the archive grows by one snapshot a month.
+///
+/// ```toml
+/// [repo]
+/// cold_storage = true
+///
+/// [cold_repo]
+/// path     = "/mnt/archive/rustic/myapp"
+/// password = "hunter2"  # optional; defaults to [repo].password
+/// ```
+///
+/// The cold repository is opened with `password`, or `[repo].password` when
+/// that is unset; `rustic copy --init` creates it on the first copy.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default)]
+pub struct ColdRepoConfig {
+    /// Filesystem path (or URI) of the archive repository.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Password of the archive; `None` opens it with `[repo].password`.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+// ─── [mirror] ─────────────────────────────────────────────────────────────────
+
+/// Replica repository maintained by `backup mirror`.
+///
+/// Each `backup mirror` runs the pipeline against `[repo]`, then copies
+/// every snapshot newer than the last one it mirrored to `dest_repo` with
+/// `rustic copy`.  Unlike `[cold_repo]`, which receives one snapshot a
+/// month, the replica ends up holding every snapshot the primary took.
+///
+/// ```toml
+/// [mirror]
+/// dest_repo     = "/mnt/offsite/rustic/myapp"
+/// dest_password = "hunter2"  # optional; defaults to [repo].password
+/// ```
+///
+/// `rustic copy --init` creates the replica on the first mirror.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default)]
+pub struct MirrorConfig {
+    /// Filesystem path (or URI) of the replica repository.
+    #[serde(default)]
+    pub dest_repo: Option<String>,
+
+    /// Password of the replica; `None` opens it with `[repo].password`.
+    #[serde(default)]
+    pub dest_password: Option<String>,
 }
 
 // ─── Defaults ─────────────────────────────────────────────────────────────────
@@ -201,6 +698,13 @@ pub struct MountConfig {
 // cannot call `Default::default()` for individual fields, only for whole
 // structs.
 
+/// Pack sizes (in MB) `Config::validate` accepts for `[repo]`.
+pub const PACK_SIZE_RANGE_MB: std::ops::RangeInclusive<u32> = 1..=2048;
+
+/// Arguments `rustic_base` already emits, which `[repo].extra_args` must not
+/// repeat.
+const REPO_ARGS: &[&str] = &["rustic", "-r", "--repository"];
+
 pub fn default_repo_path() -> String {
     "./.backup".into()
 }
@@ -231,8 +735,320 @@ pub const fn default_keep_monthly() -> u32 {
     1
 }
 
+// ─── Validation ───────────────────────────────────────────────────────────────
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The config cannot be used as-is; the pipeline refuses to run.
+    Error,
+    /// The config works but probably does not do what the user intended.
+    Warning,
+}
+
+/// A single problem found by [`Config::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Whether this issue blocks the pipeline.
+    pub severity: Severity,
+    /// Dotted path of the offending key, e.g. `"backup.max_file_size_mb"`.
+    pub field: String,
+    /// What is wrong, in plain language.
+    pub message: String,
+    /// How to fix it.
+    pub suggestion: String,
+}
+
+impl ValidationIssue {
+    fn error(field: &str, message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.into(),
+            message: message.into(),
+            suggestion: suggestion.into(),
+        }
+    }
+
+    fn warning(field: &str, message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.into(),
+            message: message.into(),
+            suggestion: suggestion.into(),
+        }
+    }
+
+    /// Returns `true` for [`Severity::Error`] issues.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+        };
+        write!(
+            f,
+            "{level}: {}: {} (hint: {})",
+            self.field, self.message, self.suggestion
+        )
+    }
+}
+
+impl Config {
+    /// Check the resolved config for values rustic would reject or that are
+    /// almost certainly mistakes.
+    ///
+    /// Returns every issue found rather than stopping at the first one, so the
+    /// user can fix them all in a single edit.  An empty `Vec` means the config
+    /// is good to go.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = self.zero_limit_issues();
+
+        for (field, value) in [
+            ("repo.pack_size_mb", self.repo.pack_size_mb),
+            ("repo.tree_pack_size_mb", self.repo.tree_pack_size_mb),
+        ] {
+            if let Some(mb) = value.filter(|mb| !PACK_SIZE_RANGE_MB.contains(mb)) {
+                issues.push(ValidationIssue::error(
+                    field,
+                    format!(
+                        "{mb} is outside {}–{} MB",
+                        PACK_SIZE_RANGE_MB.start(),
+                        PACK_SIZE_RANGE_MB.end()
+                    ),
+                    "pick a size in range, or remove the key to use rustic's default",
+                ));
+            }
+        }
+
+        if let Some(rest) = &self.repo.rest {
+            issues.extend(rest.validate());
+        }
+
+        for arg in &self.repo.extra_args {
+            if REPO_ARGS.contains(&arg.as_str()) {
+                issues.push(ValidationIssue::warning(
+                    "repo.extra_args",
+                    format!("'{arg}' is already passed by backup-rs"),
+                    "remove it; rustic and the repository come from [repo].path",
+                ));
+            }
+        }
+
+        if self.repo.cold_storage {
+            match self.cold_repo.path.as_deref() {
+                None | Some("") => issues.push(ValidationIssue::error(
+                    "cold_repo.path",
+                    "is required when [repo].cold_storage is on",
+                    "set [cold_repo].path, or remove cold_storage",
+                )),
+                Some(path) if path == self.repo.path => issues.push(ValidationIssue::error(
+                    "cold_repo.path",
+                    "is the same repository as [repo].path",
+                    "point it at a separate archive repository",
+                )),
+                Some(_) => {},
+            }
+        }
+
+        if self.mirror.dest_repo.as_deref() == Some(crate::runner::repo_location(self).as_str()) {
+            issues.push(ValidationIssue::error(
+                "mirror.dest_repo",
+                "is the same repository as [repo]",
+                "point it at a separate replica repository",
+            ));
+        }
+
+        for file in &self.backup.exclude_files {
+            if !file.exists() {
+                issues.push(ValidationIssue::warning(
+                    "backup.exclude_files",
+                    format!("'{}' does not exist", file.display()),
+                    "create the file or remove it from the list",
+                ));
+            }
+        }
+
+        let r = &self.retention;
+        for (field, within) in [
+            ("retention.keep_all_within", &r.keep_all_within),
+            ("retention.keep_within_daily", &r.keep_within_daily),
+            ("retention.keep_within_weekly", &r.keep_within_weekly),
+            ("retention.keep_within_monthly", &r.keep_within_monthly),
+        ] {
+            if let Some(within) = within
+                && !is_duration(within)
+            {
+                issues.push(ValidationIssue::error(
+                    field,
+                    format!("'{within}' is not a duration"),
+                    "use a number followed by a unit, e.g. \"24h\", \"7d\" or \"1y6M\"",
+                ));
+            }
+        }
+
+        if self.retention.is_effectively_disabled() {
+            issues.push(ValidationIssue::warning(
+                "retention",
+                "no retention option keeps anything, so Forget and Compact will be skipped",
+                "set at least one of keep_last/daily/weekly/monthly/keep_tags/keep_*within*, or \
+                 run with --no-prune",
+            ));
+        }
+
+        issues
+    }
+
+    /// Limits set to zero, which would stop the matching work before it
+    /// starts; omitting the key is how to lift the limit.
+    fn zero_limit_issues(&self) -> Vec<ValidationIssue> {
+        [
+            (
+                "stage_timeout_secs",
+                self.stage_timeout_secs,
+                "remove the key to let stages run without a time limit",
+            ),
+            (
+                "mount.timeout_secs",
+                self.mount.timeout_secs,
+                "remove the key to let the mount run without a time limit",
+            ),
+            (
+                "backup.max_file_size_mb",
+                self.backup.max_file_size_mb,
+                "remove the key to back up files of any size",
+            ),
+        ]
+        .into_iter()
+        .filter(|&(_, value, _)| value == Some(0))
+        .map(|(field, _, suggestion)| {
+            ValidationIssue::error(field, "must be greater than zero", suggestion)
+        })
+        .collect()
+    }
+}
+
+/// Units rustic accepts in a duration such as `--keep-all-within`.
+const DURATION_UNITS: &[&str] = &[
+    "s", "sec", "second", "seconds", "m", "min", "minute", "minutes", "h", "hr", "hour", "hours",
+    "d", "day", "days", "w", "week", "weeks", "M", "month", "months", "y", "year", "years",
+];
+
+/// `true` for one or more `<number><unit>` groups, optionally separated by
+/// spaces: `"24h"`, `"7d"`, `"1y 6M"`.
+fn is_duration(text: &str) -> bool {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return false;
+    }
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let after = &rest[digits..];
+        let letters = after.len()
+            - after
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .len();
+        if digits == 0 || !DURATION_UNITS.contains(&&after[..letters]) {
+            return false;
+        }
+        rest = after[letters..].trim_start();
+    }
+    true
+}
+
+// ─── CLI overrides ────────────────────────────────────────────────────────────
+
+/// What the pipeline will actually do once CLI flags are applied to the
+/// loaded [`Config`].
+///
+/// `commands::run::run` reads these switches instead of consulting `Cli`
+/// directly, so the precedence rules live in one place:
+/// [`Config::merge_cli_overrides`].
+///
+/// `--sudo` is not here: it changes how commands are spelled, not which
+/// stages run, and [`crate::runner::prefix`] applies it to every
+/// subcommand's argument builders, not just the pipeline's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct EffectiveConfig {
+    /// Run the Mount stage: `[mount].share` is set and `--no-mount` is absent.
+    pub mount: bool,
+    /// What the Mount stage does about a share that may already be mounted.
+    pub mounted: MountedPolicy,
+    /// Create a missing repository (`--init-if-missing`).
+    pub init_if_missing: bool,
+    /// Run the Check stage (`--no-check` absent).
+    pub check: bool,
+    /// Run the Verify stage: `--no-verify` absent and not a dry run, which
+    /// records no snapshot to verify.
+    pub verify: bool,
+    /// Run Forget and Compact (`--no-prune` absent).  Retention safety is
+    /// still enforced separately by the pipeline.
+    pub prune: bool,
+    /// Preview only (`--dry-run`).
+    pub dry_run: bool,
+    /// Per-stage time limit: `--timeout` wins over `stage_timeout_secs`.
+    pub stage_timeout: Option<Duration>,
+}
+
+/// How the Mount stage treats a share that is already mounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MountedPolicy {
+    /// Check first; report success without mounting again if it is.
+    #[default]
+    Reuse,
+    /// Check by device number instead of `doas mount`; mount if the
+    /// mountpoint is not a mount yet (`--force-mount`, or
+    /// `[mount].force_recheck`).
+    Force,
+    /// Check first; report the stage as skipped if it is
+    /// (`--skip-if-mounted`).
+    Skip,
+}
+
+impl Config {
+    /// Apply the run-shaping CLI flags to this config.
+    ///
+    /// A flag always beats the file; where the file has no say (e.g.
+    /// `--no-check`), the flag alone decides.
+    pub fn merge_cli_overrides(&self, cli: &Cli) -> EffectiveConfig {
+        EffectiveConfig {
+            mount: self.mount.share.is_some() && !cli.no_mount,
+            mounted: if cli.force_mount {
+                MountedPolicy::Force
+            } else if cli.skip_if_mounted {
+                MountedPolicy::Skip
+            } else if self.mount.force_recheck {
+                MountedPolicy::Force
+            } else {
+                MountedPolicy::Reuse
+            },
+            init_if_missing: cli.init_if_missing,
+            check: !cli.no_check,
+            verify: !cli.no_verify && !cli.dry_run,
+            prune: !cli.no_prune,
+            dry_run: cli.dry_run,
+            stage_timeout: cli
+                .timeout
+                .or(self.stage_timeout_secs)
+                .map(Duration::from_secs),
+        }
+    }
+}
+
 // ─── Loader ───────────────────────────────────────────────────────────────────
 
+/// Where the global config lives: `$XDG_CONFIG_HOME/backup.rs/config.toml`,
+/// usually `~/.config/backup.rs/config.toml`.  `None` when the platform has
+/// no config directory.
+pub fn global_config_path() -> Option<PathBuf> {
+    dirs_next::config_dir().map(|d| d.join("backup.rs").join("config.toml"))
+}
+
 /// Read and parse a `Config` from `path`.
 ///
 /// If the file does not exist, a warning is printed to `stderr` and a
@@ -277,8 +1093,9 @@ pub fn load_config(path: &Path) -> Result<Config> {
 /// distinguished from "explicitly set to a value".  Call [`PartialConfig::merge`]
 /// to layer a local partial over a global one, then [`PartialConfig::resolve`]
 /// to fill any remaining `None`s with defaults and produce a concrete [`Config`].
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct PartialConfig {
+    pub stage_timeout_secs: Option<u64>,
     #[serde(default)]
     pub repo: PartialRepoConfig,
     #[serde(default)]
@@ -287,36 +1104,201 @@ pub struct PartialConfig {
     pub retention: PartialRetentionConfig,
     #[serde(default)]
     pub mount: PartialMountConfig,
+    #[serde(default)]
+    pub cold_repo: PartialColdRepoConfig,
+    #[serde(default)]
+    pub mirror: PartialMirrorConfig,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct PartialRepoConfig {
     pub path: Option<String>,
     pub password: Option<String>,
+    pub password_env: Option<String>,
+    pub pack_size_mb: Option<u32>,
+    pub tree_pack_size_mb: Option<u32>,
+    pub extra_args: Option<Vec<String>>,
+    pub cold_storage: Option<bool>,
+    pub rest: Option<RestConfig>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct PartialBackupConfig {
     pub sources: Option<Vec<String>>,
+    pub sources_exclude: Option<Vec<String>>,
     pub compression: Option<u8>,
     pub globs: Option<Vec<String>>,
+    pub iglobs: Option<Vec<String>>,
+    pub exclude_files: Option<Vec<PathBuf>>,
     pub exclude_if_present: Option<String>,
+    pub exclude_caches: Option<bool>,
+    pub max_file_size_mb: Option<u64>,
+    pub ignore_inaccessible: Option<bool>,
+    pub no_scan: Option<bool>,
+    pub custom_metadata: Option<BTreeMap<String, String>>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct PartialRetentionConfig {
     pub daily: Option<u32>,
     pub weekly: Option<u32>,
     pub monthly: Option<u32>,
+    pub keep_last: Option<u32>,
+    pub keep_tags: Option<Vec<String>>,
+    pub keep_all_within: Option<String>,
+    pub keep_within_daily: Option<String>,
+    pub keep_within_weekly: Option<String>,
+    pub keep_within_monthly: Option<String>,
+    pub group_by: Option<String>,
+    pub max_repack_size_mb: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct PartialMountConfig {
     pub share: Option<String>,
     pub user: Option<String>,
+    pub mountpoint: Option<PathBuf>,
+    pub method: Option<MountMethod>,
+    pub timeout_secs: Option<u64>,
+    pub shares: Option<BTreeMap<String, String>>,
+    pub force_recheck: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct PartialColdRepoConfig {
+    pub path: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct PartialMirrorConfig {
+    pub dest_repo: Option<String>,
+    pub dest_password: Option<String>,
 }
 
 impl PartialConfig {
+    /// `true` when this partial sets nothing: every field is `None` and every
+    /// list is absent or empty.
+    ///
+    /// A file that parses to an empty partial resolves to the same defaults
+    /// as a missing one, which is rarely what its author intended.
+    // Destructures every field, so a new one cannot be missed; that is long.
+    #[allow(clippy::too_many_lines)]
+    pub fn is_empty(&self) -> bool {
+        fn blank<T>(list: Option<&Vec<T>>) -> bool {
+            list.is_none_or(Vec::is_empty)
+        }
+
+        // Destructure so a new field cannot be forgotten here.
+        let Self {
+            stage_timeout_secs,
+            repo:
+                PartialRepoConfig {
+                    path,
+                    password,
+                    password_env,
+                    pack_size_mb,
+                    tree_pack_size_mb,
+                    extra_args,
+                    cold_storage,
+                    rest,
+                },
+            backup:
+                PartialBackupConfig {
+                    sources,
+                    sources_exclude,
+                    compression,
+                    globs,
+                    iglobs,
+                    exclude_files,
+                    exclude_if_present,
+                    exclude_caches,
+                    max_file_size_mb,
+                    ignore_inaccessible,
+                    no_scan,
+                    custom_metadata,
+                },
+            retention:
+                PartialRetentionConfig {
+                    daily,
+                    weekly,
+                    monthly,
+                    keep_last,
+                    keep_tags,
+                    keep_all_within,
+                    keep_within_daily,
+                    keep_within_weekly,
+                    keep_within_monthly,
+                    group_by,
+                    max_repack_size_mb,
+                },
+            mount:
+                PartialMountConfig {
+                    share,
+                    user,
+                    mountpoint,
+                    method,
+                    timeout_secs,
+                    shares,
+                    force_recheck,
+                },
+            cold_repo:
+                PartialColdRepoConfig {
+                    path: cold_path,
+                    password: cold_password,
+                },
+            mirror:
+                PartialMirrorConfig {
+                    dest_repo,
+                    dest_password,
+                },
+        } = self;
+
+        stage_timeout_secs.is_none()
+            && path.is_none()
+            && password.is_none()
+            && password_env.is_none()
+            && pack_size_mb.is_none()
+            && tree_pack_size_mb.is_none()
+            && blank(extra_args.as_ref())
+            && cold_storage.is_none()
+            && rest.is_none()
+            && blank(sources.as_ref())
+            && blank(sources_exclude.as_ref())
+            && compression.is_none()
+            && blank(globs.as_ref())
+            && blank(iglobs.as_ref())
+            && blank(exclude_files.as_ref())
+            && exclude_if_present.is_none()
+            && exclude_caches.is_none()
+            && max_file_size_mb.is_none()
+            && ignore_inaccessible.is_none()
+            && no_scan.is_none()
+            && custom_metadata.as_ref().is_none_or(BTreeMap::is_empty)
+            && daily.is_none()
+            && weekly.is_none()
+            && monthly.is_none()
+            && keep_last.is_none()
+            && blank(keep_tags.as_ref())
+            && keep_all_within.is_none()
+            && keep_within_daily.is_none()
+            && keep_within_weekly.is_none()
+            && keep_within_monthly.is_none()
+            && group_by.is_none()
+            && max_repack_size_mb.is_none()
+            && share.is_none()
+            && user.is_none()
+            && mountpoint.is_none()
+            && method.is_none()
+            && timeout_secs.is_none()
+            && shares.as_ref().is_none_or(BTreeMap::is_empty)
+            && force_recheck.is_none()
+            && cold_path.is_none()
+            && cold_password.is_none()
+            && dest_repo.is_none()
+            && dest_password.is_none()
+    }
+
     /// Overlay `other` (local) on top of `self` (global).
     ///
     /// For each field, the local value wins if it is `Some`; otherwise the
@@ -324,27 +1306,84 @@ impl PartialConfig {
     /// still inherits `[mount]` from the global config.
     pub fn merge(self, other: Self) -> Self {
         Self {
+            stage_timeout_secs: other.stage_timeout_secs.or(self.stage_timeout_secs),
             repo: PartialRepoConfig {
                 path: other.repo.path.or(self.repo.path),
                 password: other.repo.password.or(self.repo.password),
+                password_env: other.repo.password_env.or(self.repo.password_env),
+                pack_size_mb: other.repo.pack_size_mb.or(self.repo.pack_size_mb),
+                tree_pack_size_mb: other.repo.tree_pack_size_mb.or(self.repo.tree_pack_size_mb),
+                extra_args: other.repo.extra_args.or(self.repo.extra_args),
+                cold_storage: other.repo.cold_storage.or(self.repo.cold_storage),
+                rest: other.repo.rest.or(self.repo.rest),
             },
             backup: PartialBackupConfig {
                 sources: other.backup.sources.or(self.backup.sources),
+                sources_exclude: other.backup.sources_exclude.or(self.backup.sources_exclude),
                 compression: other.backup.compression.or(self.backup.compression),
                 globs: other.backup.globs.or(self.backup.globs),
+                iglobs: other.backup.iglobs.or(self.backup.iglobs),
+                exclude_files: other.backup.exclude_files.or(self.backup.exclude_files),
                 exclude_if_present: other
                     .backup
                     .exclude_if_present
                     .or(self.backup.exclude_if_present),
+                exclude_caches: other.backup.exclude_caches.or(self.backup.exclude_caches),
+                max_file_size_mb: other
+                    .backup
+                    .max_file_size_mb
+                    .or(self.backup.max_file_size_mb),
+                ignore_inaccessible: other
+                    .backup
+                    .ignore_inaccessible
+                    .or(self.backup.ignore_inaccessible),
+                no_scan: other.backup.no_scan.or(self.backup.no_scan),
+                custom_metadata: other.backup.custom_metadata.or(self.backup.custom_metadata),
             },
             retention: PartialRetentionConfig {
                 daily: other.retention.daily.or(self.retention.daily),
                 weekly: other.retention.weekly.or(self.retention.weekly),
                 monthly: other.retention.monthly.or(self.retention.monthly),
+                keep_last: other.retention.keep_last.or(self.retention.keep_last),
+                keep_tags: other.retention.keep_tags.or(self.retention.keep_tags),
+                keep_all_within: other
+                    .retention
+                    .keep_all_within
+                    .or(self.retention.keep_all_within),
+                keep_within_daily: other
+                    .retention
+                    .keep_within_daily
+                    .or(self.retention.keep_within_daily),
+                keep_within_weekly: other
+                    .retention
+                    .keep_within_weekly
+                    .or(self.retention.keep_within_weekly),
+                keep_within_monthly: other
+                    .retention
+                    .keep_within_monthly
+                    .or(self.retention.keep_within_monthly),
+                group_by: other.retention.group_by.or(self.retention.group_by),
+                max_repack_size_mb: other
+                    .retention
+                    .max_repack_size_mb
+                    .or(self.retention.max_repack_size_mb),
             },
             mount: PartialMountConfig {
                 share: other.mount.share.or(self.mount.share),
                 user: other.mount.user.or(self.mount.user),
+                mountpoint: other.mount.mountpoint.or(self.mount.mountpoint),
+                method: other.mount.method.or(self.mount.method),
+                timeout_secs: other.mount.timeout_secs.or(self.mount.timeout_secs),
+                shares: other.mount.shares.or(self.mount.shares),
+                force_recheck: other.mount.force_recheck.or(self.mount.force_recheck),
+            },
+            cold_repo: PartialColdRepoConfig {
+                path: other.cold_repo.path.or(self.cold_repo.path),
+                password: other.cold_repo.password.or(self.cold_repo.password),
+            },
+            mirror: PartialMirrorConfig {
+                dest_repo: other.mirror.dest_repo.or(self.mirror.dest_repo),
+                dest_password: other.mirror.dest_password.or(self.mirror.dest_password),
             },
         }
     }
@@ -353,46 +1392,343 @@ impl PartialConfig {
     /// `None` fields with their default values.
     pub fn resolve(self) -> Config {
         Config {
+            stage_timeout_secs: self.stage_timeout_secs,
             repo: RepoConfig {
                 path: self.repo.path.unwrap_or_else(default_repo_path),
                 password: self.repo.password.unwrap_or_default(),
+                password_env: self.repo.password_env,
+                pack_size_mb: self.repo.pack_size_mb,
+                tree_pack_size_mb: self.repo.tree_pack_size_mb,
+                extra_args: self.repo.extra_args.unwrap_or_default(),
+                cold_storage: self.repo.cold_storage.unwrap_or_default(),
+                rest: self.repo.rest,
             },
             backup: BackupConfig {
                 sources: self.backup.sources.unwrap_or_default(),
+                sources_exclude: self.backup.sources_exclude.unwrap_or_default(),
                 compression: self.backup.compression.unwrap_or_else(default_compression),
                 globs: self.backup.globs.unwrap_or_else(default_globs),
+                iglobs: self.backup.iglobs.unwrap_or_default(),
+                exclude_files: self.backup.exclude_files.unwrap_or_default(),
                 exclude_if_present: self
                     .backup
                     .exclude_if_present
                     .unwrap_or_else(default_exclude_marker),
+                exclude_caches: self.backup.exclude_caches.unwrap_or_default(),
+                max_file_size_mb: self.backup.max_file_size_mb,
+                ignore_inaccessible: self.backup.ignore_inaccessible.unwrap_or_default(),
+                no_scan: self.backup.no_scan.unwrap_or_default(),
+                custom_metadata: self.backup.custom_metadata.unwrap_or_default(),
             },
             retention: RetentionConfig {
                 daily: self.retention.daily.unwrap_or_else(default_keep_daily),
                 weekly: self.retention.weekly.unwrap_or_else(default_keep_weekly),
                 monthly: self.retention.monthly.unwrap_or_else(default_keep_monthly),
+                keep_last: self.retention.keep_last.unwrap_or_default(),
+                keep_tags: self.retention.keep_tags.unwrap_or_default(),
+                keep_all_within: self.retention.keep_all_within,
+                keep_within_daily: self.retention.keep_within_daily,
+                keep_within_weekly: self.retention.keep_within_weekly,
+                keep_within_monthly: self.retention.keep_within_monthly,
+                group_by: self.retention.group_by,
+                max_repack_size_mb: self.retention.max_repack_size_mb,
             },
             mount: MountConfig {
                 share: self.mount.share,
                 user: self.mount.user,
+                mountpoint: self.mount.mountpoint,
+                method: self.mount.method.unwrap_or_default(),
+                timeout_secs: self.mount.timeout_secs,
+                shares: self.mount.shares.unwrap_or_default(),
+                force_recheck: self.mount.force_recheck.unwrap_or_default(),
+            },
+            cold_repo: ColdRepoConfig {
+                path: self.cold_repo.path,
+                password: self.cold_repo.password,
+            },
+            mirror: MirrorConfig {
+                dest_repo: self.mirror.dest_repo,
+                dest_password: self.mirror.dest_password,
             },
         }
     }
+
+    /// Render as TOML with every set field active and every `None` field as a
+    /// commented-out `# key = value` line.
+    ///
+    /// The commented value is the field's default, or an example for
+    /// settings that have none (`password_env`, `[mount].share`, …).  Fields
+    /// keep [`Config`]'s order and every section header is written, so the
+    /// output doubles as a reference for the options left unset.
+    #[allow(dead_code)]
+    pub fn to_commented_toml(&self) -> Result<String> {
+        let set = to_document(self)?;
+        let reference = to_document(&commented_reference())?;
+
+        let mut out = toml_edit::DocumentMut::new();
+        let mut pending = String::new();
+        fill_commented(
+            out.as_table_mut(),
+            set.as_table(),
+            reference.as_table(),
+            "",
+            &mut pending,
+        );
+        out.set_trailing(pending);
+        Ok(out.to_string().trim_start_matches('\n').to_string())
+    }
+
+    /// Render every resolved field whose value differs between `self` and
+    /// `other`, as coloured `- key = old` / `+ key = new` lines.
+    ///
+    /// Both sides are resolved first, so a key one file omits and the other
+    /// sets to its default is not a difference.  A field only one side sets
+    /// gets only that side's line.  Identical configs render as `""`.
+    pub fn display_diff(&self, other: &Self) -> Result<String> {
+        let left = flatten(&to_document(&self.clone().resolve())?);
+        let right = flatten(&to_document(&other.clone().resolve())?);
+        let lookup = |side: &[(String, String)], key: &str| {
+            side.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+        };
+
+        let mut out = String::new();
+        let extra = right.iter().filter(|(key, _)| lookup(&left, key).is_none());
+        for (key, _) in left.iter().chain(extra) {
+            let (old, new) = (lookup(&left, key), lookup(&right, key));
+            if old == new {
+                continue;
+            }
+            if let Some(old) = old {
+                let _ = writeln!(out, "  {}", style(format!("- {key} = {old}")).red());
+            }
+            if let Some(new) = new {
+                let _ = writeln!(out, "  {}", style(format!("+ {key} = {new}")).green());
+            }
+        }
+        Ok(out)
+    }
 }
 
-/// Parse a TOML file at `path` into a [`PartialConfig`].
-///
-/// Returns:
-/// - `Ok(Some(partial))` — file exists and parsed successfully
-/// - `Ok(None)`          — file does not exist (not an error)
-/// - `Err(_)`            — file exists but could not be read or parsed
-pub fn parse_partial(path: &Path) -> Result<Option<PartialConfig>> {
-    if !path.exists() {
-        return Ok(None);
+/// Every leaf of `doc` as a `(dotted.key, value)` pair, in document order.
+fn flatten(doc: &toml_edit::DocumentMut) -> Vec<(String, String)> {
+    fn walk(table: &toml_edit::Table, prefix: &str, out: &mut Vec<(String, String)>) {
+        for (key, item) in table {
+            let path = if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            if let Some(sub) = item.as_table() {
+                walk(sub, &path, out);
+            } else if let Some(value) = item.as_value() {
+                out.push((path, value.to_string().trim().to_string()));
+            }
+        }
     }
-    let text =
-        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
-    let partial = toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
-    Ok(Some(partial))
+    let mut out = Vec::new();
+    walk(doc.as_table(), "", &mut out);
+    out
+}
+
+// ─── Commented TOML ───────────────────────────────────────────────────────────
+
+/// The values [`PartialConfig::to_commented_toml`] shows for unset fields:
+/// the defaults, plus examples for optional settings.
+fn commented_reference() -> Config {
+    let mut cfg = Config {
+        stage_timeout_secs: Some(3600),
+        ..Config::default()
+    };
+    cfg.repo.password_env = Some("BACKUP_PASSWORD".into());
+    cfg.repo.pack_size_mb = Some(128);
+    cfg.repo.tree_pack_size_mb = Some(32);
+    cfg.repo.extra_args = vec!["--no-cache".into()];
+    cfg.repo.rest = Some(RestConfig {
+        url: "http://nas.lan:8000/".into(),
+        username: Some("alice".into()),
+        password: None,
+    });
+    cfg.backup.max_file_size_mb = Some(512);
+    cfg.backup.custom_metadata = BTreeMap::from([("project".into(), "myapp".into())]);
+    cfg.retention.keep_all_within = Some("24h".into());
+    cfg.retention.keep_within_weekly = Some("1y".into());
+    cfg.retention.group_by = Some("host".into());
+    cfg.retention.max_repack_size_mb = Some(10_240);
+    cfg.mount.share = Some("new-backups".into());
+    cfg.mount.user = Some("alice".into());
+    cfg.mount.mountpoint = Some("/Volumes/new-backups".into());
+    cfg.mount.timeout_secs = Some(30);
+    cfg.mount.shares = BTreeMap::from([("media".into(), "media.lan:/export/media".into())]);
+    cfg.cold_repo.path = Some("/mnt/archive/rustic/myapp".into());
+    cfg.cold_repo.password = Some(String::new());
+    cfg.mirror.dest_repo = Some("/mnt/offsite/rustic/myapp".into());
+    cfg.mirror.dest_password = Some(String::new());
+    cfg
+}
+
+fn to_document<T: Serialize>(value: &T) -> Result<toml_edit::DocumentMut> {
+    let text = toml::to_string(value).context("serialising config")?;
+    text.parse().context("re-parsing serialised config")
+}
+
+/// Copy `set` into `out` in `reference`'s key order, turning every key that
+/// only `reference` has into a `# key = value` line.
+///
+/// Comment lines collect in `pending` and are emitted in front of the next
+/// active key or section header, or by the caller at the end of the file.
+/// `path` is the dotted name of the table being filled, `""` at the root.
+fn fill_commented(
+    out: &mut toml_edit::Table,
+    set: &toml_edit::Table,
+    reference: &toml_edit::Table,
+    path: &str,
+    pending: &mut String,
+) {
+    use toml_edit::{Item, Table};
+
+    let extra = set.iter().filter(|(key, _)| !reference.contains_key(key));
+    for (key, _) in reference.iter().chain(extra) {
+        let set_item = set.get(key);
+        let ref_item = reference.get(key);
+        let is_section =
+            set_item.map_or_else(|| ref_item.is_some_and(Item::is_table), Item::is_table);
+
+        if is_section {
+            let empty = Table::new();
+            let sub_path = if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{path}.{key}")
+            };
+            if set_item.is_none() && !path.is_empty() {
+                // A nested section nothing sets (e.g. `[mount.shares]`) is
+                // commented out header and all, so it parses back as unset.
+                let _ = writeln!(pending, "\n# [{sub_path}]");
+                fill_commented(
+                    &mut Table::new(),
+                    &empty,
+                    ref_item.and_then(Item::as_table).unwrap_or(&empty),
+                    &sub_path,
+                    pending,
+                );
+                continue;
+            }
+            let mut sub = Table::new();
+            // A section only `set` has (e.g. `[mount.method.rclone]`) needs
+            // no header of its own.
+            sub.set_implicit(!ref_item.is_some_and(Item::is_table));
+            sub.decor_mut()
+                .set_prefix(format!("{}\n", std::mem::take(pending)));
+            fill_commented(
+                &mut sub,
+                set_item.and_then(Item::as_table).unwrap_or(&empty),
+                ref_item.and_then(Item::as_table).unwrap_or(&empty),
+                &sub_path,
+                pending,
+            );
+            out.insert(key, Item::Table(sub));
+        } else if let Some(item) = set_item {
+            out.insert(key, item.clone());
+            if let Some(mut k) = out.key_mut(key) {
+                k.leaf_decor_mut().set_prefix(std::mem::take(pending));
+            }
+        } else if let Some(item) = ref_item {
+            let _ = writeln!(pending, "# {key} = {}", item.to_string().trim());
+        }
+    }
+}
+
+impl Config {
+    /// Resolve `partial` into a [`Config`], then apply environment-sourced
+    /// fields.
+    ///
+    /// Currently this means `[repo].password_env`: when set, the named
+    /// variable must exist and its value replaces `[repo].password`.  Call
+    /// [`load_dotenv`] first so variables from a `.env` file are visible.
+    pub fn from_env(partial: PartialConfig) -> Result<Self> {
+        let mut cfg = partial.resolve();
+        if let Some(ref var) = cfg.repo.password_env {
+            cfg.repo.password = std::env::var(var).with_context(|| {
+                format!("[repo].password_env is \"{var}\" but ${var} is not set")
+            })?;
+        }
+        Ok(cfg)
+    }
+
+    /// Render as TOML with only the fields that differ from
+    /// [`Config::default`].
+    ///
+    /// Fields keep [`Config`]'s order.  Sections left with no differing field
+    /// are dropped, so a default config renders as `""`.  Loading the output
+    /// as a `backup.toml` gives back an equal config.
+    pub fn to_minimal_toml(&self) -> Result<String> {
+        let mut value = toml::Value::try_from(self).context("serialising config")?;
+        let default = toml::Value::try_from(Self::default()).context("serialising config")?;
+        if let (Some(table), Some(default)) = (value.as_table_mut(), default.as_table()) {
+            strip_defaults(table, default);
+        }
+        toml::to_string_pretty(&value).context("serialising config")
+    }
+}
+
+/// Remove every entry of `table` equal to the same key in `default`,
+/// recursing into sub-tables and dropping those left empty.
+fn strip_defaults(table: &mut toml::Table, default: &toml::Table) {
+    table.retain(|key, value| match (value, default.get(key)) {
+        (toml::Value::Table(sub), Some(toml::Value::Table(default))) => {
+            strip_defaults(sub, default);
+            !sub.is_empty()
+        },
+        (value, default) => default != Some(&*value),
+    });
+}
+
+/// The first line of `reader`, without its line ending, for
+/// `--stdin-password`.
+///
+/// An empty line is an empty password (an unencrypted repository); no line
+/// at all is an error, so a forgotten pipe does not silently mean "".
+pub fn read_password_line(mut reader: impl std::io::BufRead) -> Result<String> {
+    let mut line = String::new();
+    if reader
+        .read_line(&mut line)
+        .context("reading the password from stdin")?
+        == 0
+    {
+        anyhow::bail!("--stdin-password: stdin is empty");
+    }
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Load `.env` from the directory containing `config_path`, if present.
+///
+/// Variables already set in the process environment win over the file, so
+/// an explicit `BACKUP_PASSWORD=… backup` still takes effect.  Returns the
+/// path that was loaded, or `None` when there is no `.env` file.
+pub fn load_dotenv(config_path: &Path) -> Result<Option<std::path::PathBuf>> {
+    let dir = config_path.parent().unwrap_or_else(|| Path::new(""));
+    let path = dir.join(".env");
+    if !path.exists() {
+        return Ok(None);
+    }
+    dotenvy::from_path(&path).with_context(|| format!("loading {}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// Parse a TOML file at `path` into a [`PartialConfig`].
+///
+/// Returns:
+/// - `Ok(Some(partial))` — file exists and parsed successfully
+/// - `Ok(None)`          — file does not exist (not an error)
+/// - `Err(_)`            — file exists but could not be read or parsed
+pub fn parse_partial(path: &Path) -> Result<Option<PartialConfig>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let partial = toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(Some(partial))
 }
 
 // ─── Tests ────────────────────────────────────────────────────────────────────
@@ -448,11 +1784,151 @@ mod tests {
         );
     }
 
+    #[test]
+    fn retention_flags_for_default_policy() {
+        assert_eq!(RetentionConfig::default().as_rustic_flags(), [
+            "--keep-daily",
+            "2",
+            "--keep-weekly",
+            "1",
+            "--keep-monthly",
+            "1"
+        ]);
+    }
+
+    #[test]
+    fn retention_flags_skip_zero_windows() {
+        let retention = RetentionConfig {
+            daily: 0,
+            weekly: 4,
+            monthly: 0,
+            ..RetentionConfig::default()
+        };
+        assert_eq!(retention.as_rustic_flags(), ["--keep-weekly", "4"]);
+    }
+
+    #[test]
+    fn all_zero_retention_has_no_keep_count_flags() {
+        let retention = RetentionConfig {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+            ..RetentionConfig::default()
+        };
+        assert!(retention.as_rustic_flags().is_empty());
+
+        let retention = RetentionConfig {
+            keep_tags: vec!["release".into()],
+            ..retention
+        };
+        assert_eq!(retention.as_rustic_flags(), ["--keep-tag", "release"]);
+    }
+
+    #[test]
+    fn retention_flags_put_within_windows_before_tags() {
+        let retention = RetentionConfig {
+            keep_tags: vec!["pin".into()],
+            keep_within_weekly: Some("1y".into()),
+            keep_all_within: Some("24h".into()),
+            ..RetentionConfig::default()
+        };
+        let flags = retention.as_rustic_flags();
+        assert_eq!(flags[6..], [
+            "--keep-all-within",
+            "24h",
+            "--keep-within-weekly",
+            "1y",
+            "--keep-tag",
+            "pin"
+        ]);
+    }
+
+    #[test]
+    fn retention_flags_end_with_group_by() {
+        let retention = RetentionConfig {
+            keep_tags: vec!["pin".into()],
+            group_by: Some("host".into()),
+            ..RetentionConfig::default()
+        };
+        let flags = retention.as_rustic_flags();
+        assert_eq!(flags[flags.len() - 4..], [
+            "--keep-tag",
+            "pin",
+            "--group-by",
+            "host"
+        ]);
+        assert!(
+            !RetentionConfig::default()
+                .as_rustic_flags()
+                .contains(&"--group-by".into())
+        );
+    }
+
+    #[test]
+    fn backup_flags_for_default_section() {
+        let flags = BackupConfig::default().as_rustic_backup_flags();
+        assert_eq!(flags[..4], [
+            "--set-compression",
+            "3",
+            "--exclude-if-present",
+            "ignore"
+        ]);
+        let globs: Vec<String> = default_globs()
+            .iter()
+            .map(|g| format!("--glob={g}"))
+            .collect();
+        assert_eq!(flags[4..], globs[..]);
+    }
+
+    #[test]
+    fn backup_flags_cover_optional_settings_in_order() {
+        let backup = BackupConfig {
+            sources: vec!["/ignored".into()],
+            sources_exclude: vec!["/home/a/Downloads".into()],
+            globs: vec!["!*.iso".into()],
+            iglobs: vec!["!*.tmp".into()],
+            exclude_files: vec![PathBuf::from(".backupignore")],
+            exclude_caches: true,
+            max_file_size_mb: Some(100),
+            ignore_inaccessible: true,
+            no_scan: true,
+            ..BackupConfig::default()
+        };
+        let flags = backup.as_rustic_backup_flags();
+        assert_eq!(flags[4..], [
+            "--exclude-caches",
+            "--exclude-larger-than",
+            "100M",
+            "--ignore-inaccessible",
+            "--no-scan",
+            "--exclude-file",
+            ".backupignore",
+            "--glob=!/home/a/Downloads",
+            "--glob=!*.iso",
+            "--iglob=!*.tmp",
+        ]);
+        assert!(!flags.contains(&"/ignored".to_string()));
+    }
+
     #[test]
     fn default_mount_is_none() {
         let m = MountConfig::default();
         assert!(m.share.is_none());
         assert!(m.user.is_none());
+        assert_eq!(m.method, MountMethod::Nfs);
+    }
+
+    #[test]
+    fn mount_method_parses_nfs_and_rclone() {
+        let nfs: MountConfig = toml::from_str("share = \"isos\"\nmethod = \"nfs\"").unwrap();
+        assert_eq!(nfs.method, MountMethod::Nfs);
+
+        let rclone: MountConfig =
+            toml::from_str("share = \"cloud\"\nmethod = { rclone = { remote = \"gdrive\" } }")
+                .unwrap();
+        assert_eq!(rclone.method, MountMethod::Rclone {
+            remote: "gdrive".into()
+        });
     }
 
     // ── Round-trip serialisation ──────────────────────────────────────────────
@@ -460,40 +1936,156 @@ mod tests {
     #[test]
     fn config_roundtrips_through_toml() {
         let original = Config {
+            stage_timeout_secs: Some(600),
             repo: RepoConfig {
                 path: "/tmp/test-repo".into(),
                 password: "hunter2".into(),
+                password_env: Some("BACKUP_PASSWORD".into()),
+                pack_size_mb: Some(128),
+                tree_pack_size_mb: Some(8),
+                extra_args: vec!["--no-cache".into()],
+                cold_storage: true,
+                rest: Some(RestConfig {
+                    url: "http://nas.lan:8000/".into(),
+                    username: Some("alice".into()),
+                    password: None,
+                }),
             },
             backup: BackupConfig {
                 sources: vec!["/home/alice/projects".into()],
+                sources_exclude: vec!["/home/alice/projects/scratch".into()],
                 compression: 6,
                 globs: vec!["!**/.git".into(), "!**/node_modules/".into()],
+                iglobs: vec!["!**/*.tmp".into()],
+                exclude_files: vec![".backupignore".into()],
                 exclude_if_present: "ignore".into(),
+                exclude_caches: true,
+                max_file_size_mb: Some(512),
+                ignore_inaccessible: true,
+                no_scan: true,
+                custom_metadata: BTreeMap::from([
+                    ("project".into(), "myapp".into()),
+                    ("owner".into(), "alice".into()),
+                ]),
             },
             retention: RetentionConfig {
                 daily: 7,
                 weekly: 4,
                 monthly: 3,
+                keep_last: 5,
+                keep_tags: vec!["release".into(), "pre-upgrade".into()],
+                keep_all_within: Some("7d".into()),
+                keep_within_daily: Some("14d".into()),
+                keep_within_weekly: Some("3M".into()),
+                keep_within_monthly: Some("2y".into()),
+                group_by: Some("host,paths".into()),
+                max_repack_size_mb: Some(2048),
             },
             mount: MountConfig {
-                share: Some("new-backups".into()),
+                share: Some("cloud-backups".into()),
                 user: Some("alice".into()),
+                mountpoint: Some("/Volumes/cloud-backups".into()),
+                method: MountMethod::Rclone {
+                    remote: "gdrive".into(),
+                },
+                timeout_secs: Some(45),
+                shares: BTreeMap::from([("media".into(), "media.lan:/export/media".into())]),
+                force_recheck: true,
+            },
+            cold_repo: ColdRepoConfig {
+                path: Some("/mnt/archive/rustic/test".into()),
+                password: Some("hunter4".into()),
+            },
+            mirror: MirrorConfig {
+                dest_repo: Some("/mnt/offsite/rustic/test".into()),
+                dest_password: Some("hunter3".into()),
             },
         };
 
         let toml_str = toml::to_string(&original).expect("serialisation failed");
         let recovered: Config = toml::from_str(&toml_str).expect("deserialisation failed");
 
+        assert_eq!(recovered.stage_timeout_secs, original.stage_timeout_secs);
         assert_eq!(recovered.repo.path, original.repo.path);
         assert_eq!(recovered.repo.password, original.repo.password);
+        assert_eq!(recovered.repo.password_env, original.repo.password_env);
+        assert_eq!(recovered.repo.pack_size_mb, original.repo.pack_size_mb);
+        assert_eq!(
+            recovered.repo.tree_pack_size_mb,
+            original.repo.tree_pack_size_mb
+        );
+        assert_eq!(recovered.repo.extra_args, original.repo.extra_args);
+        assert_eq!(recovered.repo.cold_storage, original.repo.cold_storage);
+        assert_eq!(recovered.repo.rest, original.repo.rest);
         assert_eq!(recovered.backup.sources, original.backup.sources);
+        assert_eq!(
+            recovered.backup.sources_exclude,
+            original.backup.sources_exclude
+        );
         assert_eq!(recovered.backup.compression, original.backup.compression);
         assert_eq!(recovered.backup.globs, original.backup.globs);
+        assert_eq!(recovered.backup.iglobs, original.backup.iglobs);
+        assert_eq!(
+            recovered.backup.exclude_files,
+            original.backup.exclude_files
+        );
+        assert_eq!(
+            recovered.backup.exclude_caches,
+            original.backup.exclude_caches
+        );
+        assert_eq!(
+            recovered.backup.max_file_size_mb,
+            original.backup.max_file_size_mb
+        );
+        assert_eq!(
+            recovered.backup.ignore_inaccessible,
+            original.backup.ignore_inaccessible
+        );
+        assert_eq!(recovered.backup.no_scan, original.backup.no_scan);
+        assert_eq!(
+            recovered.backup.custom_metadata,
+            original.backup.custom_metadata
+        );
         assert_eq!(recovered.retention.daily, original.retention.daily);
         assert_eq!(recovered.retention.weekly, original.retention.weekly);
         assert_eq!(recovered.retention.monthly, original.retention.monthly);
+        assert_eq!(recovered.retention.keep_last, original.retention.keep_last);
+        assert_eq!(recovered.retention.keep_tags, original.retention.keep_tags);
+        assert_eq!(
+            recovered.retention.keep_all_within,
+            original.retention.keep_all_within
+        );
+        assert_eq!(
+            recovered.retention.keep_within_daily,
+            original.retention.keep_within_daily
+        );
+        assert_eq!(
+            recovered.retention.keep_within_weekly,
+            original.retention.keep_within_weekly
+        );
+        assert_eq!(
+            recovered.retention.keep_within_monthly,
+            original.retention.keep_within_monthly
+        );
+        assert_eq!(recovered.retention.group_by, original.retention.group_by);
+        assert_eq!(
+            recovered.retention.max_repack_size_mb,
+            original.retention.max_repack_size_mb
+        );
         assert_eq!(recovered.mount.share, original.mount.share);
         assert_eq!(recovered.mount.user, original.mount.user);
+        assert_eq!(recovered.mount.mountpoint, original.mount.mountpoint);
+        assert_eq!(recovered.mount.method, original.mount.method);
+        assert_eq!(recovered.mount.timeout_secs, original.mount.timeout_secs);
+        assert_eq!(recovered.mount.shares, original.mount.shares);
+        assert_eq!(recovered.mount.force_recheck, original.mount.force_recheck);
+        assert_eq!(recovered.cold_repo.path, original.cold_repo.path);
+        assert_eq!(recovered.cold_repo.password, original.cold_repo.password);
+        assert_eq!(recovered.mirror.dest_repo, original.mirror.dest_repo);
+        assert_eq!(
+            recovered.mirror.dest_password,
+            original.mirror.dest_password
+        );
     }
 
     #[test]
@@ -511,12 +2103,678 @@ mod tests {
         assert!(cfg.mount.share.is_none());
     }
 
+    #[test]
+    fn max_repack_size_mb_roundtrips_through_toml() {
+        let cfg: Config = toml::from_str("[retention]\nmax_repack_size_mb = 2048\n").unwrap();
+        assert_eq!(cfg.retention.max_repack_size_mb, Some(2048));
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(recovered.retention.max_repack_size_mb, Some(2048));
+        assert_eq!(Config::default().retention.max_repack_size_mb, None);
+    }
+
+    #[test]
+    fn group_by_roundtrips_through_toml() {
+        let cfg: Config = toml::from_str("[retention]\ngroup_by = \"host,tags\"\n").unwrap();
+        assert_eq!(cfg.retention.group_by.as_deref(), Some("host,tags"));
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(recovered.retention.group_by.as_deref(), Some("host,tags"));
+        assert_eq!(Config::default().retention.group_by, None);
+    }
+
+    #[test]
+    fn max_file_size_mb_roundtrips_through_toml() {
+        let cfg: Config = toml::from_str("[backup]\nsources = []\nmax_file_size_mb = 100\n")
+            .expect("parse failed");
+        assert_eq!(cfg.backup.max_file_size_mb, Some(100));
+
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(recovered.backup.max_file_size_mb, Some(100));
+    }
+
+    #[test]
+    fn max_file_size_mb_defaults_to_none() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert!(cfg.backup.max_file_size_mb.is_none());
+    }
+
+    #[test]
+    fn custom_metadata_roundtrips_through_toml() {
+        assert!(Config::default().backup.custom_metadata.is_empty());
+        let cfg: Config = toml::from_str(
+            r#"
+            [backup]
+            sources = []
+
+            [backup.custom_metadata]
+            project = "myapp"
+            "owner name" = "Alice \"Al\" Smith"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.backup.custom_metadata["project"], "myapp");
+        assert_eq!(
+            cfg.backup.custom_metadata["owner name"],
+            r#"Alice "Al" Smith"#
+        );
+
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(recovered.backup.custom_metadata, cfg.backup.custom_metadata);
+    }
+
+    #[test]
+    fn custom_metadata_flag_carries_valid_json() {
+        let backup = BackupConfig {
+            custom_metadata: BTreeMap::from([
+                ("project".into(), "myapp".into()),
+                ("note".into(), r#"quotes " and \ backslashes"#.into()),
+            ]),
+            ..BackupConfig::default()
+        };
+        let flags = backup.as_rustic_backup_flags();
+        let at = flags.iter().position(|f| f == "--custom-metadata").unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&flags[at + 1]).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!({"project": "myapp", "note": r#"quotes " and \ backslashes"#})
+        );
+        assert!(
+            !BackupConfig::default()
+                .as_rustic_backup_flags()
+                .contains(&"--custom-metadata".to_string()),
+            "an empty map adds no flag"
+        );
+    }
+
+    #[test]
+    fn no_scan_defaults_to_false_and_roundtrips() {
+        assert!(!Config::default().backup.no_scan);
+        let cfg: Config = toml::from_str("[backup]\nsources = []\nno_scan = true\n").unwrap();
+        assert!(cfg.backup.no_scan);
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert!(recovered.backup.no_scan);
+    }
+
+    #[test]
+    fn ignore_inaccessible_defaults_to_false_and_roundtrips() {
+        assert!(!Config::default().backup.ignore_inaccessible);
+        let cfg: Config =
+            toml::from_str("[backup]\nsources = []\nignore_inaccessible = true\n").unwrap();
+        assert!(cfg.backup.ignore_inaccessible);
+
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert!(recovered.backup.ignore_inaccessible);
+    }
+
+    // ── to_minimal_toml ───────────────────────────────────────────────────────
+
+    #[test]
+    fn minimal_toml_of_default_config_is_empty() {
+        assert_eq!(Config::default().to_minimal_toml().unwrap(), "");
+    }
+
+    #[test]
+    fn minimal_toml_keeps_only_non_defaults() {
+        let mut cfg = Config::default();
+        cfg.repo.path = "/srv/repo".into();
+        cfg.backup.compression = 9;
+        cfg.retention.keep_tags = vec!["release".into()];
+        cfg.mount.method = MountMethod::Rclone {
+            remote: "gdrive".into(),
+        };
+        let text = cfg.to_minimal_toml().unwrap();
+
+        assert!(text.contains("path = \"/srv/repo\""), "{text}");
+        assert!(text.contains("compression = 9"));
+        assert!(text.contains("keep_tags = [\"release\"]"));
+        assert!(text.contains("remote = \"gdrive\""));
+        for default in [
+            "password",
+            "globs",
+            "exclude_if_present",
+            "daily",
+            "[cold_repo]",
+        ] {
+            assert!(!text.contains(default), "{default} is a default:\n{text}");
+        }
+
+        assert!(
+            text.find("[repo]") < text.find("[backup]"),
+            "sections keep Config's order:\n{text}"
+        );
+        let parsed = toml::from_str::<PartialConfig>(&text).unwrap().resolve();
+        assert_eq!(parsed.to_minimal_toml().unwrap(), text);
+    }
+
+    #[test]
+    fn iglobs_parse_from_toml() {
+        let cfg: Config =
+            toml::from_str("[backup]\nsources = []\niglobs = [\"!**/*.TMP\", \"!**/thumbs.db\"]\n")
+                .unwrap();
+        assert_eq!(cfg.backup.iglobs, ["!**/*.TMP", "!**/thumbs.db"]);
+    }
+
+    #[test]
+    fn iglobs_default_to_empty() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert!(cfg.backup.iglobs.is_empty());
+    }
+
+    #[test]
+    fn iglobs_serialise_to_toml() {
+        let cfg = Config {
+            backup: BackupConfig {
+                iglobs: vec!["!**/*.bak".into()],
+                ..BackupConfig::default()
+            },
+            ..Config::default()
+        };
+        let text = toml::to_string(&cfg).unwrap();
+        assert!(text.contains("iglobs = [\"!**/*.bak\"]"), "got:\n{text}");
+    }
+
+    #[test]
+    fn exclude_caches_defaults_to_false() {
+        let cfg: Config = toml::from_str("[backup]\nsources = []\n").unwrap();
+        assert!(!cfg.backup.exclude_caches);
+    }
+
+    #[test]
+    fn exclude_caches_roundtrips_through_toml() {
+        for value in [true, false] {
+            let cfg: Config = toml::from_str(&format!(
+                "[backup]\nsources = []\nexclude_caches = {value}\n"
+            ))
+            .unwrap();
+            assert_eq!(cfg.backup.exclude_caches, value);
+
+            let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+            assert_eq!(recovered.backup.exclude_caches, value);
+        }
+    }
+
+    #[test]
+    fn local_exclude_caches_false_overrides_global_true() {
+        let global: PartialConfig = toml::from_str("[backup]\nexclude_caches = true").unwrap();
+        let local: PartialConfig = toml::from_str("[backup]\nexclude_caches = false").unwrap();
+        assert!(!global.merge(local).resolve().backup.exclude_caches);
+    }
+
+    #[test]
+    fn sources_exclude_parse_from_toml() {
+        let cfg: Config = toml::from_str(
+            "[backup]\nsources = [\"/home/alice\"]\nsources_exclude = [\"/home/alice/Downloads\"]\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.backup.sources_exclude, ["/home/alice/Downloads"]);
+    }
+
+    #[test]
+    fn sources_exclude_default_to_empty() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert!(cfg.backup.sources_exclude.is_empty());
+    }
+
+    #[test]
+    fn exclude_files_parse_from_toml() {
+        let cfg: Config = toml::from_str(
+            "[backup]\nsources = []\nexclude_files = [\".backupignore\", \"/etc/backup.ignore\"]\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.backup.exclude_files, [
+            PathBuf::from(".backupignore"),
+            PathBuf::from("/etc/backup.ignore")
+        ]);
+    }
+
+    #[test]
+    fn exclude_files_default_to_empty() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert!(cfg.backup.exclude_files.is_empty());
+    }
+
+    #[test]
+    fn local_exclude_files_replace_global() {
+        let global: PartialConfig = toml::from_str("[backup]\nexclude_files = [\"/a\"]").unwrap();
+        let local: PartialConfig = toml::from_str("[backup]\nexclude_files = [\"/b\"]").unwrap();
+        let cfg = global.merge(local).resolve();
+        assert_eq!(cfg.backup.exclude_files, [PathBuf::from("/b")]);
+    }
+
     #[test]
     fn empty_toml_deserialises_to_defaults() {
         let cfg: Config = toml::from_str("").expect("empty toml should parse");
         assert_eq!(cfg.repo.path, "./.backup");
     }
 
+    // ── validate ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn default_config_has_no_issues() {
+        assert!(Config::default().validate().is_empty());
+    }
+
+    #[test]
+    fn zero_max_file_size_is_an_error() {
+        let mut cfg = Config::default();
+        cfg.backup.max_file_size_mb = Some(0);
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+        assert_eq!(issues[0].field, "backup.max_file_size_mb");
+    }
+
+    #[test]
+    fn zero_stage_timeout_is_an_error() {
+        let cfg = Config {
+            stage_timeout_secs: Some(0),
+            ..Config::default()
+        };
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "stage_timeout_secs");
+    }
+
+    #[test]
+    fn zero_mount_timeout_is_an_error() {
+        let mut cfg = Config::default();
+        cfg.mount.timeout_secs = Some(0);
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "mount.timeout_secs");
+        assert!(issues[0].is_error());
+    }
+
+    #[test]
+    fn missing_exclude_file_is_a_warning() {
+        let mut cfg = Config::default();
+        cfg.backup.exclude_files = vec!["/definitely/not/here.ignore".into()];
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(!issues[0].is_error());
+        assert_eq!(issues[0].field, "backup.exclude_files");
+        assert!(issues[0].message.contains("/definitely/not/here.ignore"));
+    }
+
+    #[test]
+    fn existing_exclude_file_is_valid() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut cfg = Config::default();
+        cfg.backup.exclude_files = vec![file.path().into()];
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn pack_sizes_in_range_are_valid() {
+        let mut cfg = Config::default();
+        cfg.repo.pack_size_mb = Some(1);
+        cfg.repo.tree_pack_size_mb = Some(2048);
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn zero_pack_size_is_an_error() {
+        let mut cfg = Config::default();
+        cfg.repo.pack_size_mb = Some(0);
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+        assert_eq!(issues[0].field, "repo.pack_size_mb");
+    }
+
+    #[test]
+    fn oversized_tree_pack_size_is_an_error() {
+        let mut cfg = Config::default();
+        cfg.repo.tree_pack_size_mb = Some(2049);
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "repo.tree_pack_size_mb");
+        assert!(issues[0].message.contains("2049"));
+    }
+
+    #[test]
+    fn extra_args_repeating_repo_flags_warn() {
+        let mut cfg = Config::default();
+        cfg.repo.extra_args = vec!["--no-cache".into(), "-r".into(), "rustic".into()];
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 2);
+        assert!(
+            issues
+                .iter()
+                .all(|i| i.field == "repo.extra_args" && !i.is_error())
+        );
+        assert!(issues[0].message.contains("'-r'"));
+        assert!(issues[1].message.contains("'rustic'"));
+    }
+
+    #[test]
+    fn global_extra_args_are_valid() {
+        let mut cfg = Config::default();
+        cfg.repo.extra_args = vec!["--cache-dir".into(), "/var/cache/rustic".into()];
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn rest_section_parses_under_repo() {
+        let cfg: Config = toml::from_str(
+            "[repo]\npath = \"/unused\"\npassword = \"\"\n\n\
+             [repo.rest]\nurl = \"http://nas.lan:8000/\"\nusername = \"alice\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.repo.rest,
+            Some(RestConfig {
+                url: "http://nas.lan:8000/".into(),
+                username: Some("alice".into()),
+                password: None,
+            })
+        );
+        assert!(cfg.validate().is_empty(), "{:?}", cfg.validate());
+    }
+
+    #[test]
+    fn validate_rejects_bad_rest_settings() {
+        let mut cfg = Config::default();
+        cfg.repo.rest = Some(RestConfig {
+            url: "nas.lan:8000".into(),
+            username: None,
+            password: Some("s3cr3t".into()),
+        });
+        let fields: Vec<_> = cfg.validate().into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, ["repo.rest.url", "repo.rest.password"]);
+    }
+
+    #[test]
+    fn cold_storage_requires_a_separate_cold_repo() {
+        let mut cfg = Config::default();
+        cfg.repo.cold_storage = true;
+        assert_eq!(cfg.validate()[0].field, "cold_repo.path");
+
+        cfg.cold_repo.path = Some(cfg.repo.path.clone());
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("same repository"));
+
+        cfg.cold_repo.path = Some("/mnt/archive/rustic/app".into());
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn mirror_must_not_target_the_primary_repo() {
+        let mut cfg = Config::default();
+        cfg.mirror.dest_repo = Some(cfg.repo.path.clone());
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "mirror.dest_repo");
+
+        cfg.mirror.dest_repo = Some("/mnt/offsite/rustic/app".into());
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn mirror_section_parses_from_toml() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [mirror]
+            dest_repo = "/mnt/offsite/rustic/app"
+            dest_password = "hunter3"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.mirror.dest_repo.as_deref(),
+            Some("/mnt/offsite/rustic/app")
+        );
+        assert_eq!(cfg.mirror.dest_password.as_deref(), Some("hunter3"));
+        assert!(Config::default().mirror.dest_repo.is_none());
+    }
+
+    #[test]
+    fn positive_max_file_size_is_valid() {
+        let mut cfg = Config::default();
+        cfg.backup.max_file_size_mb = Some(1);
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn all_zero_retention_is_a_warning() {
+        let cfg = Config {
+            retention: RetentionConfig {
+                daily: 0,
+                weekly: 0,
+                monthly: 0,
+                keep_last: 0,
+                keep_tags: vec![],
+                keep_all_within: None,
+                keep_within_daily: None,
+                keep_within_weekly: None,
+                keep_within_monthly: None,
+                group_by: None,
+                max_repack_size_mb: None,
+            },
+            ..Config::default()
+        };
+        let issues = cfg.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].field, "retention");
+    }
+
+    // ── keep_tags ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn keep_tags_parse_from_toml() {
+        let cfg: Config =
+            toml::from_str("[retention]\nkeep_tags = [\"release\", \"audit\"]\n").unwrap();
+        assert_eq!(cfg.retention.keep_tags, ["release", "audit"]);
+    }
+
+    #[test]
+    fn keep_tags_default_to_empty() {
+        let cfg: Config = toml::from_str("[retention]\ndaily = 1\n").unwrap();
+        assert!(cfg.retention.keep_tags.is_empty());
+    }
+
+    #[test]
+    fn local_keep_tags_replace_global() {
+        let global: PartialConfig = toml::from_str("[retention]\nkeep_tags = [\"a\"]").unwrap();
+        let local: PartialConfig = toml::from_str("[retention]\nkeep_tags = [\"b\"]").unwrap();
+        assert_eq!(global.merge(local).resolve().retention.keep_tags, ["b"]);
+    }
+
+    // ── keep_all_within ───────────────────────────────────────────────────────
+
+    #[test]
+    fn keep_all_within_parses_and_roundtrips() {
+        let cfg: Config = toml::from_str("[retention]\nkeep_all_within = \"24h\"\n").unwrap();
+        assert_eq!(cfg.retention.keep_all_within.as_deref(), Some("24h"));
+
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(recovered.retention.keep_all_within.as_deref(), Some("24h"));
+    }
+
+    #[test]
+    fn keep_all_within_defaults_to_none() {
+        let cfg: Config = toml::from_str("[retention]\ndaily = 1\n").unwrap();
+        assert!(cfg.retention.keep_all_within.is_none());
+    }
+
+    #[test]
+    fn keep_within_windows_parse_and_roundtrip() {
+        let cfg: Config = toml::from_str(
+            "[retention]\nkeep_within_daily = \"14d\"\nkeep_within_weekly = \"1y\"\n\
+             keep_within_monthly = \"5y\"\n",
+        )
+        .unwrap();
+        let recovered: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        for r in [&cfg.retention, &recovered.retention] {
+            assert_eq!(r.keep_within_daily.as_deref(), Some("14d"));
+            assert_eq!(r.keep_within_weekly.as_deref(), Some("1y"));
+            assert_eq!(r.keep_within_monthly.as_deref(), Some("5y"));
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_keep_within_windows() {
+        let mut cfg = Config::default();
+        cfg.retention.keep_within_daily = Some("two weeks".into());
+        cfg.retention.keep_within_weekly = Some("1y".into());
+        cfg.retention.keep_within_monthly = Some("5".into());
+        let fields: Vec<_> = cfg
+            .validate()
+            .into_iter()
+            .filter(ValidationIssue::is_error)
+            .map(|i| i.field)
+            .collect();
+        assert_eq!(fields, [
+            "retention.keep_within_daily",
+            "retention.keep_within_monthly"
+        ]);
+    }
+
+    #[test]
+    fn duration_format_accepts_rustic_durations() {
+        for ok in ["24h", "7d", "1y6M", "1y 6M", "2weeks", "90min"] {
+            assert!(is_duration(ok), "{ok} should be a duration");
+        }
+        for bad in ["", "24", "h", "1.5h", "7 days ago", "-1d", "1x"] {
+            assert!(!is_duration(bad), "{bad:?} should not be a duration");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_keep_all_within() {
+        let mut cfg = Config::default();
+        cfg.retention.keep_all_within = Some("a day".into());
+        let issues = cfg.validate();
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.is_error() && i.field == "retention.keep_all_within"),
+            "got: {issues:?}"
+        );
+
+        cfg.retention.keep_all_within = Some("24h".into());
+        assert!(cfg.validate().is_empty());
+    }
+
+    // ── RetentionConfig::is_effectively_disabled ──────────────────────────────
+
+    #[test]
+    fn all_zero_retention_is_disabled() {
+        let r = RetentionConfig {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+            keep_last: 0,
+            keep_tags: vec![],
+            keep_all_within: None,
+            keep_within_daily: None,
+            keep_within_weekly: None,
+            keep_within_monthly: None,
+            group_by: None,
+            max_repack_size_mb: None,
+        };
+        assert!(r.is_effectively_disabled());
+    }
+
+    #[test]
+    fn mixed_zero_retention_is_not_disabled() {
+        for (daily, weekly, monthly) in [(1, 0, 0), (0, 1, 0), (0, 0, 1), (0, 2, 3)] {
+            let r = RetentionConfig {
+                daily,
+                weekly,
+                monthly,
+                keep_last: 0,
+                keep_tags: vec![],
+                keep_all_within: None,
+                keep_within_daily: None,
+                keep_within_weekly: None,
+                keep_within_monthly: None,
+                group_by: None,
+                max_repack_size_mb: None,
+            };
+            assert!(
+                !r.is_effectively_disabled(),
+                "{daily}/{weekly}/{monthly} keeps something"
+            );
+        }
+    }
+
+    #[test]
+    fn keep_last_alone_keeps_retention_enabled() {
+        let r = RetentionConfig {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+            keep_last: 2,
+            ..RetentionConfig::default()
+        };
+        assert!(!r.is_effectively_disabled());
+        assert_eq!(r.as_rustic_flags(), ["--keep-last", "2"]);
+    }
+
+    #[test]
+    fn keep_last_precedes_the_time_windows() {
+        let r = RetentionConfig {
+            keep_last: 3,
+            ..RetentionConfig::default()
+        };
+        assert_eq!(r.as_rustic_flags()[..4], [
+            "--keep-last",
+            "3",
+            "--keep-daily",
+            "2"
+        ]);
+    }
+
+    #[test]
+    fn keep_tags_alone_keeps_retention_enabled() {
+        let r = RetentionConfig {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+            keep_tags: vec!["pinned".into()],
+            ..RetentionConfig::default()
+        };
+        assert!(!r.is_effectively_disabled());
+        assert_eq!(r.as_rustic_flags(), ["--keep-tag", "pinned"]);
+    }
+
+    #[test]
+    fn a_within_duration_alone_keeps_retention_enabled() {
+        let zero = || RetentionConfig {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+            ..RetentionConfig::default()
+        };
+        for r in [
+            RetentionConfig {
+                keep_all_within: Some("24h".into()),
+                ..zero()
+            },
+            RetentionConfig {
+                keep_within_daily: Some("7d".into()),
+                ..zero()
+            },
+            RetentionConfig {
+                keep_within_weekly: Some("1m".into()),
+                ..zero()
+            },
+            RetentionConfig {
+                keep_within_monthly: Some("1y".into()),
+                ..zero()
+            },
+        ] {
+            assert!(!r.is_effectively_disabled(), "{r:?} keeps something");
+        }
+    }
+
+    #[test]
+    fn default_retention_is_not_disabled() {
+        assert!(!RetentionConfig::default().is_effectively_disabled());
+    }
+
     // ── load_config ───────────────────────────────────────────────────────────
 
     #[test]
@@ -628,6 +2886,84 @@ mod tests {
         assert_eq!(cfg.mount.user.as_deref(), Some("alice"));
     }
 
+    #[test]
+    fn local_stage_timeout_overrides_global() {
+        let global: PartialConfig = toml::from_str("stage_timeout_secs = 60").unwrap();
+        let local: PartialConfig = toml::from_str("stage_timeout_secs = 5").unwrap();
+        assert_eq!(global.merge(local).resolve().stage_timeout_secs, Some(5));
+    }
+
+    // ── PartialConfig::is_empty ───────────────────────────────────────────────
+
+    #[test]
+    fn default_partial_is_empty() {
+        assert!(PartialConfig::default().is_empty());
+        assert!(toml::from_str::<PartialConfig>("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn empty_sections_and_lists_are_empty() {
+        let partial: PartialConfig =
+            toml::from_str("[repo]\n[backup]\nsources = []\nglobs = []\n[mount]\n").unwrap();
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn one_set_field_is_not_empty() {
+        let partial: PartialConfig = toml::from_str("[mount]\nuser = \"alice\"").unwrap();
+        assert!(!partial.is_empty());
+        let partial: PartialConfig =
+            toml::from_str("[retention]\nkeep_tags = [\"release\"]").unwrap();
+        assert!(!partial.is_empty());
+    }
+
+    #[test]
+    fn fully_populated_partial_is_not_empty() {
+        let original = Config {
+            stage_timeout_secs: Some(60),
+            ..Config::default()
+        };
+        let partial: PartialConfig = toml::from_str(&toml::to_string(&original).unwrap()).unwrap();
+        assert!(!partial.is_empty());
+    }
+
+    // ── PartialConfig::to_commented_toml ──────────────────────────────────────
+
+    #[test]
+    fn unset_fields_are_commented_out() {
+        let partial: PartialConfig =
+            toml::from_str("[repo]\npath = \"/srv/repo\"\n[retention]\ndaily = 9\n").unwrap();
+        let text = partial.to_commented_toml().unwrap();
+        assert!(text.contains("\npath = \"/srv/repo\"\n"), "got:\n{text}");
+        assert!(text.contains("\ndaily = 9\n"), "got:\n{text}");
+        assert!(text.contains("# password = \"\"\n"), "got:\n{text}");
+        assert!(text.contains("# weekly = 1\n"), "got:\n{text}");
+        assert!(text.contains("# share = \"new-backups\"\n"), "got:\n{text}");
+        assert!(
+            !text.contains("# path = \"./.backup\""),
+            "set fields are not repeated as comments"
+        );
+    }
+
+    #[test]
+    fn commented_toml_parses_back_to_the_same_partial() {
+        let partial: PartialConfig = toml::from_str(
+            "stage_timeout_secs = 60\n[backup]\nglobs = []\n[mount]\nmethod = { rclone = { remote = \"gdrive\" } }\n",
+        )
+        .unwrap();
+        let text = partial.to_commented_toml().unwrap();
+        assert_eq!(
+            toml::from_str::<PartialConfig>(&text).unwrap(),
+            partial,
+            "got:\n{text}"
+        );
+    }
+
+    #[test]
+    fn snapshot_commented_toml_of_empty_partial() {
+        insta::assert_snapshot!(PartialConfig::default().to_commented_toml().unwrap());
+    }
+
     #[test]
     fn merge_with_no_global_equals_local_only() {
         use std::io::Write;
@@ -642,4 +2978,567 @@ mod tests {
         assert_eq!(cfg.repo.path, "/tmp/solo");
         assert!(cfg.mount.share.is_none());
     }
+
+    // ── read_password_line ────────────────────────────────────────────────────
+
+    #[test]
+    fn password_line_drops_line_ending_only() {
+        assert_eq!(
+            read_password_line(&b" s3cret \r\nrest\n"[..]).unwrap(),
+            " s3cret "
+        );
+        assert_eq!(read_password_line(&b"last"[..]).unwrap(), "last");
+    }
+
+    #[test]
+    fn empty_line_is_an_empty_password_but_no_line_is_an_error() {
+        assert_eq!(read_password_line(&b"\n"[..]).unwrap(), "");
+        assert!(read_password_line(&b""[..]).is_err());
+    }
+
+    // ── password_env / .env ───────────────────────────────────────────────────
+
+    #[test]
+    fn from_env_reads_password_from_dotenv_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("backup.toml");
+        std::fs::write(
+            dir.path().join(".env"),
+            "BACKUP_RS_TEST_DOTENV_PASSWORD=s3cret\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &config,
+            "[repo]\npassword_env = \"BACKUP_RS_TEST_DOTENV_PASSWORD\"\n",
+        )
+        .unwrap();
+
+        assert!(load_dotenv(&config).unwrap().is_some());
+        let cfg = Config::from_env(parse_partial(&config).unwrap().unwrap()).unwrap();
+        assert_eq!(cfg.repo.password, "s3cret");
+    }
+
+    #[test]
+    fn from_env_errors_when_variable_is_unset() {
+        let partial: PartialConfig =
+            toml::from_str("[repo]\npassword_env = \"BACKUP_RS_TEST_NEVER_SET\"").unwrap();
+        let err = Config::from_env(partial).unwrap_err().to_string();
+        assert!(err.contains("BACKUP_RS_TEST_NEVER_SET"), "got: {err}");
+    }
+
+    #[test]
+    fn from_env_keeps_config_password_without_password_env() {
+        let partial: PartialConfig = toml::from_str("[repo]\npassword = \"pw\"").unwrap();
+        assert_eq!(Config::from_env(partial).unwrap().repo.password, "pw");
+    }
+
+    #[test]
+    fn load_dotenv_without_file_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            load_dotenv(&dir.path().join("backup.toml"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn local_password_env_overrides_global() {
+        let global: PartialConfig = toml::from_str("[repo]\npassword_env = \"A\"").unwrap();
+        let local: PartialConfig = toml::from_str("[repo]\npassword_env = \"B\"").unwrap();
+        assert_eq!(
+            global.merge(local).resolve().repo.password_env.as_deref(),
+            Some("B")
+        );
+    }
+
+    // ── merge_cli_overrides ───────────────────────────────────────────────────
+
+    fn cli(extra: &[&str]) -> Cli {
+        use clap::Parser;
+        Cli::parse_from(std::iter::once("backup").chain(extra.iter().copied()))
+    }
+
+    #[test]
+    fn effective_defaults_run_every_stage() {
+        let cfg = Config {
+            mount: MountConfig {
+                share: Some("new-backups".into()),
+                user: None,
+                mountpoint: None,
+                method: MountMethod::Nfs,
+                timeout_secs: None,
+                shares: BTreeMap::new(),
+                force_recheck: false,
+            },
+            ..Config::default()
+        };
+        assert_eq!(cfg.merge_cli_overrides(&cli(&[])), EffectiveConfig {
+            mount: true,
+            mounted: MountedPolicy::Reuse,
+            init_if_missing: false,
+            check: true,
+            verify: true,
+            prune: true,
+            dry_run: false,
+            stage_timeout: None,
+        });
+    }
+
+    #[test]
+    fn effective_flags_disable_stages() {
+        let eff = Config::default().merge_cli_overrides(&cli(&[
+            "--no-mount",
+            "--no-check",
+            "--no-verify",
+            "--no-prune",
+            "--dry-run",
+            "--init-if-missing",
+        ]));
+        assert!(!eff.mount && !eff.check && !eff.verify && !eff.prune);
+        assert!(eff.dry_run && eff.init_if_missing);
+    }
+
+    #[test]
+    fn effective_dry_run_skips_verify() {
+        assert!(Config::default().merge_cli_overrides(&cli(&[])).verify);
+        assert!(
+            !Config::default()
+                .merge_cli_overrides(&cli(&["--dry-run"]))
+                .verify
+        );
+    }
+
+    #[test]
+    fn effective_mounted_policy_from_flags_and_config() {
+        let policy = |cfg: &Config, args: &[&str]| cfg.merge_cli_overrides(&cli(args)).mounted;
+        let mut cfg = Config::default();
+        assert_eq!(policy(&cfg, &[]), MountedPolicy::Reuse);
+        assert_eq!(policy(&cfg, &["--force-mount"]), MountedPolicy::Force);
+        assert_eq!(policy(&cfg, &["--skip-if-mounted"]), MountedPolicy::Skip);
+
+        cfg.mount.force_recheck = true;
+        assert_eq!(policy(&cfg, &[]), MountedPolicy::Force);
+        assert_eq!(
+            policy(&cfg, &["--skip-if-mounted"]),
+            MountedPolicy::Skip,
+            "the flag beats the file"
+        );
+    }
+
+    #[test]
+    fn force_mount_conflicts_with_skip_if_mounted() {
+        use clap::Parser;
+        let parsed = Cli::try_parse_from(["backup", "--force-mount", "--skip-if-mounted"]);
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn effective_mount_needs_a_share() {
+        assert!(!Config::default().merge_cli_overrides(&cli(&[])).mount);
+    }
+
+    #[test]
+    fn effective_timeout_from_config() {
+        let cfg = Config {
+            stage_timeout_secs: Some(90),
+            ..Config::default()
+        };
+        assert_eq!(
+            cfg.merge_cli_overrides(&cli(&[])).stage_timeout,
+            Some(Duration::from_secs(90))
+        );
+    }
+
+    #[test]
+    fn effective_timeout_flag_beats_config() {
+        let cfg = Config {
+            stage_timeout_secs: Some(90),
+            ..Config::default()
+        };
+        assert_eq!(
+            cfg.merge_cli_overrides(&cli(&["--timeout", "5"]))
+                .stage_timeout,
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    // ── PartialConfig properties ──────────────────────────────────────────────
+
+    mod props {
+        use proptest::prelude::*;
+
+        use super::super::*;
+
+        // TOML integers are i64, so unsigned fields are kept in range.
+        fn toml_u64() -> impl Strategy<Value = u64> {
+            0..=i64::MAX as u64
+        }
+
+        impl Arbitrary for PartialRepoConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                (
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    any::<Option<u32>>(),
+                    any::<Option<u32>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<bool>>(),
+                    proptest::option::of(any::<(String, Option<String>, Option<String>)>()),
+                )
+                    .prop_map(
+                        |(
+                            path,
+                            password,
+                            password_env,
+                            pack,
+                            tree_pack,
+                            extra_args,
+                            cold,
+                            rest,
+                        )| {
+                            Self {
+                                path,
+                                password,
+                                password_env,
+                                pack_size_mb: pack,
+                                tree_pack_size_mb: tree_pack,
+                                extra_args,
+                                cold_storage: cold,
+                                rest: rest.map(|(url, username, password)| RestConfig {
+                                    url,
+                                    username,
+                                    password,
+                                }),
+                            }
+                        },
+                    )
+                    .boxed()
+            }
+        }
+
+        impl Arbitrary for PartialBackupConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                (
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<u8>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<String>>(),
+                    any::<Option<bool>>(),
+                    proptest::option::of(toml_u64()),
+                    any::<Option<bool>>(),
+                    any::<Option<bool>>(),
+                    any::<Option<BTreeMap<String, String>>>(),
+                )
+                    .prop_map(
+                        |(
+                            sources,
+                            sources_exclude,
+                            compression,
+                            globs,
+                            iglobs,
+                            files,
+                            exclude_if_present,
+                            exclude_caches,
+                            max_mb,
+                            ignore_inaccessible,
+                            no_scan,
+                            custom_metadata,
+                        )| {
+                            Self {
+                                sources,
+                                sources_exclude,
+                                compression,
+                                globs,
+                                iglobs,
+                                exclude_files: files
+                                    .map(|f| f.into_iter().map(PathBuf::from).collect()),
+                                exclude_if_present,
+                                exclude_caches,
+                                max_file_size_mb: max_mb,
+                                ignore_inaccessible,
+                                no_scan,
+                                custom_metadata,
+                            }
+                        },
+                    )
+                    .boxed()
+            }
+        }
+
+        impl Arbitrary for PartialRetentionConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                (
+                    any::<Option<u32>>(),
+                    any::<Option<u32>>(),
+                    any::<Option<u32>>(),
+                    any::<Option<u32>>(),
+                    any::<Option<Vec<String>>>(),
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    any::<Option<String>>(),
+                    proptest::option::of(toml_u64()),
+                )
+                    .prop_map(
+                        |(
+                            daily,
+                            weekly,
+                            monthly,
+                            keep_last,
+                            keep_tags,
+                            keep_all_within,
+                            keep_within_daily,
+                            keep_within_weekly,
+                            keep_within_monthly,
+                            group_by,
+                            max_repack_size_mb,
+                        )| Self {
+                            daily,
+                            weekly,
+                            monthly,
+                            keep_last,
+                            keep_tags,
+                            keep_all_within,
+                            keep_within_daily,
+                            keep_within_weekly,
+                            keep_within_monthly,
+                            group_by,
+                            max_repack_size_mb,
+                        },
+                    )
+                    .boxed()
+            }
+        }
+
+        impl Arbitrary for PartialMountConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let method = prop_oneof![
+                    Just(MountMethod::Nfs),
+                    any::<String>().prop_map(|remote| MountMethod::Rclone {
+                        remote
+                    }),
+                ];
+                (
+                    any::<(Option<String>, Option<String>, Option<String>)>(),
+                    proptest::option::of(method),
+                    proptest::option::of(toml_u64()),
+                    any::<Option<BTreeMap<String, String>>>(),
+                    any::<Option<bool>>(),
+                )
+                    .prop_map(
+                        |(
+                            (share, user, mountpoint),
+                            method,
+                            timeout_secs,
+                            shares,
+                            force_recheck,
+                        )| {
+                            Self {
+                                share,
+                                user,
+                                mountpoint: mountpoint.map(PathBuf::from),
+                                method,
+                                timeout_secs,
+                                shares,
+                                force_recheck,
+                            }
+                        },
+                    )
+                    .boxed()
+            }
+        }
+
+        impl Arbitrary for PartialConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                (
+                    proptest::option::of(toml_u64()),
+                    any::<PartialRepoConfig>(),
+                    any::<PartialBackupConfig>(),
+                    any::<PartialRetentionConfig>(),
+                    any::<PartialMountConfig>(),
+                    any::<(Option<String>, Option<String>)>(),
+                    any::<(Option<String>, Option<String>)>(),
+                )
+                    .prop_map(
+                        |(
+                            stage_timeout_secs,
+                            repo,
+                            backup,
+                            retention,
+                            mount,
+                            (cold_path, cold_password),
+                            (dest_repo, dest_password),
+                        )| Self {
+                            stage_timeout_secs,
+                            repo,
+                            backup,
+                            retention,
+                            mount,
+                            cold_repo: PartialColdRepoConfig {
+                                path: cold_path,
+                                password: cold_password,
+                            },
+                            mirror: PartialMirrorConfig {
+                                dest_repo,
+                                dest_password,
+                            },
+                        },
+                    )
+                    .boxed()
+            }
+        }
+
+        /// Clear every field of `b` that is set in `a`, so the two never overlap.
+        fn disjoint_from(a: &PartialConfig, mut b: PartialConfig) -> PartialConfig {
+            fn clear<T>(taken: Option<&T>, field: &mut Option<T>) {
+                if taken.is_some() {
+                    *field = None;
+                }
+            }
+            clear(a.stage_timeout_secs.as_ref(), &mut b.stage_timeout_secs);
+            clear(a.repo.path.as_ref(), &mut b.repo.path);
+            clear(a.repo.password.as_ref(), &mut b.repo.password);
+            clear(a.repo.password_env.as_ref(), &mut b.repo.password_env);
+            clear(a.repo.pack_size_mb.as_ref(), &mut b.repo.pack_size_mb);
+            clear(
+                a.repo.tree_pack_size_mb.as_ref(),
+                &mut b.repo.tree_pack_size_mb,
+            );
+            clear(a.repo.extra_args.as_ref(), &mut b.repo.extra_args);
+            clear(a.repo.cold_storage.as_ref(), &mut b.repo.cold_storage);
+            clear(a.repo.rest.as_ref(), &mut b.repo.rest);
+            clear(a.backup.sources.as_ref(), &mut b.backup.sources);
+            clear(
+                a.backup.sources_exclude.as_ref(),
+                &mut b.backup.sources_exclude,
+            );
+            clear(a.backup.compression.as_ref(), &mut b.backup.compression);
+            clear(a.backup.globs.as_ref(), &mut b.backup.globs);
+            clear(a.backup.iglobs.as_ref(), &mut b.backup.iglobs);
+            clear(a.backup.exclude_files.as_ref(), &mut b.backup.exclude_files);
+            clear(
+                a.backup.exclude_if_present.as_ref(),
+                &mut b.backup.exclude_if_present,
+            );
+            clear(
+                a.backup.exclude_caches.as_ref(),
+                &mut b.backup.exclude_caches,
+            );
+            clear(
+                a.backup.max_file_size_mb.as_ref(),
+                &mut b.backup.max_file_size_mb,
+            );
+            clear(
+                a.backup.ignore_inaccessible.as_ref(),
+                &mut b.backup.ignore_inaccessible,
+            );
+            clear(a.backup.no_scan.as_ref(), &mut b.backup.no_scan);
+            clear(
+                a.backup.custom_metadata.as_ref(),
+                &mut b.backup.custom_metadata,
+            );
+            clear(a.retention.daily.as_ref(), &mut b.retention.daily);
+            clear(a.retention.weekly.as_ref(), &mut b.retention.weekly);
+            clear(a.retention.monthly.as_ref(), &mut b.retention.monthly);
+            clear(a.retention.keep_last.as_ref(), &mut b.retention.keep_last);
+            clear(a.retention.keep_tags.as_ref(), &mut b.retention.keep_tags);
+            clear(
+                a.retention.keep_all_within.as_ref(),
+                &mut b.retention.keep_all_within,
+            );
+            clear(
+                a.retention.keep_within_daily.as_ref(),
+                &mut b.retention.keep_within_daily,
+            );
+            clear(
+                a.retention.keep_within_weekly.as_ref(),
+                &mut b.retention.keep_within_weekly,
+            );
+            clear(
+                a.retention.keep_within_monthly.as_ref(),
+                &mut b.retention.keep_within_monthly,
+            );
+            clear(a.retention.group_by.as_ref(), &mut b.retention.group_by);
+            clear(
+                a.retention.max_repack_size_mb.as_ref(),
+                &mut b.retention.max_repack_size_mb,
+            );
+            clear(a.mount.share.as_ref(), &mut b.mount.share);
+            clear(a.mount.user.as_ref(), &mut b.mount.user);
+            clear(a.mount.mountpoint.as_ref(), &mut b.mount.mountpoint);
+            clear(a.mount.method.as_ref(), &mut b.mount.method);
+            clear(a.mount.timeout_secs.as_ref(), &mut b.mount.timeout_secs);
+            clear(a.mount.shares.as_ref(), &mut b.mount.shares);
+            clear(a.mount.force_recheck.as_ref(), &mut b.mount.force_recheck);
+            clear(a.cold_repo.path.as_ref(), &mut b.cold_repo.path);
+            clear(a.cold_repo.password.as_ref(), &mut b.cold_repo.password);
+            clear(a.mirror.dest_repo.as_ref(), &mut b.mirror.dest_repo);
+            clear(a.mirror.dest_password.as_ref(), &mut b.mirror.dest_password);
+            b
+        }
+
+        proptest! {
+            #[test]
+            fn partial_config_roundtrips_through_toml(partial in any::<PartialConfig>()) {
+                let text = toml::to_string(&partial).unwrap();
+                let recovered: PartialConfig = toml::from_str(&text).unwrap();
+                prop_assert_eq!(recovered, partial);
+            }
+
+            #[test]
+            fn commented_toml_roundtrips(partial in any::<PartialConfig>()) {
+                let text = partial.to_commented_toml().unwrap();
+                let recovered: PartialConfig = toml::from_str(&text).unwrap();
+                prop_assert_eq!(recovered, partial);
+            }
+
+            #[test]
+            fn merge_is_commutative_on_disjoint_fields(
+                a in any::<PartialConfig>(),
+                b in any::<PartialConfig>(),
+            ) {
+                let b = disjoint_from(&a, b);
+                prop_assert_eq!(a.clone().merge(b.clone()), b.merge(a));
+            }
+
+            #[test]
+            fn merge_prefers_local_on_overlapping_fields(
+                global in any::<PartialConfig>(),
+                local in any::<PartialConfig>(),
+            ) {
+                // Dropping every global value that the local file also sets
+                // must not change the result: on overlap, local wins.
+                let merged = global.clone().merge(local.clone());
+                prop_assert_eq!(&merged, &disjoint_from(&local, global.clone()).merge(local.clone()));
+                prop_assert_eq!(merged.repo.path, local.repo.path.or(global.repo.path));
+            }
+
+            #[test]
+            fn merge_with_empty_is_identity(partial in any::<PartialConfig>()) {
+                prop_assert_eq!(&PartialConfig::default().merge(partial.clone()), &partial);
+                prop_assert_eq!(partial.clone().merge(PartialConfig::default()), partial);
+            }
+        }
+    }
 }
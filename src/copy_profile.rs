@@ -0,0 +1,170 @@
+//! Temporary rustic profiles naming the repository `rustic copy` writes to.
+//!
+//! rustic takes copy targets from its config profiles, not from the command
+//! line: the profile chosen with `-P <name>` lists them under
+//! `[copy].targets`, and each target is itself a profile with a
+//! `[repository]` section.  [`CopyProfile::create`] writes such a pair to
+//! rustic's config directory (`$XDG_CONFIG_HOME/rustic`) and removes both
+//! files again when dropped:
+//!
+//! ```text
+//! backup-rs-copy-<pid>.toml    [copy] targets = ["backup-rs-target-<pid>"]
+//! backup-rs-target-<pid>.toml  [repository] repository = "…"  password = "…"
+//! ```
+//!
+//! The target's password therefore never appears on a command line.  Both
+//! files are created with mode `0600`.
+//!
+//! Under `--sudo`, rustic runs as root and looks in root's config directory,
+//! so `doas` must keep `$HOME` (`permit keepenv …`) for it to find them.
+
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+/// A copy profile and its target profile, deleted on drop.
+#[derive(Debug)]
+pub struct CopyProfile {
+    name: String,
+    files: Vec<PathBuf>,
+}
+
+impl CopyProfile {
+    /// Write a profile copying to `repository`, opened with `password`, to
+    /// rustic's config directory.
+    pub fn create(repository: &str, password: &str) -> Result<Self> {
+        let dir = profile_dir().context("no config directory for the rustic copy profile")?;
+        Self::create_in(&dir, repository, password)
+    }
+
+    /// Like [`Self::create`], but in `dir`.
+    pub fn create_in(dir: &Path, repository: &str, password: &str) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("mkdir -p {}", dir.display()))?;
+        let pid = std::process::id();
+        let name = format!("backup-rs-copy-{pid}");
+        let target = format!("backup-rs-target-{pid}");
+
+        // Tracked as soon as each file exists, so an error part-way through
+        // still removes what was written.
+        let mut profile = Self {
+            name: name.clone(),
+            files: Vec::with_capacity(2),
+        };
+        for (stem, text) in [
+            (&target, render_target_profile(repository, password)),
+            (&name, render_copy_profile(&target)),
+        ] {
+            let path = dir.join(format!("{stem}.toml"));
+            write_private(&path, &text)?;
+            profile.files.push(path);
+        }
+        Ok(profile)
+    }
+
+    /// What to pass to rustic's `-P`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for CopyProfile {
+    fn drop(&mut self) {
+        for path in &self.files {
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!(path = %path.display(), "could not remove copy profile: {e}");
+            }
+        }
+    }
+}
+
+/// rustic's per-user config directory, or `None` when the platform has none.
+pub fn profile_dir() -> Option<PathBuf> {
+    dirs_next::config_dir().map(|d| d.join("rustic"))
+}
+
+/// The profile `-P` selects: copy to the profile named `target`.
+pub fn render_copy_profile(target: &str) -> String {
+    format!("[copy]\ntargets = [{}]\n", quoted(target))
+}
+
+/// The target profile: where the copy goes and how to open it.
+pub fn render_target_profile(repository: &str, password: &str) -> String {
+    format!(
+        "[repository]\nrepository = {}\npassword = {}\n",
+        quoted(repository),
+        quoted(password)
+    )
+}
+
+/// `text` as a TOML string literal, escaped as needed.
+fn quoted(text: &str) -> String {
+    toml::Value::String(text.into()).to_string()
+}
+
+/// Replace `path` with `text`, readable by the owner only.
+fn write_private(path: &Path, text: &str) -> Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut f| f.write_all(text.as_bytes()))
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn target_profile_escapes_its_values() {
+        let text = render_target_profile("/mnt/archive", "pa\"ss\\word");
+        let parsed: toml::Table = toml::from_str(&text).unwrap();
+        assert_eq!(
+            parsed["repository"]["repository"].as_str(),
+            Some("/mnt/archive")
+        );
+        assert_eq!(
+            parsed["repository"]["password"].as_str(),
+            Some("pa\"ss\\word")
+        );
+    }
+
+    #[test]
+    fn copy_profile_lists_the_target() {
+        let parsed: toml::Table = toml::from_str(&render_copy_profile("t")).unwrap();
+        assert_eq!(parsed["copy"]["targets"].as_array().unwrap(), &[
+            toml::Value::from("t")
+        ]);
+    }
+
+    #[test]
+    fn profiles_are_private_and_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = CopyProfile::create_in(dir.path(), "/mnt/archive", "s3cr3t").unwrap();
+
+        let copy = dir.path().join(format!("{}.toml", profile.name()));
+        let copy_text = std::fs::read_to_string(&copy).unwrap();
+        let target: toml::Table = toml::from_str(&copy_text).unwrap();
+        let target = target["copy"]["targets"][0].as_str().unwrap();
+        let target = dir.path().join(format!("{target}.toml"));
+        for path in [&copy, &target] {
+            let mode = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{}", path.display());
+        }
+        assert!(std::fs::read_to_string(&target).unwrap().contains("s3cr3t"));
+
+        drop(profile);
+        assert!(!copy.exists() && !target.exists(), "profiles left behind");
+    }
+}
@@ -0,0 +1,81 @@
+//! Structured diagnostics via [`tracing`].
+//!
+//! The spinner UI in [`crate::ui`] is the primary output channel; tracing is
+//! the secondary one, for operators who need to see *why* the pipeline made a
+//! decision.  Events go to stderr so they never mix with machine-readable
+//! stdout.
+//!
+//! The subscriber level is controlled by `--log-level` (default `warn`).  At
+//! `debug` or more verbose, [`crate::ui`] hides the spinner so log lines can
+//! stream without being overdrawn by the animation.
+
+use tracing::Level;
+
+/// Install the global tracing subscriber at `level`.
+///
+/// Must be called once, before any events are emitted.
+pub fn init(level: Level) {
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .init();
+}
+
+// ─── Test support ─────────────────────────────────────────────────────────────
+
+/// Run `f` under a debug-level subscriber and return everything it logged.
+///
+/// Used by unit tests across the crate to assert that key code paths emit the
+/// expected events.
+#[cfg(test)]
+pub fn capture(f: impl FnOnce()) -> String {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, f);
+
+    let bytes = buffer.0.lock().unwrap().clone();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_records_debug_events() {
+        let logs = capture(|| tracing::debug!(answer = 42, "hello from test"));
+        assert!(logs.contains("hello from test"));
+        assert!(logs.contains("answer=42"));
+    }
+
+    #[test]
+    fn capture_skips_trace_events() {
+        let logs = capture(|| tracing::trace!("too chatty"));
+        assert!(!logs.contains("too chatty"));
+    }
+}
@@ -10,76 +10,493 @@
 //!
 //! ```text
 //! backup                 # run the full backup pipeline using backup.toml
+//! backup --init-if-missing  # first run: create the repository too
 //! backup init            # scaffold a backup.toml in the current directory
+//! backup init --template rust  # start from the Rust project globs
+//! backup init --global   # scaffold ~/.config/backup.rs/config.toml
+//! backup import --repo DIR  # write a backup.toml for an existing repository
+//! backup cat latest PATH # print a file from the latest snapshot
+//! backup ls latest --source-index 0  # list one source's files in a snapshot
+//! backup snapshots --group-by host  # list snapshots, one section per host
+//! backup snapshots --count        # print the number of snapshots
+//! backup snapshot-id     # print the newest snapshot's ID (for scripts)
+//! backup snapshot-count  # print the number of snapshots (for scripts)
+//! backup snapshot-size latest  # how much data the newest snapshot holds
+//! backup cat-tree latest # print the newest snapshot's root tree as JSON
+//! backup config validate # report config errors and warnings
+//! backup config lint --output-format json  # the same, as JSON for CI
+//! backup config export   # print the merged config as TOML
+//! backup config path     # print which config file is in use
+//! backup diff-config ../bob/backup.toml  # show fields that differ
+//! backup key change-password --new-password-file FILE  # rotate the password
+//! backup mount --list-shares  # show the NAS share names [mount] accepts
+//! backup mount latest /mnt/snap  # browse a snapshot over FUSE
+//! backup umount         # unmount the NAS share again
+//! backup mount-test      # check the NAS server answers, without mounting
 //! backup --print-config  # show parsed config without running anything
 //! backup --no-prune      # skip forget/prune (fast incremental snapshot)
+//! backup --no-verify     # don't confirm the backup recorded a snapshot
 //! backup --sudo          # prefix all commands with doas
+//! backup --source DIR    # back up DIR instead of the configured sources
+//! backup --repo DIR      # use a different repository for this run
+//! pass show backup | backup --stdin-password  # password from a secret manager
+//! backup --log-level debug  # stream diagnostic logs instead of spinners
+//! backup --label NAME    # label the snapshot and the run log entry
+//! backup log             # list past runs
+//! backup log --since 2026-10-01  # only runs from October onwards
+//! backup check data      # verify the repository, reading every pack
+//! backup gc              # prune unreferenced data, keep every snapshot
+//! backup mirror          # back up, then copy new snapshots to [mirror]
+//! backup repair-index    # rebuild the index from pack files
+//! pg_dump db | backup backup-stdin db.sql  # snapshot piped data
+//! backup forget ID ID    # remove specific snapshots (then `backup gc`)
+//! backup --quiet        # skip the startup banner (for cron)
+//! backup --verbose --no-prune  # show the planned stages, then run
+//! backup completions zsh # print a shell completion script
+//! backup export-schema > backup.schema.json  # JSON Schema for backup.toml
+//! backup env             # show config path, rustic binary, user, backend
+//! backup self-check --checksum-file backup-rs.sha256  # verify this binary
+//! backup show-excludes   # list every exclusion rule and where it comes from
+//! backup paths --check   # check every configured path exists
+//! backup --exclude GLOB  # also leave out GLOB for this run
 //! ```
 //!
 //! # Module layout
 //!
-//! | Module                   | Responsibility                              |
-//! |--------------------------|---------------------------------------------|
-//! | [`cli`]                  | Argument types parsed by clap               |
-//! | [`config`]               | `Config` struct + TOML loader               |
-//! | [`runner`]               | Argument construction helpers               |
-//! | [`ui`]                   | Spinner, captured execution, stage output   |
-//! | [`commands::init`]       | `backup init` subcommand                    |
-//! | [`commands::run`]        | Default backup pipeline                     |
-//! | [`mount`]                | Built-in NFS share mounting                 |
+//! | Module                      | Responsibility                            |
+//! |-----------------------------|-------------------------------------------|
+//! | [`cli`]                     | Argument types parsed by clap             |
+//! | [`config`]                  | `Config` struct + TOML loader             |
+//! | [`copy_profile`]            | Temporary rustic profiles for `copy`      |
+//! | [`logging`]                 | `tracing` subscriber setup                |
+//! | [`runlog`]                  | Persistent JSONL log of pipeline runs     |
+//! | [`runner`]                  | Argument construction helpers             |
+//! | [`ui`]                      | Spinner, captured execution, stage output |
+//! | [`commands::init`]          | `backup init` subcommand                  |
+//! | [`commands::run`]           | Default backup pipeline                   |
+//! | [`commands::dump`]          | `backup dump` / `backup cat` subcommands  |
+//! | [`commands::ls`]            | `backup ls` subcommand                    |
+//! | [`commands::snapshots`]     | `backup snapshots` subcommand             |
+//! | [`commands::snapshot_id`]   | `backup snapshot-id` subcommand           |
+//! | [`commands::snapshot_size`] | `backup snapshot-size` subcommand         |
+//! | [`commands::cat_tree`]      | `backup cat-tree` subcommand              |
+//! | [`commands::config_cmd`]    | `backup config …` subcommands             |
+//! | [`commands::diff_config`]   | `backup diff-config` subcommand           |
+//! | [`commands::mount_cmd`]     | `backup mount` / `backup umount`          |
+//! | [`commands::fusemount`]     | `backup mount <snapshot> <dir>` (FUSE)    |
+//! | [`commands::mount_test`]    | `backup mount-test` subcommand            |
+//! | [`commands::log`]           | `backup log` subcommand                   |
+//! | [`commands::check`]         | `backup check` subcommand                 |
+//! | [`commands::gc`]            | `backup gc` subcommand                    |
+//! | [`commands::repair_index`]  | `backup repair-index` subcommand          |
+//! | [`commands::forget`]        | `backup forget` subcommand                |
+//! | [`commands::import`]        | `backup import` subcommand                |
+//! | [`commands::env_cmd`]       | `backup env` subcommand                   |
+//! | [`commands::self_check`]    | `backup self-check` subcommand            |
+//! | [`commands::completions`]   | `backup completions` subcommand           |
+//! | [`commands::export_schema`] | `backup export-schema` subcommand         |
+//! | [`commands::show_excludes`] | `backup show-excludes` subcommand         |
+//! | [`commands::paths`]         | `backup paths` subcommand                 |
+//! | [`commands::stdin_backup`]  | `backup backup-stdin` subcommand          |
+//! | [`commands::key_change`]    | `backup key change-password` subcommand   |
+//! | [`commands::mirror`]        | `backup mirror` subcommand                |
+//! | [`mount`]                   | Built-in NFS share mounting               |
+
+// Duplicate transitive versions (e.g. `syn` via `dirs-next` and `clap`) are
+// outside our control.
+#![allow(clippy::multiple_crate_versions)]
 
 mod cli;
 mod commands;
 mod config;
+mod copy_profile;
+mod logging;
 mod mount;
+mod runlog;
 mod runner;
 mod ui;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use cli::{Cli, Subcommand};
+use cli::{Cli, ConfigAction, KeyAction, Subcommand};
 use config::{PartialConfig, parse_partial};
 
+// One match arm per subcommand; splitting the dispatch would only scatter it.
+#[allow(clippy::too_many_lines)]
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    logging::init(cli.log_level);
 
     match &cli.command {
         // ── backup init ───────────────────────────────────────────────────────
-        Some(Subcommand::Init) => {
-            commands::init::run(&cli.config)?;
+        Some(Subcommand::Init {
+            repo,
+            sources,
+            template,
+            list_templates,
+            global,
+        }) => {
+            let ui = ui::UiContext::from_cli(&cli);
+            if *global {
+                let dest = config::global_config_path()
+                    .context("no config directory for the global config")?;
+                commands::init::run_global(ui, &dest)?;
+            } else {
+                commands::init::run(
+                    ui,
+                    &cli.new_config_path(),
+                    repo.as_deref(),
+                    sources,
+                    template.as_deref(),
+                    *list_templates,
+                )?;
+            }
         },
 
-        // ── backup (default pipeline) ─────────────────────────────────────────
-        None => {
-            let cfg = load_merged_config(&cli.config)?;
+        // ── backup import ─────────────────────────────────────────────────────
+        Some(Subcommand::Import {
+            repo,
+            password,
+        }) => {
+            commands::import::run(&cli, repo, password)?;
+        },
+
+        // ── backup dump / backup cat ──────────────────────────────────────────
+        Some(
+            Subcommand::Dump {
+                snapshot,
+                path,
+            }
+            | Subcommand::Cat {
+                snapshot,
+                path,
+            },
+        ) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::dump::run(&cli, &cfg, snapshot, path)?;
+        },
+
+        // ── backup ls ─────────────────────────────────────────────────────────
+        Some(Subcommand::Ls {
+            snapshot,
+            source_index,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::ls::run(&cli, &cfg, snapshot, *source_index)?;
+        },
+
+        // ── backup cat-tree ───────────────────────────────────────────────────
+        Some(Subcommand::CatTree {
+            snapshot,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::cat_tree::run(&cli, &cfg, snapshot)?;
+        },
+
+        // ── backup snapshots ──────────────────────────────────────────────────
+        Some(Subcommand::Snapshots {
+            group_by,
+            count,
+            json,
+            output_format,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            if *count {
+                commands::snapshots::run_count(&cli, &cfg, *json)?;
+            } else {
+                commands::snapshots::run(&cli, &cfg, *group_by, *output_format)?;
+            }
+        },
+
+        // ── backup snapshot-id ────────────────────────────────────────────────
+        Some(Subcommand::SnapshotId {
+            latest: _,
+            index,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::snapshot_id::run(&cli, &cfg, *index)?;
+        },
+
+        // ── backup snapshot-count ─────────────────────────────────────────────
+        Some(Subcommand::SnapshotCount) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::snapshots::run_snapshot_count(&cli, &cfg)?;
+        },
+
+        // ── backup snapshot-size ──────────────────────────────────────────────
+        Some(Subcommand::SnapshotSize {
+            snapshot,
+            raw_bytes,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::snapshot_size::run(&cli, &cfg, snapshot, *raw_bytes)?;
+        },
+
+        // ── backup mount ──────────────────────────────────────────────────────
+        Some(Subcommand::Mount {
+            snapshot,
+            mountpoint,
+            foreground,
+            list_shares,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            match (snapshot, mountpoint) {
+                (Some(snapshot), Some(mountpoint)) => {
+                    commands::fusemount::run(&cli, &cfg, snapshot, mountpoint, *foreground)?;
+                },
+                _ => commands::mount_cmd::run(
+                    ui::UiContext::from_cli(&cli),
+                    &cfg,
+                    *list_shares,
+                    cli.dry_run,
+                    cfg.merge_cli_overrides(&cli).mounted,
+                )?,
+            }
+        },
+
+        // ── backup umount ─────────────────────────────────────────────────────
+        Some(Subcommand::Umount) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::mount_cmd::unmount(ui::UiContext::from_cli(&cli), &cfg, cli.dry_run)?;
+        },
+
+        // ── backup mount-test ─────────────────────────────────────────────────
+        Some(Subcommand::MountTest {
+            port,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::mount_test::run(&cfg, *port)?;
+        },
 
-            if cli.print_config {
-                println!("{cfg:#?}");
-                return Ok(());
+        // ── backup self-check ─────────────────────────────────────────────────
+        Some(Subcommand::SelfCheck {
+            checksum_file,
+        }) => {
+            commands::self_check::run(checksum_file.as_deref())?;
+        },
+
+        // ── backup check ──────────────────────────────────────────────────────
+        Some(Subcommand::Check {
+            mode,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::check::run(&cli, &cfg, *mode)?;
+        },
+
+        // ── backup gc ─────────────────────────────────────────────────────────
+        Some(Subcommand::Gc) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::gc::run(&cli, &cfg)?;
+        },
+
+        // ── backup mirror ─────────────────────────────────────────────────────
+        Some(Subcommand::Mirror) => {
+            let cfg = load_merged_config(&cli)?;
+            // Fail before the backup, not after it, when [mirror] is unset.
+            commands::mirror::dest_repo(&cfg)?;
+            back_up(&cli, &cfg)?;
+            commands::mirror::run(&cli, &cfg)?;
+        },
+
+        // ── backup backup-stdin ───────────────────────────────────────────────
+        Some(Subcommand::Stdin {
+            filename,
+        }) => {
+            if cli.stdin_password {
+                anyhow::bail!(
+                    "--stdin-password cannot be used with backup-stdin, which reads stdin"
+                );
             }
+            let cfg = load_merged_config(&cli)?;
+            commands::stdin_backup::run(&cli, &cfg, filename)?;
+        },
+
+        // ── backup repair-index ───────────────────────────────────────────────
+        Some(Subcommand::RepairIndex) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::repair_index::run(&cli, &cfg)?;
+        },
+
+        // ── backup forget ─────────────────────────────────────────────────────
+        Some(Subcommand::Forget {
+            snapshots,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::forget::run(&cli, &cfg, snapshots)?;
+        },
+
+        // ── backup log ────────────────────────────────────────────────────────
+        Some(Subcommand::Log {
+            label,
+            since,
+            until,
+        }) => {
+            commands::log::run(label.as_deref(), *since, *until)?;
+        },
 
-            commands::run::run(&cli, &cfg)?;
+        // ── backup completions ────────────────────────────────────────────────
+        Some(Subcommand::Completions {
+            shell,
+        }) => {
+            commands::completions::run(*shell);
         },
+
+        // ── backup export-schema ──────────────────────────────────────────────
+        Some(Subcommand::ExportSchema) => commands::export_schema::run()?,
+
+        // ── backup env ────────────────────────────────────────────────────────
+        Some(Subcommand::Env) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::env_cmd::run(&cli, &cfg);
+        },
+
+        // ── backup show-excludes ──────────────────────────────────────────────
+        Some(Subcommand::ShowExcludes) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::show_excludes::run(&cli, &cfg);
+        },
+
+        // ── backup paths ──────────────────────────────────────────────────────
+        Some(Subcommand::Paths {
+            check,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::paths::run(&cli, &cfg, *check)?;
+        },
+
+        // ── backup config … ───────────────────────────────────────────────────
+        Some(Subcommand::Config {
+            action: ConfigAction::Validate,
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::config_cmd::validate(&cfg, &cli.effective_config_path())?;
+        },
+        Some(Subcommand::Config {
+            action: ConfigAction::Lint {
+                output_format,
+            },
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::config_cmd::lint(&cfg, &cli.effective_config_path(), *output_format)?;
+        },
+        Some(Subcommand::Config {
+            action:
+                ConfigAction::Export {
+                    output,
+                    minimal,
+                },
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::config_cmd::export(&cfg, output.as_deref(), *minimal)?;
+        },
+        Some(Subcommand::Config {
+            action: ConfigAction::Path,
+        }) => {
+            commands::config_cmd::path(&cli.effective_config_path());
+        },
+
+        // ── backup diff-config ────────────────────────────────────────────────
+        Some(Subcommand::DiffConfig {
+            other,
+        }) => {
+            commands::diff_config::run(&cli.effective_config_path(), other)?;
+        },
+
+        // ── backup key … ──────────────────────────────────────────────────────
+        Some(Subcommand::Key {
+            action:
+                KeyAction::ChangePassword {
+                    new_password,
+                    new_password_file,
+                    confirm_password,
+                },
+        }) => {
+            let cfg = load_merged_config(&cli)?;
+            commands::key_change::run(
+                &cli,
+                &cfg,
+                new_password.as_deref(),
+                new_password_file.as_deref(),
+                *confirm_password,
+            )?;
+        },
+
+        // ── backup (default pipeline) ─────────────────────────────────────────
+        None => run_pipeline(&cli)?,
     }
 
     Ok(())
 }
 
+/// The default command: load the merged config, then [`back_up`] with it.
+fn run_pipeline(cli: &Cli) -> Result<()> {
+    let cfg = load_merged_config(cli)?;
+    if cli.print_config {
+        println!("{cfg:#?}");
+        return Ok(());
+    }
+    back_up(cli, &cfg)
+}
+
+/// Validate `cfg`, then run the pipeline and record it in the run log.
+fn back_up(cli: &Cli, cfg: &config::Config) -> Result<()> {
+    let config_path = cli.effective_config_path();
+    let issues = cfg.validate();
+    for issue in &issues {
+        eprintln!("{issue}");
+    }
+    if issues.iter().any(config::ValidationIssue::is_error) {
+        anyhow::bail!("invalid configuration in '{}'", config_path.display());
+    }
+
+    if !cli.quiet {
+        println!();
+        ui::print_banner(env!("CARGO_PKG_VERSION"), &config_path);
+    }
+
+    let started_at = jiff::Timestamp::now();
+    let result = commands::run::run(cli, cfg);
+    if !cli.dry_run {
+        record_run(cli, cfg, started_at, result.is_ok());
+    }
+    result
+}
+
 /// Load configuration from two sources and merge them.
 ///
 /// 1. `~/.config/backup.rs/config.toml` — global defaults (e.g. `[mount]` share/user)
-/// 2. `local_path` (default: `./backup.toml`) — per-project overrides
+/// 2. [`Cli::effective_config_path`] (usually `./backup.toml`) — per-project overrides
 ///
 /// Local values win on a per-field basis.  Either file may be absent.
-fn load_merged_config(local_path: &std::path::Path) -> Result<config::Config> {
-    let global_path = dirs_next::config_dir().map(|d| d.join("backup.rs").join("config.toml"));
+/// A `.env` file next to the local config is loaded first so that
+/// `[repo].password_env` can name a variable defined there.
+/// `--repo` and `--password` (or `--stdin-password`) are applied last and
+/// beat both files; with either password flag, `[repo].password_env` is not
+/// read, so the variable it names may be unset.
+fn load_merged_config(cli: &Cli) -> Result<config::Config> {
+    let local_path = &cli.effective_config_path();
+    let global_path = config::global_config_path();
 
     let global: PartialConfig = global_path
         .as_deref()
-        .and_then(|p| parse_partial(p).ok().flatten())
+        .and_then(|p| {
+            tracing::debug!(path = %p.display(), "loading global config");
+            parse_partial(p)
+                .inspect_err(
+                    |e| tracing::warn!(path = %p.display(), "ignoring global config: {e:#}"),
+                )
+                .ok()
+                .flatten()
+        })
         .unwrap_or_default();
 
+    if let Some(env) = config::load_dotenv(local_path)? {
+        tracing::debug!(path = %env.display(), "loaded .env");
+    }
+
+    tracing::debug!(path = %local_path.display(), "loading local config");
     let local: PartialConfig = parse_partial(local_path)?.unwrap_or_else(|| {
+        tracing::info!(path = %local_path.display(), "local config not found");
         eprintln!(
             "Warning: config file '{}' not found, using defaults.\n\
                  Run 'backup init' to generate a starter config.",
@@ -87,6 +504,57 @@ fn load_merged_config(local_path: &std::path::Path) -> Result<config::Config> {
         );
         PartialConfig::default()
     });
+    if local_path.exists() && local.is_empty() && global.is_empty() {
+        eprintln!(
+            "Warning: config file '{}' sets no options, so every setting is a default \
+                 (repo '{}', sources '.').\n\
+                 Set at least [repo].path, or delete the file and run 'backup init'.",
+            local_path.display(),
+            config::default_repo_path()
+        );
+    }
+
+    let merged = global.merge(local);
+    if cli.stdin_password && merged.repo.password_env.is_some() {
+        anyhow::bail!("--stdin-password cannot be combined with [repo].password_env");
+    }
+    let mut cfg = if cli.password.is_some() || cli.stdin_password {
+        merged.resolve()
+    } else {
+        config::Config::from_env(merged)?
+    };
+    if let Some(ref repo) = cli.repo {
+        tracing::debug!(repo, "repo path overridden by --repo");
+        cfg.repo.path.clone_from(repo);
+    }
+    if let Some(ref password) = cli.password {
+        tracing::debug!("repo password overridden by --password");
+        cfg.repo.password.clone_from(password);
+    }
+    if cli.stdin_password {
+        tracing::debug!("repo password read from stdin");
+        cfg.repo.password = config::read_password_line(std::io::stdin().lock())?;
+    }
+    tracing::debug!(repo = %cfg.repo.path, "config resolved");
+    Ok(cfg)
+}
 
-    Ok(global.merge(local).resolve())
+/// Append this run to the persistent run log.
+///
+/// Failing to write the log must never fail a backup, so errors are only
+/// reported as warnings.
+fn record_run(cli: &Cli, cfg: &config::Config, started_at: jiff::Timestamp, success: bool) {
+    let Some(path) = runlog::default_path() else {
+        tracing::warn!("no data directory; run not logged");
+        return;
+    };
+    let entry = runlog::RunLogEntry {
+        started_at,
+        label: cli.label.clone(),
+        repo: cfg.repo.path.clone(),
+        success,
+    };
+    if let Err(e) = runlog::append(&path, &entry) {
+        tracing::warn!("could not write run log: {e:#}");
+    }
 }
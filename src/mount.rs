@@ -4,11 +4,30 @@
 //!
 //! 1. Runs `mount | grep <share>` to check whether the share is already mounted.  If so, returns a
 //!    success outcome immediately.
-//! 2. Creates the mountpoint (`/home/<user>/nfs/<share>`) with `mkdir -p`.
+//! 2. Creates the mountpoint (`/home/<user>/nfs/<share>`, or `mountpoint` when set) with `mkdir
+//!    -p`.
 //! 3. Calls `doas mount -t nfs <server>:<export> <mountpoint>`.
 //!
-//! The server and NFS export path are looked up from the [`SHARES`] table,
-//! which mirrors the mapping in the original `mount-nas` shell script.
+//! The server and NFS export path are looked up in `[mount.shares]` first,
+//! then in [`BuiltinShareMap::GLOBAL`], which mirrors the mapping in the
+//! original `mount-nas` shell script.
+//!
+//! With `method = { rclone = { remote = "…" } }` step 3 runs
+//! `rclone mount <remote>: <mountpoint> --daemon` instead, and `share` only
+//! names the mountpoint directory.  [`unmount_share`] reverses either method.
+//!
+//! With `timeout_secs` set, a mount command still running after that many
+//! seconds is killed and the stage fails, instead of a hard NFS mount of an
+//! unreachable server hanging the run.
+//!
+//! With `--dry-run` none of these steps run: the outcome reports the mount
+//! command that would have been executed.
+//!
+//! The [`MountedPolicy`] passed to [`mount_share`] changes step 1:
+//! `--force-mount` (or `force_recheck = true`) replaces the `mount` listing
+//! with a check that needs no `doas` — whether the mountpoint sits on a
+//! different device than its parent — and `--skip-if-mounted` reports an
+//! already-mounted share as a skipped stage rather than a success.
 //!
 //! # Config
 //!
@@ -16,27 +35,133 @@
 //! [mount]
 //! share = "new-backups"   # name of the NFS share to mount
 //! user  = "alice"         # optional; defaults to $USER / $LOGNAME
+//! mountpoint = "/Volumes/new-backups"  # optional; replaces /home/<user>/nfs/<share>
+//! method = "nfs"          # optional; or { rclone = { remote = "gdrive" } }
+//! timeout_secs = 30       # optional; kill a mount that hangs
+//! force_recheck = true    # optional; check for a mount without `doas mount`
+//!
+//! [mount.shares]          # optional; extra or replacement NFS sources
+//! media = "media.lan:/export/media"
 //! ```
 //!
 //! Omit the `[mount]` section entirely (or omit `share`) to skip mounting.
 
-use std::process::Command;
+use std::{
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Result, bail};
 
-use crate::{config::MountConfig, ui::StageOutcome};
+use crate::{
+    config::{MountConfig, MountMethod, MountedPolicy},
+    ui::{KILL_GRACE, StageOutcome, reap_within, skipped_stage},
+};
 
 // ─── Share map ────────────────────────────────────────────────────────────────
 
-/// Full NFS source string (`server:/export/path`) for `name`.
+/// Known share names and their NFS sources (`server:/export/path`).
+const SHARES: &[(&str, &str)] = &[
+    ("new-backups", "nas.lan:/mnt/vol2/backups"),
+    ("new-documents", "documents.lan:/documents"),
+    ("isos", "nas.lan:/mnt/vol1/isos"),
+    ("pictures", "nas.lan:/mnt/vol1/pictures"),
+    ("movies", "nas.lan:/mnt/vol1/movies"),
+    ("videos", "nas.lan:/mnt/vol1/videos"),
+    ("backups", "nas.lan:/mnt/vol1/backups"),
+    ("owncloud", "nas.lan:/mnt/vol1/owncloud"),
+    ("lan-share", "nas.lan:/mnt/vol1/lan-share"),
+    ("repos", "nas.lan:/mnt/vol1/repos"),
+    ("documents", "nas.lan:/mnt/vol1/documents"),
+];
+
+/// A fixed table of share names and their NFS sources.
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinShareMap {
+    entries: &'static [(&'static str, &'static str)],
+}
+
+impl BuiltinShareMap {
+    /// The shares compiled into this binary.
+    pub const GLOBAL: Self = Self {
+        entries: SHARES,
+    };
+
+    /// NFS source for the share called `name`.
+    pub fn get(&self, name: &str) -> Option<&'static str> {
+        self.entries
+            .iter()
+            .find(|(share, _)| *share == name)
+            .map(|&(_, source)| source)
+    }
+
+    /// Every `(name, source)` pair, in table order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        self.entries.iter().copied()
+    }
+}
+
+/// Full NFS source string (`server:/export/path`) for a built-in share.
 fn nfs_source(name: &str) -> Option<String> {
-    match name {
-        "new-documents" => Some("documents.lan:/documents".into()),
-        "new-backups" => Some("nas.lan:/mnt/vol2/backups".into()),
-        "isos" | "pictures" | "movies" | "videos" | "backups" | "owncloud" | "lan-share"
-        | "repos" | "documents" => Some(format!("nas.lan:/mnt/vol1/{name}")),
-        _ => None,
+    BuiltinShareMap::GLOBAL.get(name).map(String::from)
+}
+
+/// NFS source for `name`: `[mount.shares]` first, then the built-in map.
+fn share_source(cfg: &MountConfig, name: &str) -> Result<String> {
+    cfg.shares
+        .get(name)
+        .cloned()
+        .or_else(|| nfs_source(name))
+        .with_context(|| format!("unknown share name: '{name}'"))
+}
+
+/// Server part of an NFS source: `nas.lan` for `nas.lan:/mnt/vol2/backups`.
+///
+/// A bracketed IPv6 server (`[fd00::2]:/export`) is returned without its
+/// brackets.  `None` when there is no `:` separator or the server is empty.
+pub fn nfs_host(source: &str) -> Option<&str> {
+    let host = match source.strip_prefix('[') {
+        Some(rest) => rest.split_once("]:")?.0,
+        None => source.split_once(':')?.0,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// NFS server of the configured share, for `backup mount-test`.
+///
+/// Fails when `[mount].share` is unset or unknown, or the share is mounted
+/// with rclone, which has no NFS server to probe.
+pub fn configured_nfs_host(cfg: &MountConfig) -> Result<String> {
+    let share = configured_share(cfg)?;
+    if !matches!(cfg.method, MountMethod::Nfs) {
+        bail!("[mount].share '{share}' is mounted with rclone, not NFS");
     }
+    let source = share_source(cfg, share)?;
+    nfs_host(&source)
+        .map(String::from)
+        .with_context(|| format!("no server in NFS source '{source}'"))
+}
+
+/// Every share name accepted by `[mount].share`, paired with its NFS source.
+///
+/// Built-in shares come first in table order, with any `[mount.shares]`
+/// replacement applied, followed by the shares only the config defines.
+/// This is what `backup mount --list-shares` prints.
+pub fn list_known_shares(cfg: &MountConfig) -> Vec<(&str, &str)> {
+    let builtin = BuiltinShareMap::GLOBAL;
+    let mut shares: Vec<(&str, &str)> = builtin
+        .iter()
+        .map(|(name, source)| (name, cfg.shares.get(name).map_or(source, String::as_str)))
+        .collect();
+    shares.extend(
+        cfg.shares
+            .iter()
+            .filter(|(name, _)| builtin.get(name).is_none())
+            .map(|(name, source)| (name.as_str(), source.as_str())),
+    );
+    shares
 }
 
 // ─── Public entry point ───────────────────────────────────────────────────────
@@ -45,84 +170,316 @@ fn nfs_source(name: &str) -> Option<String> {
 ///
 /// Equivalent to running `mount-nas <share>` but implemented natively:
 ///
-/// 1. If the share is already mounted, returns success immediately.
+/// 1. If the share is already mounted, returns success immediately — or a skipped outcome under
+///    [`MountedPolicy::Skip`].  Under [`MountedPolicy::Force`] only the mountpoint's device is
+///    compared with its parent's, without running `doas mount`.
 /// 2. Creates `/home/<user>/nfs/<share>` with `mkdir -p`.
 /// 3. Runs `doas mount -t nfs <server>:<export> <mountpoint>`.
 ///
+/// When `dry_run` is set, nothing is spawned or created; the outcome's
+/// stdout reads `would run: doas mount -t nfs …`.
+///
 /// Returns a failed outcome (without panicking) if:
 /// - `[mount].share` is not set in the config
 /// - the share name is not in the known share map
 /// - any subprocess fails
-pub fn mount_share(cfg: &MountConfig) -> StageOutcome {
-    match try_mount(cfg) {
+pub fn mount_share(cfg: &MountConfig, dry_run: bool, policy: MountedPolicy) -> StageOutcome {
+    tracing::debug!(share = ?cfg.share, dry_run, ?policy, "mount stage started");
+    match try_mount(cfg, dry_run, policy) {
+        Ok(None) => skipped_stage("Mount"),
+        Ok(Some(msg)) => outcome("Mount", Ok(msg)),
+        Err(e) => outcome("Mount", Err(e)),
+    }
+}
+
+/// Unmount the configured share, returning a [`StageOutcome`].
+///
+/// Runs `doas umount <mountpoint>` for NFS and `fusermount3 -u <mountpoint>`
+/// for rclone.  With `dry_run`, only reports the command.
+pub fn unmount_share(cfg: &MountConfig, dry_run: bool) -> StageOutcome {
+    tracing::debug!(share = ?cfg.share, dry_run, "unmount started");
+    outcome("Unmount", try_unmount(cfg, dry_run))
+}
+
+fn outcome(label: &str, result: Result<String>) -> StageOutcome {
+    match result {
         Ok(msg) => StageOutcome {
-            label: "Mount".into(),
+            label: label.into(),
+            stage_id: None,
             success: true,
             stdout: msg,
             stderr: String::new(),
             error: None,
+            error_kind: None,
         },
         Err(e) => StageOutcome {
-            label: "Mount".into(),
+            label: label.into(),
+            stage_id: None,
             success: false,
             stdout: String::new(),
             stderr: String::new(),
             error: Some(e.to_string()),
+            error_kind: None,
         },
     }
 }
 
 // ─── Implementation ───────────────────────────────────────────────────────────
 
-fn try_mount(cfg: &MountConfig) -> Result<String> {
-    let share = cfg
-        .share
+/// The mount command for the configured share, without running it.
+///
+/// NFS: `doas mount -t nfs <server>:<export> <mountpoint>`.
+/// rclone: `rclone mount <remote>: <mountpoint> --daemon`.
+pub fn build_mount_command(cfg: &MountConfig) -> Result<Vec<String>> {
+    let share = configured_share(cfg)?;
+    let mountpoint = mountpoint(cfg, share);
+    match &cfg.method {
+        MountMethod::Nfs => {
+            let source = share_source(cfg, share)?;
+            Ok(vec![
+                "doas".into(),
+                "mount".into(),
+                "-t".into(),
+                "nfs".into(),
+                source,
+                mountpoint,
+            ])
+        },
+        MountMethod::Rclone {
+            remote,
+        } => Ok(vec![
+            "rclone".into(),
+            "mount".into(),
+            rclone_source(remote)?,
+            mountpoint,
+            "--daemon".into(),
+        ]),
+    }
+}
+
+/// The unmount command for the configured share, without running it.
+///
+/// NFS: `doas umount <mountpoint>`.  rclone: `fusermount3 -u <mountpoint>`.
+pub fn build_unmount_command(cfg: &MountConfig) -> Result<Vec<String>> {
+    let share = configured_share(cfg)?;
+    let mountpoint = mountpoint(cfg, share);
+    Ok(match cfg.method {
+        MountMethod::Nfs => vec!["doas".into(), "umount".into(), mountpoint],
+        MountMethod::Rclone {
+            ..
+        } => vec!["fusermount3".into(), "-u".into(), mountpoint],
+    })
+}
+
+/// `<remote>:` — the remote's root, accepting names with or without the colon.
+fn rclone_source(remote: &str) -> Result<String> {
+    let name = remote.trim_end_matches(':');
+    if name.is_empty() {
+        bail!("[mount].method rclone remote is empty");
+    }
+    Ok(format!("{name}:"))
+}
+
+fn configured_share(cfg: &MountConfig) -> Result<&str> {
+    cfg.share
         .as_deref()
-        .context("[mount].share is not set — add `share = \"new-backups\"` to backup.toml")?;
+        .context("[mount].share is not set — add `share = \"new-backups\"` to backup.toml")
+}
+
+/// Where the configured share is mounted, or `None` when `[mount].share` is
+/// unset.  Paths below it only exist once the Mount stage has run.
+pub fn configured_mountpoint(cfg: &MountConfig) -> Option<PathBuf> {
+    cfg.share
+        .as_deref()
+        .map(|share| PathBuf::from(mountpoint(cfg, share)))
+}
 
-    let user = effective_user(cfg);
-    let mountpoint = format!("/home/{user}/nfs/{share}");
+/// `[mount].mountpoint` when set, else `/home/<user>/nfs/<share>`.
+fn mountpoint(cfg: &MountConfig, share: &str) -> String {
+    cfg.mountpoint.as_ref().map_or_else(
+        || format!("/home/{}/nfs/{share}", effective_user(cfg)),
+        |path| path.display().to_string(),
+    )
+}
+
+/// What [`try_mount`] does once it knows about an existing mount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Precheck {
+    /// Run the mount command.
+    Mount,
+    /// Already mounted; report success.
+    AlreadyMounted,
+    /// Already mounted; report the stage as skipped.
+    Skip,
+}
+
+/// Decide whether to mount under `policy`.
+///
+/// [`MountedPolicy::Force`] asks only `is_mount_root`, which needs no
+/// privileges, and never runs `is_mounted` (the `doas mount` listing); the
+/// other policies ask only `is_mounted`.
+fn precheck(
+    policy: MountedPolicy,
+    is_mounted: impl FnOnce() -> Result<bool>,
+    is_mount_root: impl FnOnce() -> bool,
+) -> Result<Precheck> {
+    if policy == MountedPolicy::Force {
+        return Ok(if is_mount_root() {
+            Precheck::AlreadyMounted
+        } else {
+            Precheck::Mount
+        });
+    }
+    Ok(match (is_mounted()?, policy) {
+        (false, _) => Precheck::Mount,
+        (true, MountedPolicy::Skip) => Precheck::Skip,
+        (true, _) => Precheck::AlreadyMounted,
+    })
+}
+
+/// The outcome message, or `None` when the stage should show as skipped.
+fn try_mount(cfg: &MountConfig, dry_run: bool, policy: MountedPolicy) -> Result<Option<String>> {
+    let share = configured_share(cfg)?;
+    let command = build_mount_command(cfg)?;
+    if dry_run {
+        return Ok(Some(format!("would run: {}", command.join(" "))));
+    }
+
+    let mountpoint = mountpoint(cfg, share);
+    tracing::debug!(share, mountpoint, "attempting mount");
 
     // ── 1. Already mounted? ───────────────────────────────────────────────────
-    if is_mounted(share)? {
-        return Ok(format!("{share} already mounted at {mountpoint}"));
+    match precheck(
+        policy,
+        || is_mounted(share, &cfg.method),
+        || is_mount_root(Path::new(&mountpoint)),
+    )? {
+        Precheck::Mount => {},
+        Precheck::AlreadyMounted => {
+            tracing::debug!(share, "share already mounted");
+            return Ok(Some(format!("{share} already mounted at {mountpoint}")));
+        },
+        Precheck::Skip => {
+            tracing::debug!(share, "share already mounted; skipping");
+            return Ok(None);
+        },
     }
 
     // ── 2. Create mountpoint ──────────────────────────────────────────────────
     std::fs::create_dir_all(&mountpoint).with_context(|| format!("mkdir -p {mountpoint}"))?;
 
     // ── 3. Mount ──────────────────────────────────────────────────────────────
-    let source = nfs_source(share).with_context(|| format!("unknown share name: '{share}'"))?;
+    let source = match cfg.method {
+        MountMethod::Nfs => &command[4],
+        MountMethod::Rclone {
+            ..
+        } => &command[2],
+    };
+    tracing::info!(source, mountpoint, "mounting share");
 
-    let status = Command::new("doas")
-        .args(["mount", "-t", "nfs", &source, &mountpoint])
-        .status()
-        .context("failed to spawn doas mount")?;
+    spawn(&command, cfg.timeout_secs.map(Duration::from_secs))?;
+    Ok(Some(format!("mounted {source} → {mountpoint}")))
+}
 
-    if !status.success() {
-        bail!("doas mount -t nfs {source} {mountpoint} exited non-zero");
+fn try_unmount(cfg: &MountConfig, dry_run: bool) -> Result<String> {
+    let share = configured_share(cfg)?;
+    let command = build_unmount_command(cfg)?;
+    if dry_run {
+        return Ok(format!("would run: {}", command.join(" ")));
     }
 
-    Ok(format!("mounted {source} → {mountpoint}"))
+    let mountpoint = mountpoint(cfg, share);
+    tracing::info!(share, mountpoint, "unmounting share");
+    spawn(&command, None)?;
+    Ok(format!("unmounted {mountpoint}"))
+}
+
+/// Run `command` with inherited stdio, failing on a non-zero exit.
+///
+/// With a `timeout`, a command still running when it expires is killed and
+/// reported as an error.  stdio stays inherited so `doas` can still prompt.
+fn spawn(command: &[String], timeout: Option<Duration>) -> Result<()> {
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .spawn()
+        .with_context(|| format!("failed to spawn {}", command[0]))?;
+
+    let status = match timeout {
+        None => child.wait().context("failed to wait for child")?,
+        Some(limit) => {
+            let deadline = Instant::now() + limit;
+            loop {
+                if let Some(status) = child.try_wait().context("failed to poll child")? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    // The child may have exited between the poll and the kill,
+                    // which makes the kill fail harmlessly.  A root `doas
+                    // mount` may also be beyond our reach, or stuck in
+                    // uninterruptible sleep on a dead server, so give it a
+                    // bounded grace period to go away rather than blocking on
+                    // `wait`.
+                    if let Err(e) = child.kill() {
+                        tracing::warn!("could not kill {}: {e}", command[0]);
+                    }
+                    reap_within(&mut child, KILL_GRACE);
+                    bail!(
+                        "{} did not finish within {limit:?} and was killed — is the server reachable?",
+                        command.join(" ")
+                    );
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        },
+    };
+
+    if !status.success() {
+        bail!("{} exited non-zero", command.join(" "));
+    }
+    Ok(())
 }
 
 /// Check whether `share` appears in the output of `mount`.
 ///
 /// Replicates `doas mount | grep "$1" | wc -l` and tests that the count is 1.
 /// We use `doas mount` to match the original script's behaviour on systems
-/// where unprivileged users cannot run `mount`.
-fn is_mounted(share: &str) -> Result<bool> {
-    let output = Command::new("doas")
-        .arg("mount")
+/// where unprivileged users cannot run `mount`; rclone mounts are made
+/// without `doas`, so plain `mount` is used for those.
+fn is_mounted(share: &str, method: &MountMethod) -> Result<bool> {
+    let list: &[&str] = match method {
+        MountMethod::Nfs => &["doas", "mount"],
+        MountMethod::Rclone {
+            ..
+        } => &["mount"],
+    };
+    let output = Command::new(list[0])
+        .args(&list[1..])
         .output()
-        .context("failed to run doas mount")?;
+        .with_context(|| format!("failed to run {}", list.join(" ")))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let count = stdout.lines().filter(|l| l.contains(share)).count();
     Ok(count >= 1)
 }
 
+/// Whether `path` is the root of a mounted filesystem, i.e. lives on a
+/// different device than its parent.
+///
+/// Needs no privileges, unlike [`is_mounted`].  A path that cannot be
+/// inspected — typically one that does not exist yet — counts as not
+/// mounted.
+fn is_mount_root(path: &Path) -> bool {
+    let parent = path.parent().unwrap_or(path);
+    match (std::fs::metadata(path), std::fs::metadata(parent)) {
+        (Ok(here), Ok(above)) => here.dev() != above.dev(),
+        _ => false,
+    }
+}
+
 /// Resolve the effective username from config, `$USER`, or `$LOGNAME`.
+///
+/// Only used to build the default mountpoint, so it does not matter when
+/// `[mount].mountpoint` is set.
 fn effective_user(cfg: &MountConfig) -> String {
     cfg.user
         .clone()
@@ -135,6 +492,8 @@ fn effective_user(cfg: &MountConfig) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
     use super::*;
 
     // ── nfs_source ────────────────────────────────────────────────────────────
@@ -185,6 +544,125 @@ mod tests {
         assert!(nfs_source("not-a-real-share").is_none());
     }
 
+    // ── BuiltinShareMap ───────────────────────────────────────────────────────
+
+    #[test]
+    fn builtin_map_get_finds_known_names_only() {
+        let map = BuiltinShareMap::GLOBAL;
+        assert_eq!(map.get("isos"), Some("nas.lan:/mnt/vol1/isos"));
+        assert_eq!(map.get("not-a-real-share"), None);
+    }
+
+    #[test]
+    fn builtin_map_iter_keeps_table_order() {
+        let names: Vec<&str> = BuiltinShareMap::GLOBAL.iter().map(|(n, _)| n).collect();
+        assert_eq!(names.len(), SHARES.len());
+        assert_eq!(names[..2], ["new-backups", "new-documents"]);
+        assert_eq!(names.last(), Some(&"documents"));
+    }
+
+    // ── [mount.shares] ────────────────────────────────────────────────────────
+
+    fn cfg_with_shares(share: &str, shares: &[(&str, &str)]) -> MountConfig {
+        MountConfig {
+            share: Some(share.into()),
+            user: Some("alice".into()),
+            shares: shares
+                .iter()
+                .map(|&(name, source)| (name.into(), source.into()))
+                .collect(),
+            ..MountConfig::default()
+        }
+    }
+
+    #[test]
+    fn config_share_is_mountable() {
+        let cfg = cfg_with_shares("media", &[("media", "media.lan:/export/media")]);
+        assert_eq!(
+            build_mount_command(&cfg).unwrap()[4],
+            "media.lan:/export/media"
+        );
+        assert_eq!(configured_nfs_host(&cfg).unwrap(), "media.lan");
+    }
+
+    #[test]
+    fn config_share_replaces_builtin_source() {
+        let cfg = cfg_with_shares("isos", &[("isos", "backup.lan:/isos")]);
+        assert_eq!(build_mount_command(&cfg).unwrap()[4], "backup.lan:/isos");
+    }
+
+    #[test]
+    fn known_shares_list_config_entries_after_builtins() {
+        let cfg = cfg_with_shares("isos", &[
+            ("isos", "backup.lan:/isos"),
+            ("media", "media.lan:/export/media"),
+        ]);
+        let shares = list_known_shares(&cfg);
+        assert_eq!(shares.len(), SHARES.len() + 1);
+        assert!(shares.contains(&("isos", "backup.lan:/isos")));
+        assert!(!shares.contains(&("isos", "nas.lan:/mnt/vol1/isos")));
+        assert_eq!(shares.last(), Some(&("media", "media.lan:/export/media")));
+    }
+
+    // ── list_known_shares ─────────────────────────────────────────────────────
+
+    #[test]
+    fn known_shares_include_new_backups_and_new_documents() {
+        let cfg = MountConfig::default();
+        let shares = list_known_shares(&cfg);
+        assert!(shares.contains(&("new-backups", "nas.lan:/mnt/vol2/backups")));
+        assert!(shares.contains(&("new-documents", "documents.lan:/documents")));
+    }
+
+    #[test]
+    fn known_shares_agree_with_nfs_source() {
+        for (name, source) in list_known_shares(&MountConfig::default()) {
+            assert_eq!(nfs_source(name).as_deref(), Some(source));
+        }
+    }
+
+    // ── nfs_host ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn nfs_host_is_the_part_before_the_colon() {
+        assert_eq!(nfs_host("nas.lan:/mnt/vol2/backups"), Some("nas.lan"));
+        assert_eq!(nfs_host("192.168.1.10:/export"), Some("192.168.1.10"));
+    }
+
+    #[test]
+    fn nfs_host_strips_ipv6_brackets() {
+        assert_eq!(nfs_host("[fd00::2]:/export"), Some("fd00::2"));
+    }
+
+    #[test]
+    fn nfs_host_rejects_sources_without_a_server() {
+        assert_eq!(nfs_host("/mnt/local"), None);
+        assert_eq!(nfs_host(":/export"), None);
+        assert_eq!(nfs_host("[fd00::2/export"), None);
+    }
+
+    #[test]
+    fn every_known_share_has_a_host() {
+        for (name, source) in list_known_shares(&MountConfig::default()) {
+            assert!(nfs_host(source).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn configured_nfs_host_needs_an_nfs_share() {
+        let mut cfg = MountConfig {
+            share: Some("new-documents".into()),
+            ..MountConfig::default()
+        };
+        assert_eq!(configured_nfs_host(&cfg).unwrap(), "documents.lan");
+
+        cfg.method = MountMethod::Rclone {
+            remote: "gdrive".into(),
+        };
+        assert!(configured_nfs_host(&cfg).is_err());
+        assert!(configured_nfs_host(&MountConfig::default()).is_err());
+    }
+
     // ── effective_user ────────────────────────────────────────────────────────
 
     #[test]
@@ -192,6 +670,11 @@ mod tests {
         let cfg = MountConfig {
             share: Some("new-backups".into()),
             user: Some("alice".into()),
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
         };
         assert_eq!(effective_user(&cfg), "alice");
     }
@@ -201,6 +684,11 @@ mod tests {
         let cfg = MountConfig {
             share: Some("new-backups".into()),
             user: None,
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
         };
         let got = effective_user(&cfg);
         // Should be non-empty (either $USER, $LOGNAME, or the "user" fallback).
@@ -214,8 +702,13 @@ mod tests {
         let cfg = MountConfig {
             share: None,
             user: None,
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
         };
-        let outcome = mount_share(&cfg);
+        let outcome = mount_share(&cfg, false, MountedPolicy::Reuse);
         assert!(!outcome.success);
         assert!(
             outcome
@@ -226,6 +719,348 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mount_share_emits_debug_event() {
+        let cfg = MountConfig {
+            share: None,
+            user: None,
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        let logs = crate::logging::capture(|| {
+            mount_share(&cfg, false, MountedPolicy::Reuse);
+        });
+        assert!(logs.contains("mount stage started"));
+    }
+
+    // ── build_mount_command / dry run ──────────────────────────────────────────
+
+    #[test]
+    fn mount_command_uses_share_source_and_user_home() {
+        let cfg = MountConfig {
+            share: Some("isos".into()),
+            user: Some("alice".into()),
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        assert_eq!(build_mount_command(&cfg).unwrap(), [
+            "doas",
+            "mount",
+            "-t",
+            "nfs",
+            "nas.lan:/mnt/vol1/isos",
+            "/home/alice/nfs/isos"
+        ]);
+    }
+
+    // ── mountpoint ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn default_mountpoint_is_built_from_user_and_share() {
+        let cfg = MountConfig {
+            share: Some("new-backups".into()),
+            user: Some("alice".into()),
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        assert_eq!(
+            mountpoint(&cfg, "new-backups"),
+            "/home/alice/nfs/new-backups"
+        );
+    }
+
+    #[test]
+    fn mountpoint_override_ignores_user_and_share() {
+        let cfg = MountConfig {
+            share: Some("isos".into()),
+            user: Some("alice".into()),
+            mountpoint: Some("/Volumes/isos".into()),
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        assert_eq!(mountpoint(&cfg, "isos"), "/Volumes/isos");
+        assert_eq!(build_mount_command(&cfg).unwrap(), [
+            "doas",
+            "mount",
+            "-t",
+            "nfs",
+            "nas.lan:/mnt/vol1/isos",
+            "/Volumes/isos"
+        ]);
+        assert_eq!(build_unmount_command(&cfg).unwrap(), [
+            "doas",
+            "umount",
+            "/Volumes/isos"
+        ]);
+    }
+
+    #[test]
+    fn mount_command_rejects_unknown_share() {
+        let cfg = MountConfig {
+            share: Some("not-a-real-share".into()),
+            user: None,
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        let err = build_mount_command(&cfg).unwrap_err().to_string();
+        assert!(err.contains("unknown share name"), "got: {err}");
+    }
+
+    fn rclone_cfg(remote: &str) -> MountConfig {
+        MountConfig {
+            share: Some("cloud-backups".into()),
+            user: Some("alice".into()),
+            mountpoint: None,
+            method: MountMethod::Rclone {
+                remote: remote.into(),
+            },
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        }
+    }
+
+    #[test]
+    fn rclone_mount_command_daemonises_remote_root() {
+        assert_eq!(build_mount_command(&rclone_cfg("gdrive")).unwrap(), [
+            "rclone",
+            "mount",
+            "gdrive:",
+            "/home/alice/nfs/cloud-backups",
+            "--daemon"
+        ]);
+    }
+
+    #[test]
+    fn rclone_remote_may_carry_its_colon() {
+        assert_eq!(
+            build_mount_command(&rclone_cfg("gdrive:")).unwrap()[2],
+            "gdrive:"
+        );
+    }
+
+    #[test]
+    fn rclone_remote_must_not_be_empty() {
+        let err = build_mount_command(&rclone_cfg(":"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("remote is empty"), "got: {err}");
+    }
+
+    #[test]
+    fn rclone_share_need_not_be_a_known_nfs_share() {
+        // `share` only names the mountpoint for rclone.
+        assert!(nfs_source("cloud-backups").is_none());
+        assert!(build_mount_command(&rclone_cfg("gdrive")).is_ok());
+    }
+
+    #[test]
+    fn unmount_command_matches_method() {
+        assert_eq!(build_unmount_command(&rclone_cfg("gdrive")).unwrap(), [
+            "fusermount3",
+            "-u",
+            "/home/alice/nfs/cloud-backups"
+        ]);
+        let nfs = MountConfig {
+            share: Some("isos".into()),
+            user: Some("alice".into()),
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        assert_eq!(build_unmount_command(&nfs).unwrap(), [
+            "doas",
+            "umount",
+            "/home/alice/nfs/isos"
+        ]);
+    }
+
+    #[test]
+    fn unmount_dry_run_reports_command() {
+        let outcome = unmount_share(&rclone_cfg("gdrive"), true);
+        assert!(outcome.success, "error: {:?}", outcome.error);
+        assert_eq!(
+            outcome.stdout,
+            "would run: fusermount3 -u /home/alice/nfs/cloud-backups"
+        );
+    }
+
+    #[test]
+    fn dry_run_reports_command_without_running_it() {
+        // A user whose home cannot exist: if the dry run created the
+        // mountpoint or spawned `doas`, this test would see the directory or
+        // a spawn failure.
+        let user = "backup-rs-dry-run-test-user";
+        let cfg = MountConfig {
+            share: Some("new-backups".into()),
+            user: Some(user.into()),
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        let logs = crate::logging::capture(|| {
+            let outcome = mount_share(&cfg, true, MountedPolicy::Reuse);
+            assert!(outcome.success, "error: {:?}", outcome.error);
+            assert_eq!(
+                outcome.stdout,
+                format!(
+                    "would run: doas mount -t nfs nas.lan:/mnt/vol2/backups /home/{user}/nfs/new-backups"
+                )
+            );
+        });
+        assert!(!logs.contains("attempting mount"), "no mount was attempted");
+        assert!(!Path::new(&format!("/home/{user}")).exists());
+    }
+
+    #[test]
+    fn dry_run_still_reports_missing_share() {
+        let cfg = MountConfig {
+            share: None,
+            user: None,
+            mountpoint: None,
+            method: MountMethod::Nfs,
+            timeout_secs: None,
+            shares: BTreeMap::new(),
+            force_recheck: false,
+        };
+        assert!(mount_share(&cfg, true, MountedPolicy::Reuse).failed());
+    }
+
+    // ── MountedPolicy ─────────────────────────────────────────────────────────
+
+    fn no_mount_root() -> bool {
+        panic!("the device check only runs under --force-mount")
+    }
+
+    #[test]
+    fn force_never_lists_mounts() {
+        let decision = precheck(
+            MountedPolicy::Force,
+            || panic!("is_mounted must not run under --force-mount"),
+            || false,
+        );
+        assert_eq!(decision.unwrap(), Precheck::Mount);
+    }
+
+    #[test]
+    fn force_does_not_mount_over_an_existing_mount() {
+        let decision = precheck(
+            MountedPolicy::Force,
+            || panic!("is_mounted must not run under --force-mount"),
+            || true,
+        );
+        assert_eq!(decision.unwrap(), Precheck::AlreadyMounted);
+    }
+
+    #[test]
+    fn force_mounts_even_where_the_listing_would_fail() {
+        // e.g. `doas mount` denied by doas.conf
+        let decision = precheck(
+            MountedPolicy::Force,
+            || bail!("doas: operation not permitted"),
+            || false,
+        );
+        assert_eq!(decision.unwrap(), Precheck::Mount);
+        assert!(
+            precheck(
+                MountedPolicy::Reuse,
+                || bail!("doas: operation not permitted"),
+                no_mount_root
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn skip_if_mounted_skips_only_a_mounted_share() {
+        assert_eq!(
+            precheck(MountedPolicy::Skip, || Ok(true), no_mount_root).unwrap(),
+            Precheck::Skip
+        );
+        assert_eq!(
+            precheck(MountedPolicy::Skip, || Ok(false), no_mount_root).unwrap(),
+            Precheck::Mount
+        );
+    }
+
+    #[test]
+    fn default_policy_reuses_a_mounted_share() {
+        assert_eq!(
+            precheck(MountedPolicy::Reuse, || Ok(true), no_mount_root).unwrap(),
+            Precheck::AlreadyMounted
+        );
+        assert_eq!(
+            precheck(MountedPolicy::Reuse, || Ok(false), no_mount_root).unwrap(),
+            Precheck::Mount
+        );
+    }
+
+    #[test]
+    fn plain_directory_is_not_a_mount_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mountpoint = dir.path().join("share");
+        assert!(!is_mount_root(&mountpoint), "missing path is not mounted");
+        std::fs::create_dir(&mountpoint).unwrap();
+        assert!(!is_mount_root(&mountpoint));
+    }
+
+    #[test]
+    fn proc_is_a_mount_root() {
+        // /proc is its own filesystem on any Linux host running these tests.
+        if Path::new("/proc/self").exists() {
+            assert!(is_mount_root(Path::new("/proc")));
+        }
+    }
+
+    // ── spawn timeout ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn spawn_kills_a_mount_that_outlives_its_timeout() {
+        let slow_mount = ["sleep".to_string(), "5".to_string()];
+        let start = Instant::now();
+        let err = spawn(&slow_mount, Some(Duration::from_millis(200))).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("sleep 5 did not finish within 200ms and was killed"),
+            "got: {err}"
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(4),
+            "mount should have been killed, not waited for"
+        );
+    }
+
+    #[test]
+    fn spawn_lets_a_quick_mount_finish_within_its_timeout() {
+        let quick = ["true".to_string()];
+        spawn(&quick, Some(Duration::from_secs(5))).unwrap();
+    }
+
+    #[test]
+    fn spawn_reports_non_zero_exit_under_a_timeout() {
+        let failing = ["false".to_string()];
+        let err = spawn(&failing, Some(Duration::from_secs(5))).unwrap_err();
+        assert_eq!(err.to_string(), "false exited non-zero");
+    }
+
     // ── insta snapshots ───────────────────────────────────────────────────────
 
     #[test]
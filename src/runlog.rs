@@ -0,0 +1,134 @@
+//! Persistent run log — one JSON line per pipeline run.
+//!
+//! # Location
+//!
+//! `$XDG_DATA_HOME/backup.rs/runs.jsonl` (usually
+//! `~/.local/share/backup.rs/runs.jsonl`).  The file is created on the first
+//! recorded run and only ever appended to.
+//!
+//! # Format
+//!
+//! ```text
+//! {"started_at":"2026-10-16T09:30:00Z","label":"before-major-upgrade","repo":"/mnt/nas/rustic/app","success":true}
+//! {"started_at":"2026-10-17T09:30:00Z","label":null,"repo":"/mnt/nas/rustic/app","success":false}
+//! ```
+//!
+//! Dry runs are not recorded — they change nothing worth auditing.
+
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// One pipeline run, as stored in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunLogEntry {
+    /// When the pipeline started.
+    pub started_at: jiff::Timestamp,
+
+    /// Free-form annotation from `--label`, also attached to the snapshot.
+    pub label: Option<String>,
+
+    /// Repository the run targeted.
+    pub repo: String,
+
+    /// Whether every stage succeeded.
+    pub success: bool,
+}
+
+/// Default log location, or `None` when the platform has no data directory.
+pub fn default_path() -> Option<PathBuf> {
+    dirs_next::data_dir().map(|d| d.join("backup.rs").join("runs.jsonl"))
+}
+
+/// Append `entry` to the log at `path`, creating the file and its parent
+/// directory if needed.
+pub fn append(path: &Path, entry: &RunLogEntry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("mkdir -p {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(entry).context("serialising run log entry")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(line.as_bytes()))
+        .with_context(|| format!("appending to {}", path.display()))
+}
+
+/// Read every entry from the log at `path`, oldest first.
+///
+/// A missing file is an empty log.  Blank lines are skipped; any other line
+/// that does not parse is an error naming its line number.
+pub fn read(path: &Path) -> Result<Vec<RunLogEntry>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("parsing {} line {}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: Option<&str>, success: bool) -> RunLogEntry {
+        RunLogEntry {
+            started_at: "2026-10-16T09:30:00Z".parse().unwrap(),
+            label: label.map(Into::into),
+            repo: "/tmp/repo".into(),
+            success,
+        }
+    }
+
+    #[test]
+    fn entries_roundtrip_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("runs.jsonl");
+        let written = vec![
+            entry(Some("before-major-upgrade"), true),
+            entry(None, false),
+        ];
+        for e in &written {
+            append(&path, e).unwrap();
+        }
+        assert_eq!(read(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn label_is_stored_in_the_json_line() {
+        let line = serde_json::to_string(&entry(Some("nightly"), true)).unwrap();
+        assert!(line.contains(r#""label":"nightly""#), "got: {line}");
+        assert!(line.contains(r#""started_at":"2026-10-16T09:30:00Z""#));
+    }
+
+    #[test]
+    fn missing_log_reads_as_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read(&dir.path().join("runs.jsonl")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn corrupt_line_reports_line_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runs.jsonl");
+        std::fs::write(&path, "\nnot json\n").unwrap();
+        let err = format!("{:#}", read(&path).unwrap_err());
+        assert!(err.contains("line 2"), "got: {err}");
+    }
+}
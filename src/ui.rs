@@ -13,25 +13,31 @@
 //! # Typical usage
 //!
 //! ```no_run
-//! use crate::ui::{run_stage, StageOutcome};
+//! use crate::ui::{run_stage, StageOutcome, UiContext};
 //!
-//! let outcome = run_stage("Check", || {
-//!     // … call rustic, return Ok(()) or Err(…) …
-//!     Ok(())
-//! });
-//! outcome.print();
+//! let ui = UiContext::default();
+//! let outcome = run_stage(ui, "Check", &["rustic".into(), "check".into()], None);
+//! outcome.print(ui);
 //! if outcome.failed() { std::process::exit(1); }
 //! ```
 
 use std::{
-    process::{Command, Output, Stdio},
-    time::Duration,
+    fmt::Write as _,
+    io::Read,
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::{cli::Cli, runner::redacted};
+
 // ─── Icons ───────────────────────────────────────────────────────────────────
 
 /// Braille spinner frames — same style as indicatif's default.
@@ -50,6 +56,40 @@ fn icon_done() -> console::StyledObject<&'static str> {
     style("✓").cyan().bold()
 }
 
+// ─── Display context ──────────────────────────────────────────────────────────
+
+/// Display settings, built once from the command line and passed to every
+/// function that draws stage progress or results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UiContext {
+    /// `--quiet`: no spinners (the banner is left out by the caller).
+    pub quiet: bool,
+    /// `--no-color`: stage lines and the summary are printed without styling.
+    pub no_color: bool,
+    /// `--verbose`: successful stages also replay what rustic wrote to stderr.
+    pub verbose: bool,
+}
+
+impl UiContext {
+    /// The display settings `cli` asks for.
+    pub const fn from_cli(cli: &Cli) -> Self {
+        Self {
+            quiet: cli.quiet,
+            no_color: cli.no_color,
+            verbose: cli.verbose,
+        }
+    }
+
+    /// `styled`, with its styling dropped under `--no-color`.
+    fn paint<D>(self, styled: console::StyledObject<D>) -> console::StyledObject<D> {
+        if self.no_color {
+            styled.force_styling(false)
+        } else {
+            styled
+        }
+    }
+}
+
 // ─── Stage result ─────────────────────────────────────────────────────────────
 
 /// The outcome of a single pipeline stage.
@@ -60,49 +100,138 @@ fn icon_done() -> console::StyledObject<&'static str> {
 pub struct StageOutcome {
     /// Human-readable stage label, e.g. `"Check"`.
     pub label: String,
+    /// Stable machine-readable stage ID from [`StageMeta::id`], e.g.
+    /// `"check"`; `None` for stages run by plain label.
+    pub stage_id: Option<&'static str>,
     /// Whether the stage completed without error.
     pub success: bool,
-    /// Everything the command wrote to stdout (empty on success unless
-    /// `--verbose` is added in the future).
+    /// Everything the command wrote to stdout.
     pub stdout: String,
     /// Everything the command wrote to stderr.
     pub stderr: String,
     /// The anyhow error message, if any.
     pub error: Option<String>,
+    /// Why running the stage's command failed, when it did: set by
+    /// [`run_stage`] and friends, `None` on success and for failures that
+    /// did not come from running a command.  Picks the hint printed under
+    /// the error.
+    pub error_kind: Option<ErrorKind>,
+}
+
+/// How running a stage's command went wrong.
+///
+/// Lets callers tell a command that could not be started (a `PATH` or
+/// permissions problem, worth retrying once fixed) from one that ran and
+/// reported failure (a real backup problem).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The command could not be spawned or waited on.
+    SpawnFailed,
+    /// The command ran and exited non-zero; `code` is `None` when it was
+    /// ended by a signal.
+    NonZeroExit { code: Option<i32> },
+    /// The command outlived its timeout and was killed.
+    Timeout,
+}
+
+impl ErrorKind {
+    /// What to try next, for the kinds where there is something beyond
+    /// reading the command's own output.
+    pub const fn hint(self) -> Option<&'static str> {
+        match self {
+            Self::SpawnFailed => Some("check that the program is installed and on PATH"),
+            Self::Timeout => {
+                Some("raise --timeout or stage_timeout_secs if the stage needs longer")
+            },
+            Self::NonZeroExit {
+                ..
+            } => None,
+        }
+    }
+}
+
+/// Machine-readable identity of a pipeline stage.
+///
+/// Labels are for people and may be reworded; `id` is what logs and scripts
+/// should match on, and never changes once released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageMeta {
+    /// Stable identifier, lowercase and hyphenated, e.g. `"cold-archive"`.
+    pub id: &'static str,
+    /// Label shown next to the spinner and in the summary.
+    pub description: &'static str,
+    /// Whether a flag or config setting can leave this stage out of a run.
+    pub skippable: bool,
 }
 
 impl StageOutcome {
+    /// Tag this outcome with `meta`'s stable ID.
+    #[must_use]
+    pub const fn with_meta(mut self, meta: &StageMeta) -> Self {
+        self.stage_id = Some(meta.id);
+        self
+    }
+
     /// Print the one-line summary (✓/✗ + label) to stdout.
     ///
     /// On failure, also prints the captured stdout/stderr and the error
     /// message so the operator has everything they need without re-running.
-    pub fn print(&self) {
-        if self.success {
-            println!("  {}  {}", icon_ok(), style(&self.label).bold());
-        } else {
-            println!("  {}  {}", icon_err(), style(&self.label).bold());
+    pub fn print(&self, ui: UiContext) {
+        print!("{}", self.render(ui));
+        eprint!("{}", self.render_details(ui));
+    }
 
-            // Print the error message first (most useful thing).
-            if let Some(ref msg) = self.error {
-                eprintln!();
-                eprintln!("  {} {}", style("Error:").red().bold(), msg);
-            }
+    /// The ✓/✗ + label line [`Self::print`] writes to stdout.
+    pub fn render(&self, ui: UiContext) -> String {
+        let icon = if self.success { icon_ok() } else { icon_err() };
+        format!(
+            "  {}  {}\n",
+            ui.paint(icon),
+            ui.paint(style(&self.label).bold())
+        )
+    }
 
-            // Replay captured output so the operator can see what rustic said.
-            if !self.stdout.is_empty() {
-                eprintln!();
-                eprintln!("  {} stdout:", style("►").dim());
-                for line in self.stdout.lines() {
-                    eprintln!("    {line}");
+    /// What [`Self::print`] writes to stderr: on failure the error and the
+    /// captured stdout/stderr, with `--verbose` also a successful stage's
+    /// stderr.  Empty otherwise.
+    pub fn render_details(&self, ui: UiContext) -> String {
+        fn replay(out: &mut String, ui: UiContext, name: &str, text: &str) {
+            if !text.is_empty() {
+                let _ = writeln!(out, "\n  {} {name}:", ui.paint(style("►").dim()));
+                for line in text.lines() {
+                    let _ = writeln!(out, "    {line}");
                 }
             }
-            if !self.stderr.is_empty() {
-                eprintln!();
-                eprintln!("  {} stderr:", style("►").dim());
-                for line in self.stderr.lines() {
-                    eprintln!("    {line}");
-                }
+        }
+
+        let mut out = String::new();
+        if self.success {
+            if ui.verbose {
+                replay(&mut out, ui, "stderr", &self.stderr);
             }
+            return out;
+        }
+
+        // The error message first (most useful thing), then the captured
+        // output so the operator can see what rustic said.
+        if let Some(ref msg) = self.error {
+            let _ = writeln!(out, "\n  {} {msg}", ui.paint(style("Error:").red().bold()));
+        }
+        if let Some(hint) = self.error_kind.and_then(ErrorKind::hint) {
+            let _ = writeln!(out, "  {}", ui.paint(style(format!("hint: {hint}")).dim()));
+        }
+        replay(&mut out, ui, "stdout", &self.stdout);
+        replay(&mut out, ui, "stderr", &self.stderr);
+        out
+    }
+
+    /// Print the captured stdout, indented under the summary line.
+    ///
+    /// Used for stages whose output is the point, such as `--dry-run`
+    /// previews.  Prints nothing when stdout is empty.
+    pub fn print_stdout(&self) {
+        for line in self.stdout.lines() {
+            println!("    {line}");
         }
     }
 
@@ -118,41 +247,278 @@ impl StageOutcome {
 ///
 /// The spinner ticks at ~80 ms and is automatically cleared when
 /// [`ProgressBar::finish_and_clear`] is called.
-fn make_spinner(label: &str) -> ProgressBar {
+///
+/// The spinner is hidden under `--quiet`, and when debug-level tracing is
+/// enabled so streamed log lines are not overdrawn by the animation.
+fn make_spinner(ui: UiContext, label: &str) -> ProgressBar {
+    if ui.quiet || tracing::enabled!(tracing::Level::DEBUG) {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new_spinner();
     pb.set_style(
         ProgressStyle::with_template("  {spinner:.cyan}  {msg}")
             .unwrap()
             .tick_chars(SPINNER_CHARS),
     );
-    pb.set_message(format!("{}", style(label).dim()));
+    pb.set_message(format!("{}", ui.paint(style(label).dim())));
     pb.enable_steady_tick(Duration::from_millis(80));
     pb
 }
 
+/// Create a spinner for `label` together with a closure that updates its
+/// detail.
+///
+/// Calling the closure with `msg` changes the spinner's message to
+/// `label: msg`, so a long stage can show progress — e.g. the latest rustic
+/// progress line once output is streamed — without losing its label.  The
+/// spinner is hidden under the same conditions as [`make_spinner`].
+#[allow(dead_code)]
+pub fn spinner_with_detail(ui: UiContext, label: &str) -> (ProgressBar, impl Fn(&str)) {
+    let pb = make_spinner(ui, label);
+    let handle = pb.clone();
+    let label = label.to_string();
+    let update_detail = move |msg: &str| {
+        handle.set_message(format!(
+            "{}",
+            ui.paint(style(format!("{label}: {msg}")).dim())
+        ));
+    };
+    (pb, update_detail)
+}
+
 // ─── Captured execution ───────────────────────────────────────────────────────
 
+/// Everything a finished command left behind: its exit status and output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedOutput {
+    /// Whether the command exited zero.
+    pub success: bool,
+    /// Everything the command wrote to stdout.
+    pub stdout: String,
+    /// Everything the command wrote to stderr.
+    pub stderr: String,
+    /// The process exit code, or `-1` when it was ended by a signal.
+    pub exit_code: i32,
+}
+
+impl CapturedOutput {
+    /// Turn this output into the [`StageOutcome`] for stage `label`.
+    ///
+    /// A non-zero exit keeps the captured output and records the command
+    /// line (`args`) and exit code in the error message.
+    pub fn into_stage_outcome(self, label: &str, args: &[String]) -> StageOutcome {
+        let error = (!self.success).then(|| {
+            format!(
+                "command exited non-zero ({}): {}",
+                self.exit_code,
+                args.join(" ")
+            )
+        });
+        let error_kind = (!self.success).then(|| ErrorKind::NonZeroExit {
+            code: (self.exit_code >= 0).then_some(self.exit_code),
+        });
+        StageOutcome {
+            label: label.to_string(),
+            stage_id: None,
+            success: self.success,
+            stdout: self.stdout,
+            stderr: self.stderr,
+            error,
+            error_kind,
+        }
+    }
+}
+
 /// Run a command, capturing both stdout and stderr.
 ///
 /// Unlike [`crate::runner::run`] this does **not** inherit the parent's
 /// stdout/stderr — all output is buffered so the spinner can own the terminal
 /// while the command runs.
 ///
-/// Returns `(success, stdout_text, stderr_text)`.
-pub fn run_captured(args: &[String]) -> Result<(bool, String, String)> {
+/// Returns the exit status and both streams as a [`CapturedOutput`].
+pub fn run_captured(args: &[String]) -> Result<CapturedOutput> {
+    run_captured_timeout(args, None)
+}
+
+/// Like [`run_captured`], but kills the command if it is still running after
+/// `timeout`.
+///
+/// stdout and stderr are drained on background threads while we wait, so a
+/// chatty command cannot deadlock on a full pipe.  A timed-out command yields
+/// an `Err` whose message reads `timed out after <duration>`.
+pub fn run_captured_timeout(args: &[String], timeout: Option<Duration>) -> Result<CapturedOutput> {
+    run_command_timeout(command_in(args, None)?, timeout)
+}
+
+/// Like [`run_captured`], but runs the command in `cwd`, so relative paths
+/// in `args` — e.g. `backup` sources — resolve against it rather than the
+/// caller's working directory.
+#[allow(dead_code)]
+pub fn run_captured_in(args: &[String], cwd: &Path) -> Result<CapturedOutput> {
+    run_command_timeout(command_in(args, Some(cwd))?, None)
+}
+
+/// The [`Command`] for `args`, with stdin closed and run in `cwd` when given.
+fn command_in(args: &[String], cwd: Option<&Path>) -> Result<Command> {
     let (prog, rest) = args.split_first().context("cannot run an empty command")?;
+    let mut cmd = Command::new(prog);
+    cmd.args(rest).stdin(Stdio::null());
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd);
+    }
+    Ok(cmd)
+}
 
-    let output: Output = Command::new(prog)
-        .args(rest)
+/// Like [`run_captured_timeout`], but for a caller-built [`Command`].
+///
+/// stdout and stderr are always replaced with pipes; everything else the
+/// caller configured — environment, working directory, stdin — is kept.
+///
+/// With a `timeout` the command runs in a process group of its own, and the
+/// whole group is killed when time runs out, so the `rustic` that a `doas`
+/// wrapper started goes down with it.  Being outside the terminal's
+/// foreground group, such a command cannot prompt on the terminal: `doas`
+/// needs `nopass` or `persist` for timed stages.
+pub fn run_command_timeout(mut cmd: Command, timeout: Option<Duration>) -> Result<CapturedOutput> {
+    if timeout.is_some() {
+        cmd.process_group(0);
+    }
+    let mut child = cmd
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .output()
-        .with_context(|| format!("failed to spawn: {}", args.join(" ")))?;
+        .spawn()
+        .with_context(|| format!("failed to spawn: {}", command_line(&cmd).join(" ")))?;
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = match timeout {
+        None => child.wait().context("failed to wait for child")?,
+        Some(limit) => {
+            let deadline = Instant::now() + limit;
+            loop {
+                if let Some(status) = child.try_wait().context("failed to poll child")? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    kill_group(&mut child);
+                    reap_within(&mut child, KILL_GRACE);
+                    return Err(TimedOut(limit).into());
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        },
+    };
+
+    let stdout = String::from_utf8_lossy(&stdout.join().unwrap_or_default()).into_owned();
+    let stderr = String::from_utf8_lossy(&stderr.join().unwrap_or_default()).into_owned();
+
+    Ok(CapturedOutput {
+        success: status.success(),
+        stdout,
+        stderr,
+        exit_code: status.code().unwrap_or(-1),
+    })
+}
+
+/// How long a killed command gets to exit before it is left behind.
+pub const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// `SIGKILL` the process group led by `child`, falling back to the child
+/// alone.  Failures are logged: the child may have exited since it was last
+/// polled, or a privileged process in the group may be beyond our reach.
+fn kill_group(child: &mut Child) {
+    let group = format!("-{}", child.id());
+    match Command::new("kill")
+        .args(["-s", "KILL", "--", &group])
+        .stderr(Stdio::null())
+        .status()
+    {
+        Ok(status) if status.success() => return,
+        Ok(status) => tracing::warn!(pid = child.id(), "kill -s KILL -- {group}: {status}"),
+        Err(e) => tracing::warn!(pid = child.id(), "could not run kill: {e}"),
+    }
+    if let Err(e) = child.kill() {
+        tracing::warn!(pid = child.id(), "could not kill command: {e}");
+    }
+}
+
+/// Poll `child` until it exits or `grace` runs out, rather than blocking on
+/// `wait`: a process stuck in uninterruptible sleep (say, on a dead NFS
+/// server) never exits.  A child still running afterwards is left behind,
+/// and a warning logged.
+pub fn reap_within(child: &mut Child, grace: Duration) {
+    let deadline = Instant::now() + grace;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            Ok(None) => {
+                tracing::warn!(
+                    pid = child.id(),
+                    "killed command has not exited; leaving it"
+                );
+                return;
+            },
+            Err(e) => {
+                tracing::warn!(pid = child.id(), "could not poll killed command: {e}");
+                return;
+            },
+        }
+    }
+}
+
+/// The error [`run_command_timeout`] returns for a command it killed.
+#[derive(Debug)]
+struct TimedOut(Duration);
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for TimedOut {}
 
-    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
-    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+/// The program and arguments of `cmd`, lossily converted to strings.
+fn command_line(cmd: &Command) -> Vec<String> {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|s| s.to_string_lossy().into_owned())
+        .collect()
+}
 
-    Ok((output.status.success(), stdout, stderr))
+/// Read `pipe` to the end on a background thread.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+// ─── Pass-through execution ───────────────────────────────────────────────────
+
+/// Run a command with stdin, stdout and stderr inherited from this process.
+///
+/// Used by subcommands whose output *is* the product (e.g. `backup dump`), so
+/// nothing is buffered and no spinner is drawn.  Returns an error if the
+/// command cannot be spawned or exits non-zero.
+pub fn run_interactive(args: &[String]) -> Result<()> {
+    let (prog, rest) = args.split_first().context("cannot run an empty command")?;
+    tracing::debug!(cmd = %redacted(args), "running interactive command");
+
+    let status = Command::new(prog)
+        .args(rest)
+        .status()
+        .with_context(|| format!("failed to spawn: {prog}"))?;
+
+    if !status.success() {
+        anyhow::bail!("{prog} exited with {status}");
+    }
+    Ok(())
 }
 
 // ─── High-level stage runner ──────────────────────────────────────────────────
@@ -165,50 +531,235 @@ pub fn run_captured(args: &[String]) -> Result<(bool, String, String)> {
 ///
 /// The spinner is cleared before the outcome line is printed, so the terminal
 /// always shows a clean, static summary when the stage finishes.
-pub fn run_stage(label: &str, args: &[String]) -> StageOutcome {
-    let spinner = make_spinner(label);
+///
+/// When `timeout` is set and the command outlives it, the command is killed
+/// and the outcome carries a `timed out after …` error.
+pub fn run_stage(
+    ui: UiContext,
+    label: &str,
+    args: &[String],
+    timeout: Option<Duration>,
+) -> StageOutcome {
+    run_stage_in(ui, label, args, None, timeout)
+}
+
+/// Like [`run_stage`], but runs the command in `cwd` when given instead of
+/// the current working directory.  A `cwd` that does not exist fails the
+/// stage as a spawn error.
+pub fn run_stage_in(
+    ui: UiContext,
+    label: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    timeout: Option<Duration>,
+) -> StageOutcome {
+    match command_in(args, cwd) {
+        Ok(cmd) => run_stage_cmd(ui, label, cmd, timeout),
+        Err(e) => stage_outcome(label, args, Err(e)),
+    }
+}
+
+/// Like [`run_stage`], labelled with `meta.description` and recording
+/// `meta.id` in [`StageOutcome::stage_id`].
+pub fn run_stage_meta(
+    ui: UiContext,
+    meta: &StageMeta,
+    args: &[String],
+    timeout: Option<Duration>,
+) -> StageOutcome {
+    run_stage(ui, meta.description, args, timeout).with_meta(meta)
+}
 
-    let result = run_captured(args);
+/// Like [`run_stage`], but runs a caller-built [`Command`].
+///
+/// For stages a flat argument list cannot express — a custom environment,
+/// working directory, or stdin.  stdout and stderr are captured exactly as in
+/// [`run_stage`]; the rest of `cmd`'s configuration is left untouched.
+pub fn run_stage_cmd(
+    ui: UiContext,
+    label: &str,
+    cmd: Command,
+    timeout: Option<Duration>,
+) -> StageOutcome {
+    let args = command_line(&cmd);
+    let _span = tracing::info_span!("stage", label).entered();
+    tracing::debug!(cmd = %redacted(&args), ?timeout, "stage started");
+
+    let spinner = make_spinner(ui, label);
+
+    let result = run_command_timeout(cmd, timeout);
     spinner.finish_and_clear();
 
+    match &result {
+        Ok(out) => tracing::debug!(out.success, out.exit_code, "stage finished"),
+        Err(e) => tracing::warn!("stage did not complete: {e:#}"),
+    }
+
+    stage_outcome(label, &args, result)
+}
+
+/// Turn the result of a captured command into a [`StageOutcome`].
+fn stage_outcome(label: &str, args: &[String], result: Result<CapturedOutput>) -> StageOutcome {
     match result {
-        Ok((true, stdout, stderr)) => StageOutcome {
-            label: label.to_string(),
-            success: true,
-            stdout,
-            stderr,
-            error: None,
-        },
-        Ok((false, stdout, stderr)) => StageOutcome {
-            label: label.to_string(),
-            success: false,
-            stdout,
-            stderr,
-            error: Some(format!("command exited non-zero: {}", args.join(" "))),
-        },
+        Ok(out) => out.into_stage_outcome(label, args),
         Err(e) => StageOutcome {
             label: label.to_string(),
+            stage_id: None,
             success: false,
             stdout: String::new(),
             stderr: String::new(),
             error: Some(e.to_string()),
+            error_kind: Some(if e.is::<TimedOut>() {
+                ErrorKind::Timeout
+            } else {
+                ErrorKind::SpawnFailed
+            }),
         },
     }
 }
 
+/// Run several independent stages at once, one thread each.
+///
+/// Outcomes are returned in submission order, regardless of which command
+/// finishes first.  A single spinner shows progress as `[done/total]
+/// running…`; print the outcomes afterwards.
+///
+/// **Only for commands that do not share a repository.**  rustic takes an
+/// exclusive lock for `backup`, `forget` and `prune`, so running two of those
+/// against the same repo in parallel makes all but one fail.  Backing up to
+/// several *different* repositories is the intended use.
+#[allow(dead_code)]
+pub fn run_parallel_stages(ui: UiContext, stages: Vec<(String, Vec<String>)>) -> Vec<StageOutcome> {
+    let total = stages.len();
+    let done = AtomicUsize::new(0);
+    let spinner = make_spinner(ui, &format!("[0/{total}] running…"));
+
+    let outcomes = std::thread::scope(|scope| {
+        let handles: Vec<_> = stages
+            .into_iter()
+            .map(|(label, args)| {
+                let (done, spinner) = (&done, &spinner);
+                let thread_label = label.clone();
+                let handle = scope.spawn(move || {
+                    let label = thread_label;
+                    tracing::debug!(label, cmd = %redacted(&args), "parallel stage started");
+                    let result = run_captured_timeout(&args, None);
+                    let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    spinner.set_message(format!(
+                        "{}",
+                        ui.paint(style(format!("[{finished}/{total}] running…")).dim())
+                    ));
+                    stage_outcome(&label, &args, result)
+                });
+                (label, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(label, handle)| {
+                handle.join().unwrap_or_else(|_| {
+                    stage_outcome(&label, &[], Err(anyhow::anyhow!("stage thread panicked")))
+                })
+            })
+            .collect()
+    });
+
+    spinner.finish_and_clear();
+    outcomes
+}
+
 /// Like [`run_stage`] but for stages that are logically skipped (e.g. because
 /// `--no-mount` was passed and there is no mount configured).
 ///
 /// Returns a synthetic success outcome so the pipeline does not need special-
 /// case logic for optional stages.
 pub fn skipped_stage(label: &str) -> StageOutcome {
+    tracing::debug!(label, "stage skipped");
     StageOutcome {
         label: label.to_string(),
+        stage_id: None,
         success: true,
         stdout: String::new(),
         stderr: String::new(),
         error: None,
+        error_kind: None,
+    }
+}
+
+// ─── Banner ───────────────────────────────────────────────────────────────────
+
+/// Render the startup header: binary name, version, and config path.
+///
+/// `config_path` is made absolute (without requiring it to exist) so the user
+/// sees exactly which file was read, even when `--config` was relative.
+pub fn render_banner(version: &str, config_path: &Path) -> String {
+    let resolved = std::path::absolute(config_path).unwrap_or_else(|_| config_path.to_path_buf());
+    format!(
+        "{} {} {} config: {}",
+        style("backup.rs").bold(),
+        style(format!("v{version}")).cyan(),
+        style("·").dim(),
+        resolved.display()
+    )
+}
+
+/// Print the startup header produced by [`render_banner`].
+///
+/// Shown before the pipeline runs; suppressed by `--quiet`.
+pub fn print_banner(version: &str, config_path: &Path) {
+    println!("  {}", render_banner(version, config_path));
+}
+
+// ─── Stage tree ───────────────────────────────────────────────────────────────
+
+/// Render planned stages as a tree, one branch per `(label, skipped)` pair:
+///
+/// ```text
+///   Pipeline
+///   ├─ Mount
+///   ├─ Check  (skipped)
+///   └─ Backup
+/// ```
+pub fn render_stage_tree(stages: &[(&str, bool)]) -> String {
+    let width = stages
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("  {}\n", style("Pipeline").bold());
+    for (i, (label, skipped)) in stages.iter().enumerate() {
+        let branch = if i + 1 == stages.len() {
+            "└─"
+        } else {
+            "├─"
+        };
+        let line = if *skipped {
+            format!(
+                "{}  {}",
+                style(format!("{label:<width$}")).dim(),
+                style("(skipped)").dim()
+            )
+        } else {
+            (*label).to_string()
+        };
+        let _ = writeln!(out, "  {} {}", style(branch).dim(), line.trim_end());
     }
+    out
+}
+
+/// Print the tree produced by [`render_stage_tree`], followed by a blank line.
+pub fn print_stage_tree(stages: &[(&str, bool)]) {
+    println!("{}", render_stage_tree(stages));
+}
+
+// ─── Warnings ─────────────────────────────────────────────────────────────────
+
+/// Print a yellow `Warning:` line to stderr.
+///
+/// For conditions that change what the pipeline does without failing it,
+/// e.g. a stage being skipped because of the config.
+pub fn print_warning(msg: &str) {
+    eprintln!("  {} {}", style("Warning:").yellow().bold(), msg);
 }
 
 // ─── Summary banner ───────────────────────────────────────────────────────────
@@ -217,47 +768,75 @@ pub fn skipped_stage(label: &str) -> StageOutcome {
 ///
 /// Shows a success banner when all stages passed, or a failure banner listing
 /// the stages that failed.
-pub fn print_summary(outcomes: &[StageOutcome]) {
-    let failed: Vec<&StageOutcome> = outcomes.iter().filter(|o| o.failed()).collect();
+pub fn print_summary(ui: UiContext, outcomes: &[StageOutcome]) {
     println!();
-    if failed.is_empty() {
-        println!(
-            "  {} {}",
-            icon_done(),
-            style("All stages completed successfully.").cyan().bold()
-        );
+    if outcomes.iter().any(StageOutcome::failed) {
+        eprint!("{}", render_summary(ui, outcomes));
     } else {
-        eprintln!("  {}  {}", icon_err(), style("Backup failed.").red().bold());
-        for o in &failed {
-            eprintln!("    {} {}", icon_err(), style(&o.label).red());
-        }
+        print!("{}", render_summary(ui, outcomes));
     }
     println!();
 }
 
+/// The banner [`print_summary`] prints between its blank lines.
+pub fn render_summary(ui: UiContext, outcomes: &[StageOutcome]) -> String {
+    let failed: Vec<&StageOutcome> = outcomes.iter().filter(|o| o.failed()).collect();
+    if failed.is_empty() {
+        return format!(
+            "  {} {}\n",
+            ui.paint(icon_done()),
+            ui.paint(style("All stages completed successfully.").cyan().bold())
+        );
+    }
+    let mut out = format!(
+        "  {}  {}\n",
+        ui.paint(icon_err()),
+        ui.paint(style("Backup failed.").red().bold())
+    );
+    for o in &failed {
+        let _ = writeln!(
+            out,
+            "    {} {}",
+            ui.paint(icon_err()),
+            ui.paint(style(&o.label).red())
+        );
+    }
+    out
+}
+
 // ─── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const UI: UiContext = UiContext {
+        quiet: false,
+        no_color: false,
+        verbose: false,
+    };
+
     fn success(label: &str) -> StageOutcome {
         StageOutcome {
             label: label.into(),
+            stage_id: None,
             success: true,
             stdout: String::new(),
             stderr: String::new(),
             error: None,
+            error_kind: None,
         }
     }
 
     fn failure(label: &str, err: &str, stdout: &str, stderr: &str) -> StageOutcome {
         StageOutcome {
             label: label.into(),
+            stage_id: None,
             success: false,
             stdout: stdout.into(),
             stderr: stderr.into(),
             error: Some(err.into()),
+            error_kind: None,
         }
     }
 
@@ -273,42 +852,83 @@ mod tests {
         assert!(failure("Check", "oh no", "", "").failed());
     }
 
+    // ── CapturedOutput ────────────────────────────────────────────────────────
+
+    fn captured(success: bool, exit_code: i32) -> CapturedOutput {
+        CapturedOutput {
+            success,
+            stdout: "out".into(),
+            stderr: "err".into(),
+            exit_code,
+        }
+    }
+
+    #[test]
+    fn successful_output_becomes_successful_outcome() {
+        let outcome = captured(true, 0).into_stage_outcome("Check", &["rustic".into()]);
+        assert!(!outcome.failed());
+        assert_eq!(outcome.label, "Check");
+        assert_eq!(
+            (outcome.stdout.as_str(), outcome.stderr.as_str()),
+            ("out", "err")
+        );
+        assert!(outcome.error.is_none());
+    }
+
+    #[test]
+    fn failed_output_names_command_and_exit_code() {
+        let args = vec!["rustic".to_string(), "check".to_string()];
+        let outcome = captured(false, 3).into_stage_outcome("Check", &args);
+        assert!(outcome.failed());
+        assert_eq!(outcome.stdout, "out");
+        assert_eq!(
+            outcome.error.as_deref(),
+            Some("command exited non-zero (3): rustic check")
+        );
+    }
+
     // ── run_captured ─────────────────────────────────────────────────────────
 
     #[test]
     fn run_captured_true_succeeds() {
-        let (ok, _out, _err) = run_captured(&["true".into()]).unwrap();
-        assert!(ok);
+        let out = run_captured(&["true".into()]).unwrap();
+        assert!(out.success);
+        assert_eq!(out.exit_code, 0);
     }
 
     #[test]
     fn run_captured_false_fails() {
-        let (ok, _out, _err) = run_captured(&["false".into()]).unwrap();
-        assert!(!ok);
+        let out = run_captured(&["false".into()]).unwrap();
+        assert!(!out.success);
+        assert_eq!(out.exit_code, 1);
+    }
+
+    #[test]
+    fn run_captured_reports_exit_code() {
+        let out = run_captured(&["sh".into(), "-c".into(), "exit 3".into()]).unwrap();
+        assert!(!out.success);
+        assert_eq!(out.exit_code, 3);
     }
 
     #[test]
     fn run_captured_captures_stdout() {
-        let (ok, out, _err) =
-            run_captured(&["sh".into(), "-c".into(), "echo hello".into()]).unwrap();
-        assert!(ok);
-        assert!(out.contains("hello"));
+        let out = run_captured(&["sh".into(), "-c".into(), "echo hello".into()]).unwrap();
+        assert!(out.success);
+        assert!(out.stdout.contains("hello"));
     }
 
     #[test]
     fn run_captured_captures_stderr() {
-        let (ok, _out, err) =
-            run_captured(&["sh".into(), "-c".into(), "echo oops >&2".into()]).unwrap();
-        assert!(ok);
-        assert!(err.contains("oops"));
+        let out = run_captured(&["sh".into(), "-c".into(), "echo oops >&2".into()]).unwrap();
+        assert!(out.success);
+        assert!(out.stderr.contains("oops"));
     }
 
     #[test]
     fn run_captured_captures_non_zero_output() {
-        let (ok, out, _err) =
-            run_captured(&["sh".into(), "-c".into(), "echo failing; exit 1".into()]).unwrap();
-        assert!(!ok);
-        assert!(out.contains("failing"));
+        let out = run_captured(&["sh".into(), "-c".into(), "echo failing; exit 1".into()]).unwrap();
+        assert!(!out.success);
+        assert!(out.stdout.contains("failing"));
     }
 
     #[test]
@@ -317,11 +937,118 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // ── run_captured_timeout ──────────────────────────────────────────────────
+
+    #[test]
+    fn run_captured_timeout_kills_slow_command() {
+        let start = Instant::now();
+        let err = run_captured_timeout(
+            &["sleep".into(), "5".into()],
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("timed out after 200ms"),
+            "got: {err}"
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(4),
+            "child should have been killed, not waited for"
+        );
+    }
+
+    #[test]
+    fn run_captured_timeout_kills_the_whole_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let pidfile = dir.path().join("grandchild.pid");
+        // `sh` stands in for `doas`: the long-running grandchild must die too.
+        let script = format!("sleep 30 & echo $! > {}; wait", pidfile.display());
+        run_captured_timeout(
+            &["sh".into(), "-c".into(), script],
+            Some(Duration::from_millis(300)),
+        )
+        .unwrap_err();
+
+        let pid = std::fs::read_to_string(&pidfile).unwrap();
+        let alive = || {
+            Command::new("kill")
+                .args(["-0", pid.trim()])
+                .stderr(Stdio::null())
+                .status()
+                .unwrap()
+                .success()
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!alive(), "grandchild {} survived the timeout", pid.trim());
+    }
+
+    #[test]
+    fn reap_within_gives_up_on_a_child_that_will_not_exit() {
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let start = Instant::now();
+        reap_within(&mut child, Duration::from_millis(100));
+        assert!(
+            start.elapsed() < Duration::from_secs(4),
+            "reaping should be bounded by the grace period"
+        );
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn run_captured_timeout_lets_fast_command_finish() {
+        let out = run_captured_timeout(
+            &["sh".into(), "-c".into(), "echo quick".into()],
+            Some(Duration::from_secs(5)),
+        )
+        .unwrap();
+        assert!(out.success);
+        assert!(out.stdout.contains("quick"));
+    }
+
+    #[test]
+    fn run_captured_drains_large_output_without_deadlock() {
+        // Far more than a pipe buffer (64 KiB on Linux) on both streams.
+        let out = run_captured_timeout(
+            &[
+                "sh".into(),
+                "-c".into(),
+                "head -c 300000 /dev/zero | tr '\\0' a; head -c 300000 /dev/zero | tr '\\0' b >&2"
+                    .into(),
+            ],
+            Some(Duration::from_secs(10)),
+        )
+        .unwrap();
+        assert!(out.success);
+        assert_eq!(out.stdout.len(), 300_000);
+        assert_eq!(out.stderr.len(), 300_000);
+    }
+
+    // ── run_interactive ───────────────────────────────────────────────────────
+
+    #[test]
+    fn run_interactive_true_succeeds() {
+        assert!(run_interactive(&["true".into()]).is_ok());
+    }
+
+    #[test]
+    fn run_interactive_false_errors() {
+        assert!(run_interactive(&["false".into()]).is_err());
+    }
+
+    #[test]
+    fn run_interactive_empty_args_errors() {
+        assert!(run_interactive(&[]).is_err());
+    }
+
     // ── run_stage ─────────────────────────────────────────────────────────────
 
     #[test]
     fn run_stage_success_sets_success_true() {
-        let o = run_stage("Test", &["true".into()]);
+        let o = run_stage(UI, "Test", &["true".into()], None);
         assert!(o.success);
         assert_eq!(o.label, "Test");
         assert!(o.error.is_none());
@@ -329,22 +1056,361 @@ mod tests {
 
     #[test]
     fn run_stage_failure_sets_success_false() {
-        let o = run_stage("Test", &["false".into()]);
+        let o = run_stage(UI, "Test", &["false".into()], None);
         assert!(!o.success);
         assert!(o.error.is_some());
     }
 
     #[test]
     fn run_stage_captures_stdout_on_failure() {
-        let o = run_stage("Test", &[
-            "sh".into(),
-            "-c".into(),
-            "echo bad output; exit 1".into(),
-        ]);
+        let o = run_stage(
+            UI,
+            "Test",
+            &["sh".into(), "-c".into(), "echo bad output; exit 1".into()],
+            None,
+        );
         assert!(!o.success);
         assert!(o.stdout.contains("bad output"));
     }
 
+    #[test]
+    fn run_stage_emits_start_and_finish_events() {
+        let logs = crate::logging::capture(|| {
+            run_stage(UI, "Traced", &["true".into()], None);
+        });
+        assert!(logs.contains("stage started"));
+        assert!(logs.contains("stage finished"));
+        assert!(logs.contains("Traced"));
+    }
+
+    #[test]
+    fn run_stage_logs_redact_password() {
+        let logs = crate::logging::capture(|| {
+            run_stage(
+                UI,
+                "Traced",
+                &["true".into(), "--password".into(), "hunter2".into()],
+                None,
+            );
+        });
+        assert!(!logs.contains("hunter2"));
+    }
+
+    #[test]
+    fn run_stage_timeout_produces_clear_error() {
+        let o = run_stage(
+            UI,
+            "Slow",
+            &["sleep".into(), "5".into()],
+            Some(Duration::from_millis(100)),
+        );
+        assert!(o.failed());
+        assert!(
+            o.error
+                .as_deref()
+                .unwrap()
+                .contains("timed out after 100ms")
+        );
+    }
+
+    #[test]
+    fn run_stage_meta_records_stage_id() {
+        let meta = StageMeta {
+            id: "probe",
+            description: "Probe",
+            skippable: false,
+        };
+        let o = run_stage_meta(UI, &meta, &["true".into()], None);
+        assert!(o.success);
+        assert_eq!(o.label, "Probe");
+        assert_eq!(o.stage_id, Some("probe"));
+        assert_eq!(
+            run_stage(UI, "Probe", &["true".into()], None).stage_id,
+            None
+        );
+    }
+
+    #[test]
+    fn run_stage_meta_keeps_id_on_failure() {
+        let meta = StageMeta {
+            id: "probe",
+            description: "Probe",
+            skippable: true,
+        };
+        let o = run_stage_meta(UI, &meta, &[], None);
+        assert!(o.failed());
+        assert_eq!(o.stage_id, Some("probe"));
+    }
+
+    // ── ErrorKind ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn error_kind_for_missing_program_is_spawn_failed() {
+        let o = run_stage(UI, "Missing", &["backup-rs-no-such-program".into()], None);
+        assert_eq!(o.error_kind, Some(ErrorKind::SpawnFailed));
+        let o = run_stage(UI, "Empty", &[], None);
+        assert_eq!(o.error_kind, Some(ErrorKind::SpawnFailed));
+    }
+
+    #[test]
+    fn error_kind_for_failing_command_carries_exit_code() {
+        let o = run_stage(
+            UI,
+            "Fail",
+            &["sh".into(), "-c".into(), "exit 3".into()],
+            None,
+        );
+        assert_eq!(
+            o.error_kind,
+            Some(ErrorKind::NonZeroExit {
+                code: Some(3)
+            })
+        );
+    }
+
+    #[test]
+    fn error_kind_for_killed_command_has_no_code() {
+        let outcome = captured(false, -1).into_stage_outcome("Check", &["rustic".into()]);
+        assert_eq!(
+            outcome.error_kind,
+            Some(ErrorKind::NonZeroExit {
+                code: None
+            })
+        );
+    }
+
+    #[test]
+    fn error_kind_for_slow_command_is_timeout() {
+        let o = run_stage(
+            UI,
+            "Slow",
+            &["sleep".into(), "5".into()],
+            Some(Duration::from_millis(100)),
+        );
+        assert_eq!(o.error_kind, Some(ErrorKind::Timeout));
+    }
+
+    #[test]
+    fn error_kind_is_unset_on_success() {
+        let o = run_stage(UI, "Ok", &["true".into()], None);
+        assert_eq!(o.error_kind, None);
+    }
+
+    #[test]
+    fn run_stage_empty_args_fails_cleanly() {
+        let o = run_stage(UI, "Empty", &[], None);
+        assert!(o.failed());
+        assert_eq!(o.error.as_deref(), Some("cannot run an empty command"));
+    }
+
+    // ── working directory ─────────────────────────────────────────────────────
+
+    /// A fresh temp dir and its canonical path, which is what `pwd -P`
+    /// prints even when the temp root is a symlink (macOS `/var`).
+    fn temp_cwd() -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().canonicalize().unwrap();
+        (dir, real.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn run_captured_in_runs_in_the_given_directory() {
+        let (dir, real) = temp_cwd();
+        let out = run_captured_in(&["pwd".into(), "-P".into()], dir.path()).unwrap();
+        assert!(out.success);
+        assert_eq!(out.stdout.trim(), real);
+    }
+
+    #[test]
+    fn run_captured_in_resolves_relative_paths_against_cwd() {
+        let (dir, _) = temp_cwd();
+        std::fs::write(dir.path().join("marker.txt"), "found").unwrap();
+        let out = run_captured_in(&["cat".into(), "marker.txt".into()], dir.path()).unwrap();
+        assert_eq!(out.stdout, "found");
+    }
+
+    #[test]
+    fn run_stage_in_runs_in_the_given_directory() {
+        let (dir, real) = temp_cwd();
+        let o = run_stage_in(
+            UI,
+            "Pwd",
+            &["pwd".into(), "-P".into()],
+            Some(dir.path()),
+            None,
+        );
+        assert!(o.success);
+        assert_eq!(o.stdout.trim(), real);
+    }
+
+    #[test]
+    fn run_stage_in_resolves_relative_paths_against_cwd() {
+        let (dir, _) = temp_cwd();
+        std::fs::write(dir.path().join("marker.txt"), "found").unwrap();
+        let o = run_stage_in(
+            UI,
+            "Cat",
+            &["cat".into(), "marker.txt".into()],
+            Some(dir.path()),
+            None,
+        );
+        assert_eq!(o.stdout, "found");
+    }
+
+    #[test]
+    fn run_stage_in_without_cwd_inherits_ours() {
+        let o = run_stage_in(UI, "Pwd", &["pwd".into(), "-P".into()], None, None);
+        let ours = std::env::current_dir().unwrap().canonicalize().unwrap();
+        assert_eq!(o.stdout.trim(), ours.to_string_lossy());
+    }
+
+    #[test]
+    fn run_stage_in_missing_cwd_is_a_spawn_failure() {
+        let (dir, _) = temp_cwd();
+        let gone = dir.path().join("gone");
+        let o = run_stage_in(UI, "Pwd", &["pwd".into()], Some(&gone), None);
+        assert!(o.failed());
+        assert_eq!(o.error_kind, Some(ErrorKind::SpawnFailed));
+    }
+
+    // ── run_stage_cmd ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn run_stage_cmd_keeps_custom_environment() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo \"value=$BACKUP_RS_STAGE_TEST\""])
+            .env("BACKUP_RS_STAGE_TEST", "from-env");
+        let o = run_stage_cmd(UI, "Env", cmd, None);
+        assert!(o.success);
+        assert_eq!(o.stdout.trim(), "value=from-env");
+    }
+
+    #[test]
+    fn run_stage_cmd_keeps_cleared_environment() {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", "test -z \"$HOME\" && echo cleared"])
+            .env_clear();
+        let o = run_stage_cmd(UI, "Env", cmd, None);
+        assert!(o.success);
+        assert_eq!(o.stdout.trim(), "cleared");
+    }
+
+    #[test]
+    fn run_stage_cmd_failure_names_the_command() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "exit 3"]);
+        let o = run_stage_cmd(UI, "Fail", cmd, None);
+        assert!(o.failed());
+        assert!(o.error.as_deref().unwrap().contains("sh -c exit 3"));
+    }
+
+    // ── run_parallel_stages ───────────────────────────────────────────────────
+
+    fn sh(label: &str, script: &str) -> (String, Vec<String>) {
+        (label.into(), vec!["sh".into(), "-c".into(), script.into()])
+    }
+
+    #[test]
+    fn parallel_stages_keep_submission_order() {
+        // Each stage sleeps less than the one before, so they finish in
+        // reverse; the outcomes must still come back as submitted.
+        let outcomes = run_parallel_stages(UI, vec![
+            sh("slow", "sleep 0.3; echo slow"),
+            sh("medium", "sleep 0.1; echo medium"),
+            sh("fast", "echo fast"),
+        ]);
+        let labels: Vec<_> = outcomes.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, ["slow", "medium", "fast"]);
+        for o in &outcomes {
+            assert!(o.success);
+            assert_eq!(o.stdout.trim(), o.label);
+        }
+    }
+
+    #[test]
+    fn parallel_stages_run_concurrently() {
+        // The first stage waits for a file only the second creates, so it can
+        // only succeed if both are running at the same time.
+        let dir = tempfile::tempdir().unwrap();
+        let flag = dir.path().join("flag");
+        let outcomes = run_parallel_stages(UI, vec![
+            sh(
+                "waiter",
+                &format!(
+                    "for _ in $(seq 100); do [ -e '{}' ] && exit 0; sleep 0.1; done; exit 1",
+                    flag.display()
+                ),
+            ),
+            sh("signaller", &format!("touch '{}'", flag.display())),
+        ]);
+        assert!(outcomes.iter().all(|o| o.success), "{outcomes:?}");
+    }
+
+    #[test]
+    fn parallel_stage_failure_is_isolated() {
+        let outcomes = run_parallel_stages(UI, vec![
+            sh("ok", "true"),
+            sh("bad", "exit 1"),
+            ("empty".into(), vec![]),
+        ]);
+        assert!(outcomes[0].success);
+        assert!(outcomes[1].failed());
+        assert!(outcomes[2].failed());
+    }
+
+    #[test]
+    fn parallel_stages_empty_input() {
+        assert!(run_parallel_stages(UI, vec![]).is_empty());
+    }
+
+    // ── render_stage_tree ─────────────────────────────────────────────────────
+
+    fn plain_tree(stages: &[(&str, bool)]) -> String {
+        console::strip_ansi_codes(&render_stage_tree(stages)).into_owned()
+    }
+
+    #[test]
+    fn stage_tree_uses_box_drawing_branches() {
+        assert_eq!(
+            plain_tree(&[("Mount", false), ("Check", true), ("Backup", false)]),
+            "  Pipeline\n  ├─ Mount\n  ├─ Check   (skipped)\n  └─ Backup\n"
+        );
+    }
+
+    #[test]
+    fn stage_tree_last_branch_closes_the_tree() {
+        let tree = plain_tree(&[("Backup", false), ("Forget", true)]);
+        let last = tree.lines().last().unwrap();
+        assert!(last.contains('└'), "got: {last}");
+        assert!(last.contains("Forget") && last.contains("(skipped)"));
+        assert_eq!(tree.matches('├').count(), 1);
+    }
+
+    #[test]
+    fn stage_tree_without_stages_is_just_the_root() {
+        assert_eq!(plain_tree(&[]), "  Pipeline\n");
+    }
+
+    // ── render_banner ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn banner_contains_name_version_and_path() {
+        let out = render_banner("1.2.3", Path::new("/etc/backup.toml"));
+        assert!(out.contains("backup.rs"));
+        assert!(out.contains("v1.2.3"));
+        assert!(out.contains("config: /etc/backup.toml"));
+    }
+
+    #[test]
+    fn banner_resolves_relative_config_path() {
+        let out = render_banner("1.2.3", Path::new("backup.toml"));
+        let expected = std::env::current_dir().unwrap().join("backup.toml");
+        assert!(
+            out.contains(&expected.display().to_string()),
+            "banner should show the absolute path; got: {out}"
+        );
+    }
+
     // ── skipped_stage ─────────────────────────────────────────────────────────
 
     #[test]
@@ -354,23 +1420,117 @@ mod tests {
         assert_eq!(o.label, "Mount");
     }
 
-    // ── print_summary ─────────────────────────────────────────────────────────
+    // ── render_summary ────────────────────────────────────────────────────────
 
     #[test]
     fn summary_with_all_successes_does_not_list_failures() {
-        // Smoke test: just ensure it doesn't panic with all-success inputs.
         let outcomes = vec![success("Mount"), success("Check"), success("Backup")];
-        print_summary(&outcomes); // would panic if something is broken
+        let plain = console::strip_ansi_codes(&render_summary(UI, &outcomes)).into_owned();
+        assert_eq!(plain, "  ✓ All stages completed successfully.\n");
     }
 
     #[test]
     fn summary_with_failure_includes_failed_stages() {
-        // Similarly smoke-tested; insta snapshot tests give us richer coverage.
         let outcomes = vec![
             success("Mount"),
             failure("Check", "repo corrupt", "", "error detail"),
             success("Backup"),
         ];
-        print_summary(&outcomes);
+        let plain = console::strip_ansi_codes(&render_summary(UI, &outcomes)).into_owned();
+        assert_eq!(plain, "  ✗  Backup failed.\n    ✗ Check\n");
+    }
+
+    // ── UiContext ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn ui_context_follows_cli_flags() {
+        use clap::Parser as _;
+        let cli = Cli::parse_from(["backup", "--quiet", "--no-color", "--verbose"]);
+        assert_eq!(UiContext::from_cli(&cli), UiContext {
+            quiet: true,
+            no_color: true,
+            verbose: true,
+        });
+        let cli = Cli::parse_from(["backup"]);
+        assert_eq!(UiContext::from_cli(&cli), UiContext::default());
+    }
+
+    #[test]
+    fn no_color_drops_styling() {
+        let red = || style("x").red().force_styling(true);
+        assert_ne!(UI.paint(red()).to_string(), "x");
+        let ui = UiContext {
+            no_color: true,
+            ..UI
+        };
+        assert_eq!(ui.paint(red()).to_string(), "x");
+    }
+
+    #[test]
+    fn quiet_hides_the_spinner() {
+        let ui = UiContext {
+            quiet: true,
+            ..UI
+        };
+        assert!(make_spinner(ui, "Backup").is_hidden());
+    }
+
+    #[test]
+    fn update_detail_changes_the_spinner_message() {
+        let ui = UiContext {
+            quiet: true,
+            no_color: true,
+            ..UI
+        };
+        let (pb, update_detail) = spinner_with_detail(ui, "Backup");
+        update_detail("1.2 GiB scanned");
+        assert_eq!(pb.message(), "Backup: 1.2 GiB scanned");
+        update_detail("3.4 GiB scanned");
+        assert_eq!(pb.message(), "Backup: 3.4 GiB scanned");
+    }
+
+    #[test]
+    fn verbose_replays_stderr_of_successful_stage() {
+        let mut o = success("Backup");
+        o.stderr = "using 4 threads\n".into();
+        assert_eq!(o.render_details(UI), "");
+        let ui = UiContext {
+            verbose: true,
+            ..UI
+        };
+        let details = console::strip_ansi_codes(&o.render_details(ui)).into_owned();
+        assert_eq!(details, "\n  ► stderr:\n    using 4 threads\n");
+    }
+
+    #[test]
+    fn failure_details_hint_at_spawn_failures_and_timeouts() {
+        let mut o = failure("Check", "failed to spawn: rustic check", "", "");
+        o.error_kind = Some(ErrorKind::SpawnFailed);
+        let details = console::strip_ansi_codes(&o.render_details(UI)).into_owned();
+        assert_eq!(
+            details,
+            "\n  Error: failed to spawn: rustic check\n  hint: check that the program is installed \
+             and on PATH\n"
+        );
+
+        o.error_kind = Some(ErrorKind::Timeout);
+        let details = console::strip_ansi_codes(&o.render_details(UI)).into_owned();
+        assert!(details.contains("hint: raise --timeout"), "{details}");
+
+        o.error_kind = Some(ErrorKind::NonZeroExit {
+            code: Some(1),
+        });
+        let details = console::strip_ansi_codes(&o.render_details(UI)).into_owned();
+        assert!(!details.contains("hint:"), "{details}");
+    }
+
+    #[test]
+    fn failure_details_lead_with_the_error() {
+        let o = failure("Check", "repo corrupt", "", "error detail");
+        let details = console::strip_ansi_codes(&o.render_details(UI)).into_owned();
+        assert_eq!(
+            details,
+            "\n  Error: repo corrupt\n\n  ► stderr:\n    error detail\n"
+        );
     }
 }
@@ -18,10 +18,21 @@
 //!
 //! - `backup-rs` with a real config initialises a rustic repo, creates a snapshot, and exits zero.
 //! - Running a second time (repo already exists) also exits zero.
+//! - A missing repo is only created when `--init-if-missing` is passed.
 //! - A deliberately broken config (bad repo path) exits non-zero.
 //! - `--no-prune` skips the forget/compact stages and retains all snapshots.
+//! - A later run that prunes trims those snapshots to `[retention].keep_last`.
 //! - `--no-check` skips the integrity check stage.
 //! - Snapshots are actually created and their contents are verifiable.
+//! - Encrypted repositories (non-empty password) work through the whole pipeline.
+//! - `--source` overrides the configured sources for a single run.
+//! - `backup ls --source-index` lists one configured source's subtree.
+//! - `backup forget` removes several snapshots by ID in one invocation.
+//! - `backup cat-tree` prints a snapshot's root tree as valid JSON.
+//! - A deliberately corrupted pack file is detected by `rustic check`.
+//! - `backup check index` and `backup check snapshots` pass on a fresh repository.
+//! - `backup mount <snapshot> <dir>` serves the snapshot over FUSE (skipped at runtime on hosts
+//!   without FUSE).
 
 use std::{fs, path::PathBuf, process::Command};
 const BIN: &str = env!("CARGO_BIN_EXE_backup-rs");
@@ -49,6 +60,8 @@ struct Fixture {
     pub repo_dir: PathBuf,
     /// Working directory used when invoking `backup-rs`.
     pub work_dir: PathBuf,
+    /// Repository password written to `backup.toml`; empty for most tests.
+    pub password: String,
     /// Counter used by `write_unique` to ensure distinct content each call.
     counter: std::sync::atomic::AtomicU32,
 }
@@ -56,6 +69,11 @@ struct Fixture {
 impl Fixture {
     /// Create a new fixture with a small source tree and a `backup.toml`.
     fn new(test_name: &str) -> Self {
+        Self::new_encrypted(test_name, "")
+    }
+
+    /// Like [`Self::new`], but the repository is encrypted with `password`.
+    fn new_encrypted(test_name: &str, password: &str) -> Self {
         let root = tempfile::tempdir().unwrap();
         let source_dir = root.path().join("source");
         let repo_dir = root.path().join("repo");
@@ -79,7 +97,7 @@ impl Fixture {
             r#"
 [repo]
 path     = "{repo}"
-password = ""
+password = {password}
 
 [backup]
 sources  = ["{source}"]
@@ -92,6 +110,7 @@ keep_monthly = 1
 "#,
             repo = repo_dir.display(),
             source = source_dir.display(),
+            password = toml::Value::String(password.into()),
         );
         fs::write(work_dir.join("backup.toml"), config).unwrap();
 
@@ -100,6 +119,7 @@ keep_monthly = 1
             source_dir,
             repo_dir,
             work_dir,
+            password: password.into(),
             counter: std::sync::atomic::AtomicU32::new(0),
         }
     }
@@ -119,11 +139,58 @@ keep_monthly = 1
         .unwrap();
     }
 
+    /// Replace the `[retention]` table of this fixture's `backup.toml`.
+    ///
+    /// Every other key — repo, password, sources — is kept as it was.  A
+    /// zero leaves that rule out, as it does in a real config.
+    fn override_retention(
+        &self,
+        keep_last: u32,
+        keep_daily: u32,
+        keep_weekly: u32,
+        keep_monthly: u32,
+    ) {
+        let path = self.work_dir.join("backup.toml");
+        let mut config: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        let mut retention = toml::Table::new();
+        for (key, value) in [
+            ("keep_last", keep_last),
+            ("daily", keep_daily),
+            ("weekly", keep_weekly),
+            ("monthly", keep_monthly),
+        ] {
+            retention.insert(key.into(), i64::from(value).into());
+        }
+        config.insert("retention".into(), retention.into());
+        fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+    }
+
+    /// Point `[mirror]` in this fixture's `backup.toml` at `replica`'s repo.
+    ///
+    /// Both fixtures use an empty password, so the replica opens with
+    /// `[repo].password` and needs no `dest_password`.
+    fn mirror_to(&self, replica: &Self) {
+        let path = self.work_dir.join("backup.toml");
+        let mut config: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        let mut mirror = toml::Table::new();
+        mirror.insert(
+            "dest_repo".into(),
+            replica.repo_dir.display().to_string().into(),
+        );
+        config.insert("mirror".into(), mirror.into());
+        fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+    }
+
     /// Run `backup-rs` with `extra_args` inside this fixture's working directory.
+    ///
+    /// Its data and config directories, including the rustic profiles
+    /// `rustic copy` is given, live in the working directory too.
     fn run(&self, extra_args: &[&str]) -> (bool, String, String) {
         let out = Command::new(BIN)
             .args(extra_args)
             .current_dir(&self.work_dir)
+            .env("XDG_DATA_HOME", &self.work_dir)
+            .env("XDG_CONFIG_HOME", &self.work_dir)
             .output()
             .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
 
@@ -137,7 +204,8 @@ keep_monthly = 1
     /// Run `rustic` directly against this fixture's repo with `args`.
     fn rustic(&self, args: &[&str]) -> (bool, String, String) {
         let out = Command::new("rustic")
-            .args(["-r", self.repo_dir.to_str().unwrap(), "--password", ""])
+            .args(["-r", self.repo_dir.to_str().unwrap()])
+            .args(["--password", &self.password])
             .args(args)
             .output()
             .unwrap_or_else(|e| panic!("failed to spawn rustic: {e}"));
@@ -169,7 +237,56 @@ keep_monthly = 1
             return 0;
         }
         let v: serde_json::Value = serde_json::from_str(&stdout).unwrap_or(serde_json::Value::Null);
-        v.as_array().map_or(0, |a| a.len())
+        v.as_array().map_or(0, Vec::len)
+    }
+
+    /// IDs of every snapshot in the repo, oldest first.
+    ///
+    /// These are the full IDs from `rustic snapshots --json`, which rustic
+    /// also accepts wherever it takes a short ID.  Empty when the repo has no
+    /// snapshots (or does not exist yet).
+    fn snapshot_ids(&self) -> Vec<String> {
+        let (ok, stdout, _) = self.rustic(&["snapshots", "--json"]);
+        if !ok {
+            return vec![];
+        }
+        let v: serde_json::Value = serde_json::from_str(&stdout).unwrap_or(serde_json::Value::Null);
+        let mut snapshots: Vec<(String, String)> = v
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|s| {
+                        Some((
+                            s["time"].as_str()?.to_string(),
+                            s["id"].as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        // RFC 3339 times from one host share an offset, so they sort as text.
+        snapshots.sort();
+        snapshots.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Poll [`Self::snapshot_count`] every 500 ms until it equals `expected`,
+    /// panicking if it still differs after `timeout_secs`.
+    ///
+    /// On slow CI machines a freshly written snapshot can take a moment to
+    /// show up in `rustic snapshots`; asserting once would make tests flaky.
+    fn assert_snapshot_count_eventually(&self, expected: usize, timeout_secs: u64) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
+        loop {
+            let count = self.snapshot_count();
+            if count == expected {
+                return;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "expected {expected} snapshot(s) within {timeout_secs}s, last count was {count}"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
     }
 
     /// Restore the latest snapshot to a temp dir and return that dir's path.
@@ -183,17 +300,80 @@ keep_monthly = 1
         assert!(ok, "rustic restore should succeed; stderr:\n{stderr}");
         restore_dir
     }
+
+    /// Damage one pack file so integrity checks that read data fail.
+    ///
+    /// Picks a pack under `repo_dir/data/` at random and overwrites its first
+    /// 16 bytes with zeroes in place.  The file keeps its name and size, so
+    /// the index still lists it and the repo looks structurally intact — only
+    /// the blob at the start of the pack no longer decrypts.  Returns the path
+    /// of the damaged pack.
+    ///
+    /// Panics if the repo has no packs yet; back something up first.
+    fn corrupt_repo(&self) -> PathBuf {
+        use std::{
+            hash::{BuildHasher, RandomState},
+            io::{Seek, SeekFrom, Write},
+        };
+
+        let packs = walkdir(&self.repo_dir.join("data"));
+        assert!(
+            !packs.is_empty(),
+            "corrupt_repo needs at least one pack file"
+        );
+        // `RandomState` is seeded per process: a random pick without a `rand` dep.
+        let len = packs.len() as u64;
+        let index = usize::try_from(RandomState::new().hash_one(len) % len).unwrap();
+        let pack = packs[index].clone();
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .open(&pack)
+            .unwrap_or_else(|e| panic!("failed to open {}: {e}", pack.display()));
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(&[0u8; 16]).unwrap();
+        pack
+    }
+
+    /// Delete every index file, as an interrupted run can leave the repo:
+    /// the packs are intact but nothing points at the blobs inside them.
+    ///
+    /// Panics if the repo has no index files yet; back something up first.
+    fn corrupt_index(&self) {
+        let files = walkdir(&self.repo_dir.join("index"));
+        assert!(
+            !files.is_empty(),
+            "corrupt_index needs at least one index file"
+        );
+        for file in files {
+            fs::remove_file(&file)
+                .unwrap_or_else(|e| panic!("failed to remove {}: {e}", file.display()));
+        }
+    }
 }
 
 // ─── Tests ────────────────────────────────────────────────────────────────────
 
-/// A clean first run should initialise the repo and exit zero.
-#[ignore]
+/// Without `--init-if-missing`, a missing repo is an error and nothing is created.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn first_run_without_init_flag_fails() {
+    let fx = Fixture::new("no_init_flag");
+
+    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    assert!(!ok, "missing repo should fail without --init-if-missing");
+    assert!(stderr.contains("--init-if-missing"), "got: {stderr}");
+    assert!(!fx.repo_dir.exists(), "repo must not be created");
+}
+
+/// A clean first run with `--init-if-missing` should initialise the repo and
+/// exit zero.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn first_run_initialises_repo_and_exits_zero() {
     let fx = Fixture::new("first_run");
 
-    let (ok, _stdout, stderr) = fx.run(&["--no-check"]);
+    let (ok, _stdout, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok, "first backup run should succeed; stderr:\n{stderr}");
     assert!(
         fx.repo_dir.exists(),
@@ -202,29 +382,28 @@ fn first_run_initialises_repo_and_exits_zero() {
 }
 
 /// After a successful backup the repo should contain exactly one snapshot.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn first_run_creates_one_snapshot() {
     let fx = Fixture::new("one_snapshot");
 
-    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok, "backup should succeed; stderr:\n{stderr}");
 
-    let count = fx.snapshot_count();
-    assert_eq!(count, 1, "expected 1 snapshot after first run, got {count}");
+    fx.assert_snapshot_count_eventually(1, 10);
 }
 
 /// A second run on an already-initialised repo should also succeed.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn second_run_succeeds() {
     let fx = Fixture::new("second_run");
 
-    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok, "first run should succeed; stderr:\n{stderr}");
 
     fx.write_unique("second run marker");
-    let (ok2, _, stderr2) = fx.run(&["--no-check"]);
+    let (ok2, _, stderr2) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok2, "second run should also succeed; stderr:\n{stderr2}");
 }
 
@@ -235,7 +414,7 @@ fn second_run_succeeds() {
 /// routing through `backup-rs`, because rustic deduplicates snapshots whose
 /// tree hashes match — a unique label forces a distinct snapshot record even
 /// when content is identical.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn two_runs_produce_two_snapshots() {
     let fx = Fixture::new("two_snapshots");
@@ -250,22 +429,33 @@ fn two_runs_produce_two_snapshots() {
     let (ok, _, stderr) = fx.rustic(&["backup", "--label", "run-2", src]);
     assert!(ok, "second rustic backup should succeed; stderr:\n{stderr}");
 
-    let count = fx.snapshot_count();
-    assert_eq!(count, 2, "expected 2 snapshots, got {count}");
+    fx.assert_snapshot_count_eventually(2, 10);
+}
+
+/// The polling helper must give up (and fail the test) when the expected count
+/// is never reached, rather than spinning forever.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+#[should_panic(expected = "expected 5 snapshot(s) within 1s")]
+fn assert_snapshot_count_eventually_times_out() {
+    let fx = Fixture::new("count_timeout");
+    fx.init_repo();
+    fx.assert_snapshot_count_eventually(5, 1);
 }
 
 /// Three backups with `--no-prune` should retain all three snapshots.
 ///
-/// We verify this by doing a full `backup-rs --no-prune` run (which exercises
-/// our pipeline) and then confirming the count using direct rustic calls with
-/// unique labels to seed the repo with a known baseline first.
-#[ignore]
+/// We seed the repo with three labelled snapshots via rustic directly, then
+/// do a full `backup-rs --no-prune` run under `keep_last = 1`: had Forget
+/// run, only one snapshot would be left.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn no_prune_retains_all_snapshots() {
     let fx = Fixture::new("no_prune");
     let src = fx.source_dir.to_str().unwrap();
 
     fx.init_repo();
+    fx.override_retention(1, 0, 0, 0);
 
     // Seed three snapshots directly via rustic with unique labels so we know
     // exactly how many exist before testing our --no-prune flag.
@@ -275,32 +465,82 @@ fn no_prune_retains_all_snapshots() {
         assert!(ok, "seed backup {label} should succeed; stderr:\n{stderr}");
     }
 
-    let count = fx.snapshot_count();
-    assert!(
-        count >= 3,
-        "--no-prune should retain all snapshots; got {count}"
+    fx.write_unique("no-prune run");
+    let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune"]);
+    assert!(ok, "--no-prune run should succeed; stderr:\n{stderr}");
+
+    // --no-prune must retain all four.
+    fx.assert_snapshot_count_eventually(4, 10);
+}
+
+/// `override_retention` leaves valid TOML with the new `[retention]` and the
+/// fixture's repo and source untouched.  Needs no rustic, so it always runs.
+#[test]
+fn override_retention_rewrites_only_the_retention_table() {
+    let fx = Fixture::new("override_retention");
+    fx.override_retention(2, 7, 4, 0);
+
+    let text = fs::read_to_string(fx.work_dir.join("backup.toml")).unwrap();
+    let config: toml::Table = text.parse().expect("override must leave valid TOML");
+    assert_eq!(config["retention"]["keep_last"].as_integer(), Some(2));
+    assert_eq!(config["retention"]["daily"].as_integer(), Some(7));
+    assert_eq!(config["retention"]["weekly"].as_integer(), Some(4));
+    assert_eq!(config["retention"]["monthly"].as_integer(), Some(0));
+    assert_eq!(
+        config["repo"]["path"].as_str(),
+        fx.repo_dir.to_str(),
+        "got:\n{text}"
     );
+    assert_eq!(
+        config["backup"]["sources"][0].as_str(),
+        fx.source_dir.to_str()
+    );
+}
+
+/// Snapshots kept by `--no-prune` runs are trimmed to `[retention]` by the
+/// next run that prunes.
+///
+/// `keep_last = 2` with every time window zeroed makes the expected count
+/// exact: three `--no-prune` backups leave three snapshots, and a full run
+/// adds a fourth, then forgets all but the newest two.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn prune_after_no_prune_runs_applies_retention() {
+    let fx = Fixture::new("prune_after_no_prune");
+    fx.override_retention(2, 0, 0, 0);
+
+    for n in 0..3 {
+        fx.write_unique(&format!("no-prune run {n}"));
+        let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+        assert!(ok, "--no-prune run {n} should succeed; stderr:\n{stderr}");
+    }
+    fx.assert_snapshot_count_eventually(3, 10);
+
+    fx.write_unique("pruning run");
+    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    assert!(ok, "pruning run should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(2, 10);
 }
 
 /// `--no-check` should still produce a valid snapshot (the check is optional).
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn no_check_still_creates_snapshot() {
     let fx = Fixture::new("no_check");
 
-    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok, "--no-check run should succeed; stderr:\n{stderr}");
-    assert_eq!(fx.snapshot_count(), 1);
+    fx.assert_snapshot_count_eventually(1, 10);
 }
 
 /// A full run including the check stage should succeed on an existing repo.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn full_run_with_check_succeeds() {
     let fx = Fixture::new("full_run");
 
     // First run without check to init the repo cleanly.
-    fx.run(&["--no-check"]);
+    fx.run(&["--init-if-missing", "--no-check"]);
 
     fx.write_unique("full run");
     let (ok, _, stderr) = fx.run(&[]);
@@ -310,8 +550,63 @@ fn full_run_with_check_succeeds() {
     );
 }
 
+/// A pack damaged by [`Fixture::corrupt_repo`] fails `backup check data`.
+///
+/// The pipeline's Check stage runs plain `rustic check`, which verifies the
+/// index and pack sizes but not pack contents, so the damage is only visible
+/// to the data-reading mode.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn corrupted_pack_fails_check_data() {
+    let fx = Fixture::new("corrupt");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    let pack = fx.corrupt_repo();
+    assert!(pack.exists(), "corrupted pack should still be present");
+
+    let (ok, stdout, stderr) = fx.run(&["check", "data"]);
+    assert!(
+        !ok,
+        "backup check data should fail on {}; stdout:\n{stdout}\nstderr:\n{stderr}",
+        pack.display()
+    );
+}
+
+/// A backup that exits zero without writing a snapshot fails Verify.
+///
+/// rustic's global `--dry-run` (via `[repo].extra_args`) makes `backup`
+/// succeed without touching the repository — exactly the silent failure
+/// the stage exists to catch.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn verify_catches_backup_that_wrote_no_snapshot() {
+    let fx = Fixture::new("verify_no_snapshot");
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "first run should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+
+    let config = fx.work_dir.join("backup.toml");
+    let text = fs::read_to_string(&config).unwrap();
+    fs::write(
+        &config,
+        text.replacen("[repo]\n", "[repo]\nextra_args = [\"--dry-run\"]\n", 1),
+    )
+    .unwrap();
+
+    fx.write_unique("never stored");
+    let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune"]);
+    assert!(!ok, "a run that stored nothing must fail");
+    assert!(stderr.contains("predates this run"), "stderr:\n{stderr}");
+    assert_eq!(fx.snapshot_count(), 1);
+
+    let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune", "--no-verify"]);
+    assert!(ok, "--no-verify should skip the check; stderr:\n{stderr}");
+}
+
 /// A bad repo path should cause a non-zero exit.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn bad_repo_path_exits_nonzero() {
     let dir = tempfile::tempdir().unwrap();
@@ -342,12 +637,12 @@ sources = ["/tmp"]
 }
 
 /// The restored snapshot should contain the files that were in the source dir.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn snapshot_contains_source_files() {
     let fx = Fixture::new("content_check");
 
-    let (ok, _, stderr) = fx.run(&["--no-check"]);
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
     assert!(ok, "backup should succeed; stderr:\n{stderr}");
 
     // Restore and search for expected filenames anywhere in the tree.
@@ -369,15 +664,47 @@ fn snapshot_contains_source_files() {
     );
 }
 
+/// Every source file should come back from a restore with identical bytes,
+/// not just under the right name.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn restored_files_match_source_bytes() {
+    let fx = Fixture::new("byte_check");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+
+    // rustic restores the absolute source path, so match each source file by
+    // its path relative to the source dir.
+    let restore_dir = fx.restore_latest();
+    let restored = walkdir(restore_dir.path());
+    let sources = walkdir(&fx.source_dir);
+    assert!(!sources.is_empty(), "fixture source should not be empty");
+
+    for source in sources {
+        let rel = source.strip_prefix(&fx.source_dir).unwrap();
+        let copy = restored
+            .iter()
+            .find(|p| p.ends_with(rel))
+            .unwrap_or_else(|| panic!("{} not found in restored snapshot", rel.display()));
+        assert_eq!(
+            fs::read(copy).unwrap(),
+            fs::read(&source).unwrap(),
+            "restored {} differs from the source",
+            rel.display()
+        );
+    }
+}
+
 /// After modifying a source file, the next snapshot should reflect the change.
 /// Verified by restoring the latest snapshot and reading the file directly.
-#[ignore]
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
 #[test]
 fn snapshot_reflects_modified_file() {
     let fx = Fixture::new("modified_file");
 
     // First backup.
-    fx.run(&["--no-check", "--no-prune"]);
+    fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
 
     // Modify the file, write a new unique file to ensure the snapshot differs.
     fs::write(fx.source_dir.join("hello.txt"), "updated content xyz").unwrap();
@@ -385,7 +712,7 @@ fn snapshot_reflects_modified_file() {
     std::thread::sleep(std::time::Duration::from_secs(1));
 
     // Second backup.
-    fx.run(&["--no-check", "--no-prune"]);
+    fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
 
     // Restore latest and find hello.txt anywhere in the restored tree.
     let restore_dir = fx.restore_latest();
@@ -402,8 +729,532 @@ fn snapshot_reflects_modified_file() {
     );
 }
 
+/// `--source` replaces the configured sources for a single run, so a directory
+/// that is not in `backup.toml` ends up in the snapshot.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn source_flag_backs_up_unconfigured_directory() {
+    let fx = Fixture::new("source_flag");
+
+    let extra = fx.work_dir.join("fresh-clone");
+    fs::create_dir_all(&extra).unwrap();
+    fs::write(extra.join("only-here.txt"), "not in backup.toml").unwrap();
+
+    let (ok, _, stderr) = fx.run(&[
+        "--init-if-missing",
+        "--no-check",
+        "--source",
+        extra.to_str().unwrap(),
+    ]);
+    assert!(ok, "backup with --source should succeed; stderr:\n{stderr}");
+
+    let restore_dir = fx.restore_latest();
+    let names: Vec<String> = walkdir(restore_dir.path())
+        .iter()
+        .filter_map(|p| p.file_name().and_then(|n| n.to_str()).map(String::from))
+        .collect();
+
+    assert!(
+        names.contains(&"only-here.txt".to_string()),
+        "snapshot should contain the --source directory; found: {names:?}"
+    );
+    assert!(
+        !names.contains(&"hello.txt".to_string()),
+        "--source should replace, not extend, the configured sources; found: {names:?}"
+    );
+}
+
+/// `backup cat` and `backup dump` are the same command under two names, so
+/// they must print byte-identical output for the same snapshot path.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn cat_and_dump_print_identical_output() {
+    let fx = Fixture::new("cat_dump");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+
+    let path = fx.source_dir.join("hello.txt");
+    let path = path.to_str().unwrap();
+
+    let (ok_cat, cat_out, cat_err) = fx.run(&["cat", "latest", path]);
+    assert!(ok_cat, "backup cat should succeed; stderr:\n{cat_err}");
+    let (ok_dump, dump_out, dump_err) = fx.run(&["dump", "latest", path]);
+    assert!(ok_dump, "backup dump should succeed; stderr:\n{dump_err}");
+
+    assert_eq!(cat_out, dump_out);
+    assert_eq!(cat_out, "hello from cat_dump");
+}
+
+/// `backup cat-tree` prints the root tree of a snapshot as JSON on stdout.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn cat_tree_prints_valid_json() {
+    let fx = Fixture::new("cat_tree");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+
+    let (ok, stdout, stderr) = fx.run(&["cat-tree", "latest"]);
+    assert!(ok, "backup cat-tree should succeed; stderr:\n{stderr}");
+    let tree: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("cat-tree output is not JSON ({e}):\n{stdout}"));
+    assert!(
+        tree["nodes"].is_array(),
+        "tree should list its nodes; got:\n{stdout}"
+    );
+}
+
+/// An encrypted repository is created on the first run with its password.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn encrypted_first_run_succeeds() {
+    let fx = Fixture::new_encrypted("encrypted_first_run", "hunter2");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "encrypted backup should succeed; stderr:\n{stderr}");
+
+    fx.assert_snapshot_count_eventually(1, 10);
+
+    // The password really is in use: the repo must not open without it.
+    let out = Command::new("rustic")
+        .args(["-r", fx.repo_dir.to_str().unwrap(), "--password", ""])
+        .arg("snapshots")
+        .output()
+        .unwrap_or_else(|e| panic!("failed to spawn rustic: {e}"));
+    assert!(
+        !out.status.success(),
+        "the repo must not open with an empty password"
+    );
+}
+
+/// Every run against an encrypted repository adds a snapshot.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn encrypted_snapshot_count() {
+    let fx = Fixture::new_encrypted("encrypted_count", "correct horse \"battery\"");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "first encrypted run should succeed; stderr:\n{stderr}");
+    fx.write_unique("second");
+    let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune"]);
+    assert!(ok, "second encrypted run should succeed; stderr:\n{stderr}");
+
+    fx.assert_snapshot_count_eventually(2, 10);
+}
+
+/// Files backed up into an encrypted repository restore intact.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn encrypted_restore_contains_source_files() {
+    let fx = Fixture::new_encrypted("encrypted_restore", "hunter2");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check"]);
+    assert!(ok, "encrypted backup should succeed; stderr:\n{stderr}");
+
+    let restore_dir = fx.restore_latest();
+    let hello = walkdir(restore_dir.path())
+        .into_iter()
+        .find(|p| p.file_name().is_some_and(|n| n == "hello.txt"))
+        .expect("restored snapshot should contain hello.txt");
+    assert_eq!(
+        fs::read_to_string(hello).unwrap(),
+        "hello from encrypted_restore"
+    );
+}
+
+/// `backup gc` reclaims space after a manual forget without touching the
+/// snapshots that are left.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn gc_prunes_after_manual_forget() {
+    let fx = Fixture::new("gc");
+
+    for n in 0..2 {
+        fx.write_unique(&format!("gc run {n}"));
+        let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+        assert!(ok, "backup {n} should succeed; stderr:\n{stderr}");
+    }
+    fx.assert_snapshot_count_eventually(2, 10);
+
+    let (ok, _, stderr) = fx.rustic(&["forget", "--keep-last", "1"]);
+    assert!(ok, "manual forget should succeed; stderr:\n{stderr}");
+
+    let (ok, _, stderr) = fx.run(&["gc"]);
+    assert!(ok, "backup gc should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+}
+
+/// `backup ls --source-index 0` lists the first source's files, and an index
+/// past the end of `sources` is an error.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn ls_source_index_lists_that_source() {
+    let fx = Fixture::new("ls_source");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+
+    let (ok, stdout, stderr) = fx.run(&["ls", "latest", "--source-index", "0"]);
+    assert!(ok, "backup ls should succeed; stderr:\n{stderr}");
+    assert!(stdout.contains("hello.txt"), "got:\n{stdout}");
+    assert!(stdout.contains("nested.txt"), "got:\n{stdout}");
+
+    let (ok, _, stderr) = fx.run(&["ls", "latest", "--source-index", "1"]);
+    assert!(!ok, "an out-of-range index should fail");
+    assert!(stderr.contains("out of range"), "got:\n{stderr}");
+}
+
+/// `backup forget` removes several snapshots in one call and leaves the rest.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn forget_removes_two_snapshots_at_once() {
+    let fx = Fixture::new("forget_ids");
+
+    for n in 0..3 {
+        fx.write_unique(&format!("forget run {n}"));
+        let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+        assert!(ok, "backup {n} should succeed; stderr:\n{stderr}");
+    }
+    fx.assert_snapshot_count_eventually(3, 10);
+
+    let ids = fx.snapshot_ids();
+    assert_eq!(ids.len(), 3, "expected three snapshot IDs, got {ids:?}");
+
+    let (ok, _, stderr) = fx.run(&["forget", &ids[0], &ids[1]]);
+    assert!(ok, "backup forget should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+
+    assert_eq!(
+        fx.snapshot_ids(),
+        [ids[2].clone()],
+        "the newest snapshot should remain"
+    );
+}
+
+/// `backup backup-stdin` stores piped data as a single named file.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn backup_stdin_stores_piped_data() {
+    use std::{io::Write, process::Stdio};
+
+    let fx = Fixture::new("stdin");
+    fx.init_repo();
+
+    let mut child = Command::new(BIN)
+        .args(["backup-stdin", "db.sql"])
+        .current_dir(&fx.work_dir)
+        .env("XDG_DATA_HOME", &fx.work_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"CREATE TABLE t (id int);\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(
+        out.status.success(),
+        "backup-stdin should succeed; stderr:\n{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    fx.assert_snapshot_count_eventually(1, 10);
+
+    let restored = fx.restore_latest();
+    let file = walkdir(restored.path())
+        .into_iter()
+        .find(|p| p.file_name().is_some_and(|n| n == "db.sql"))
+        .expect("db.sql should be in the snapshot");
+    assert_eq!(
+        fs::read_to_string(file).unwrap(),
+        "CREATE TABLE t (id int);\n"
+    );
+}
+
+/// [`Fixture::snapshot_ids`] is empty for a fresh repo, then returns one
+/// full-length hex ID per snapshot, oldest first and stable across calls.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn snapshot_ids_are_full_length_and_stable() {
+    let fx = Fixture::new("snapshot_ids");
+    assert!(fx.snapshot_ids().is_empty(), "no repo yet, so no IDs");
+
+    fx.init_repo();
+    assert!(
+        fx.snapshot_ids().is_empty(),
+        "a fresh repo has no snapshots"
+    );
+
+    for n in 0..2 {
+        fx.write_unique(&format!("ids run {n}"));
+        let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune"]);
+        assert!(ok, "backup {n} should succeed; stderr:\n{stderr}");
+    }
+    fx.assert_snapshot_count_eventually(2, 10);
+
+    let ids = fx.snapshot_ids();
+    assert_eq!(ids.len(), 2);
+    for id in &ids {
+        assert_eq!(id.len(), 64, "expected a full snapshot ID, got {id}");
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()), "got {id}");
+    }
+    assert_ne!(ids[0], ids[1]);
+    assert_eq!(
+        fx.snapshot_ids(),
+        ids,
+        "order should be stable across calls"
+    );
+}
+
+/// `backup snapshots --count` prints just the number after one backup, and
+/// the same number as a JSON object with `--json`.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn snapshots_count_after_one_backup() {
+    let fx = Fixture::new("snapshots_count");
+    fx.init_repo();
+    let (ok, _, stderr) = fx.run(&["--no-check", "--no-prune"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+
+    let (ok, stdout, stderr) = fx.run(&["snapshots", "--count"]);
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout, "1\n");
+
+    let (ok, stdout, _) = fx.run(&["snapshots", "--count", "--json"]);
+    assert!(ok);
+    assert_eq!(stdout.trim(), r#"{"count": 1}"#);
+}
+
+/// The whole pipeline runs from a config at a non-default path: with
+/// `backup.toml` renamed, `--config` alone must find it.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn config_flag_runs_pipeline_from_custom_path() {
+    let fx = Fixture::new("custom_config_path");
+    fx.init_repo();
+    fs::rename(
+        fx.work_dir.join("backup.toml"),
+        fx.work_dir.join("custom-backup.toml"),
+    )
+    .unwrap();
+
+    let (ok, _, stderr) = fx.run(&["--config", "custom-backup.toml", "--no-check"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+}
+
+/// `backup repair-index` rebuilds a deleted index from the pack files, after
+/// which `check` passes again.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn repair_index_rebuilds_missing_index() {
+    let fx = Fixture::new("repair_index");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    fx.corrupt_index();
+    let (ok, _, _) = fx.rustic(&["check"]);
+    assert!(!ok, "check should fail without an index");
+
+    let (ok, _, stderr) = fx.run(&["repair-index"]);
+    assert!(ok, "backup repair-index should succeed; stderr:\n{stderr}");
+
+    let (ok, _, stderr) = fx.rustic(&["check"]);
+    assert!(ok, "check should pass after the repair; stderr:\n{stderr}");
+    fx.assert_snapshot_count_eventually(1, 10);
+}
+
+/// `backup mount latest <dir> --foreground` exposes the snapshot's files
+/// until the mountpoint is unmounted.
+///
+/// Returns early (passing) when the host has no FUSE device or unmount helper.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn mount_exposes_snapshot_files() {
+    if !fuse_available() {
+        eprintln!("skipping: FUSE is not available on this host");
+        return;
+    }
+    let fx = Fixture::new("fusemount");
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "backup should succeed; stderr:\n{stderr}");
+
+    let mountpoint = tempfile::tempdir().unwrap();
+    let mut child = Command::new(BIN)
+        .args([
+            "mount",
+            "latest",
+            mountpoint.path().to_str().unwrap(),
+            "--foreground",
+        ])
+        .current_dir(&fx.work_dir)
+        .env("XDG_DATA_HOME", &fx.work_dir)
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(20);
+    let found = loop {
+        let hello = walkdir(mountpoint.path())
+            .into_iter()
+            .find(|p| p.file_name().is_some_and(|n| n == "hello.txt"));
+        if hello.is_some() || std::time::Instant::now() >= deadline {
+            break hello;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    };
+    let contents = found.as_ref().map(fs::read_to_string);
+
+    let _ = Command::new("fusermount")
+        .args(["-u", mountpoint.path().to_str().unwrap()])
+        .status();
+    let status = child.wait().unwrap();
+
+    let contents = contents
+        .expect("hello.txt should appear under the mountpoint")
+        .unwrap();
+    assert_eq!(contents, "hello from fusemount");
+    assert!(
+        status.success(),
+        "backup mount should exit zero after unmount"
+    );
+}
+
+/// `backup check index` passes on a freshly written repository.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn check_index_mode_passes_on_fresh_repo() {
+    let fx = Fixture::new("check_index");
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    let (ok, _, stderr) = fx.run(&["check", "index"]);
+    assert!(ok, "backup check index should succeed; stderr:\n{stderr}");
+}
+
+/// `backup check snapshots` passes on a freshly written repository.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn check_snapshots_mode_passes_on_fresh_repo() {
+    let fx = Fixture::new("check_snapshots");
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    let (ok, _, stderr) = fx.run(&["check", "snapshots"]);
+    assert!(
+        ok,
+        "backup check snapshots should succeed; stderr:\n{stderr}"
+    );
+}
+
+/// `backup snapshot-size latest --raw-bytes` reports a positive size within
+/// an order of magnitude of the source tree the backup read.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn snapshot_size_matches_source_tree() {
+    let fx = Fixture::new("snapshot_size");
+    fx.write_unique(&"x".repeat(64 * 1024));
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    let (ok, stdout, stderr) = fx.run(&["snapshot-size", "latest", "--raw-bytes"]);
+    assert!(ok, "snapshot-size should succeed; stderr:\n{stderr}");
+    let size: u64 = stdout
+        .parse()
+        .unwrap_or_else(|_| panic!("expected a bare byte count, got {stdout:?}"));
+    assert!(size > 0, "a snapshot of a non-empty tree has a size");
+
+    let actual: u64 = walkdir(&fx.source_dir)
+        .iter()
+        .map(|f| fs::metadata(f).unwrap().len())
+        .sum();
+    assert!(
+        (actual / 10..=actual * 10).contains(&size),
+        "reported {size} bytes for a {actual}-byte source tree"
+    );
+}
+
+/// Without `--raw-bytes` the size carries a binary unit.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn snapshot_size_is_human_readable_by_default() {
+    let fx = Fixture::new("snapshot_size_human");
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune"]);
+    assert!(ok, "initial backup should succeed; stderr:\n{stderr}");
+
+    let (ok, stdout, stderr) = fx.run(&["snapshot-size", "latest"]);
+    assert!(ok, "snapshot-size should succeed; stderr:\n{stderr}");
+    let unit = stdout.trim().rsplit(' ').next().unwrap_or_default();
+    assert!(
+        ["B", "KiB", "MiB", "GiB", "TiB"].contains(&unit),
+        "got: {stdout:?}"
+    );
+}
+
+/// The first mirror creates the replica and copies the snapshot the
+/// pipeline just took.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn mirror_creates_replica_with_the_new_snapshot() {
+    let fx = Fixture::new("mirror_primary");
+    let replica = Fixture::new("mirror_replica");
+    fx.mirror_to(&replica);
+
+    let (ok, stdout, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune", "mirror"]);
+    assert!(
+        ok,
+        "mirror should succeed; stdout:\n{stdout}\nstderr:\n{stderr}"
+    );
+
+    fx.assert_snapshot_count_eventually(1, 10);
+    replica.assert_snapshot_count_eventually(1, 10);
+    assert!(
+        fx.work_dir.join("backup.rs").join("mirror.json").exists(),
+        "a successful mirror records its state"
+    );
+    let leftovers = fs::read_dir(fx.work_dir.join("rustic")).map_or(0, Iterator::count);
+    assert_eq!(leftovers, 0, "the copy profiles are removed afterwards");
+}
+
+/// A second mirror copies only the snapshot taken since the first one.
+#[ignore = "requires rustic on PATH; run with `just e2e`"]
+#[test]
+fn second_mirror_copies_only_newer_snapshots() {
+    let fx = Fixture::new("mirror_incremental");
+    let replica = Fixture::new("mirror_incremental_replica");
+    fx.mirror_to(&replica);
+
+    let (ok, _, stderr) = fx.run(&["--init-if-missing", "--no-check", "--no-prune", "mirror"]);
+    assert!(ok, "first mirror should succeed; stderr:\n{stderr}");
+
+    fx.write_unique("between mirrors");
+    let (ok, stdout, stderr) = fx.run(&["--no-check", "--no-prune", "mirror"]);
+    assert!(ok, "second mirror should succeed; stderr:\n{stderr}");
+    assert!(
+        stdout.contains("Mirror 1 snapshot(s)"),
+        "only the new snapshot is copied; stdout:\n{stdout}"
+    );
+
+    replica.assert_snapshot_count_eventually(2, 10);
+}
+
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
+/// Whether this host can serve and unmount FUSE filesystems.
+fn fuse_available() -> bool {
+    std::path::Path::new("/dev/fuse").exists()
+        && Command::new("fusermount")
+            .arg("-V")
+            .output()
+            .is_ok_and(|o| o.status.success())
+}
+
 /// Recursively collect all file paths under `root`.
 fn walkdir(root: &std::path::Path) -> Vec<std::path::PathBuf> {
     let mut out = Vec::new();
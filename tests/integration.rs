@@ -28,10 +28,16 @@ fn run(args: &[&str]) -> (bool, String, String) {
 }
 
 /// Run `backup-rs` with `args` in the given working directory.
+///
+/// `XDG_DATA_HOME` points at `dir` so the run log never lands in the real
+/// user's data directory, and `XDG_CONFIG_HOME` does too so a real global
+/// config cannot leak into the test.
 fn run_in(args: &[&str], dir: &std::path::Path) -> (bool, String, String) {
     let out = Command::new(BIN)
         .args(args)
         .current_dir(dir)
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
         .output()
         .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
 
@@ -88,6 +94,42 @@ fn init_creates_backup_toml() {
     assert!(content.contains("[retention]"));
 }
 
+#[test]
+fn init_list_templates_prints_names_and_creates_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ok, stdout, _) = run_in(&["init", "--list-templates"], dir.path());
+    assert!(ok);
+    assert!(
+        stdout.contains("Rust project with target/ excluded"),
+        "{stdout}"
+    );
+    assert!(!dir.path().join("backup.toml").exists());
+}
+
+#[test]
+fn init_rejects_unknown_template() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ok, _, stderr) = run_in(&["init", "--template", "cobol"], dir.path());
+    assert!(!ok);
+    assert!(stderr.contains("unknown template 'cobol'"), "{stderr}");
+    assert!(!dir.path().join("backup.toml").exists());
+}
+
+#[test]
+fn init_global_writes_to_xdg_config_home() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ok, _, stderr) = run_in(&["init", "--global"], dir.path());
+    assert!(ok, "stderr:\n{stderr}");
+
+    let global = dir.path().join("backup.rs").join("config.toml");
+    let content = fs::read_to_string(&global).expect("global config should be created");
+    assert!(content.contains("[mount]"), "{content}");
+    assert!(!dir.path().join("backup.toml").exists());
+
+    let (ok, _, _) = run_in(&["init", "--global"], dir.path());
+    assert!(!ok, "a second --global must not overwrite the first");
+}
+
 #[test]
 fn init_with_custom_config_path() {
     let dir = tempfile::tempdir().unwrap();
@@ -142,13 +184,7 @@ fn init_generated_config_is_valid_toml() {
     // verify the file parses without error.
     let stripped: String = content
         .lines()
-        .map(|l| {
-            if let Some(i) = l.find("   #") {
-                &l[..i]
-            } else {
-                l
-            }
-        })
+        .map(|l| l.find("   #").map_or(l, |i| &l[..i]))
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -180,6 +216,25 @@ fn print_config_exits_zero_with_missing_config() {
     );
 }
 
+#[test]
+fn empty_config_file_warns_that_nothing_is_set() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("backup.toml"), "# nothing yet\n[repo]\n").unwrap();
+
+    let (ok, _, stderr) = run_in(&["--print-config"], dir.path());
+    assert!(ok);
+    assert!(stderr.contains("sets no options"), "got:\n{stderr}");
+}
+
+#[test]
+fn generated_config_does_not_warn_about_being_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+
+    let (_, _, stderr) = run_in(&["--print-config"], dir.path());
+    assert!(!stderr.contains("sets no options"), "got:\n{stderr}");
+}
+
 #[test]
 fn print_config_errors_on_invalid_toml() {
     let dir = tempfile::tempdir().unwrap();
@@ -218,6 +273,959 @@ password = ""
     );
 }
 
+#[test]
+fn backup_toml_env_names_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg_path = dir.path().join("elsewhere.toml");
+    fs::write(&cfg_path, "[repo]\npath = \"/tmp/from-env-repo\"\n").unwrap();
+
+    let out = Command::new(BIN)
+        .arg("--print-config")
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("BACKUP_TOML", &cfg_path)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("from-env-repo"));
+}
+
+#[test]
+fn config_is_found_in_a_parent_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "[repo]\npath = \"/tmp/parent-repo\"\n",
+    )
+    .unwrap();
+    let nested = dir.path().join("src").join("deep");
+    fs::create_dir_all(&nested).unwrap();
+
+    let (ok, stdout, stderr) = run_in(&["--print-config"], &nested);
+    assert!(ok);
+    assert!(stdout.contains("parent-repo"), "got:\n{stdout}");
+    assert!(!stderr.contains("not found"), "got:\n{stderr}");
+}
+
+// ─── backup dump / backup cat ─────────────────────────────────────────────────
+
+#[test]
+fn dump_and_cat_help_exit_zero() {
+    for sub in ["dump", "cat"] {
+        let (ok, stdout, _) = run(&[sub, "--help"]);
+        assert!(ok, "{sub} --help should exit 0");
+        assert!(stdout.contains("SNAPSHOT") && stdout.contains("PATH"));
+    }
+}
+
+#[test]
+fn cat_without_path_exits_nonzero() {
+    let (ok, _, stderr) = run(&["cat", "latest"]);
+    assert!(!ok, "cat without a path should be a usage error");
+    assert!(
+        stderr.contains("PATH"),
+        "usage error should name the missing PATH"
+    );
+}
+
+// ─── backup config validate ───────────────────────────────────────────────────
+
+#[test]
+fn config_validate_passes_for_generated_config() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+
+    let (ok, stdout, stderr) = run_in(&["config", "validate"], dir.path());
+    assert!(ok, "generated config should validate; stderr:\n{stderr}");
+    assert!(stdout.contains("No problems found"));
+}
+
+#[test]
+fn config_validate_reports_every_error() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "stage_timeout_secs = 0\n[backup]\nsources = []\nmax_file_size_mb = 0\n",
+    )
+    .unwrap();
+
+    let (ok, stdout, _) = run_in(&["config", "validate"], dir.path());
+    assert!(!ok, "invalid config should exit non-zero");
+    assert!(stdout.contains("stage_timeout_secs"), "got: {stdout}");
+    assert!(stdout.contains("backup.max_file_size_mb"), "got: {stdout}");
+    assert!(
+        stdout.contains("must be greater than zero"),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains("fix:"),
+        "each issue should suggest a fix; got: {stdout}"
+    );
+}
+
+#[test]
+fn config_validate_errors_on_invalid_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("backup.toml"), "not valid toml ][[[").unwrap();
+
+    let (ok, _, _) = run_in(&["config", "validate"], dir.path());
+    assert!(!ok);
+}
+
+// ─── backup config lint ───────────────────────────────────────────────────────
+
+#[test]
+fn config_lint_json_reports_errors_and_exits_non_zero() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "stage_timeout_secs = 0\n[backup]\nsources = []\n",
+    )
+    .unwrap();
+
+    let (ok, stdout, _) = run_in(&["config", "lint", "--output-format", "json"], dir.path());
+    assert!(!ok, "an error-level issue should exit non-zero");
+    let issues: serde_json::Value = serde_json::from_str(&stdout).expect("stdout must be JSON");
+    assert_eq!(
+        issues,
+        serde_json::json!([{
+            "level": "error",
+            "field": "stage_timeout_secs",
+            "message": "must be greater than zero"
+        }])
+    );
+}
+
+#[test]
+fn config_lint_json_is_empty_for_generated_config() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+
+    let (ok, stdout, stderr) = run_in(&["config", "lint", "--output-format", "json"], dir.path());
+    assert!(ok, "generated config should lint clean; stderr:\n{stderr}");
+    assert_eq!(stdout.trim(), "[]");
+}
+
+// ─── backup config export ─────────────────────────────────────────────────────
+
+#[test]
+fn config_export_parses_back_to_the_same_config() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+
+    let (ok, exported, stderr) = run_in(&["config", "export"], dir.path());
+    assert!(ok, "config export should succeed; stderr:\n{stderr}");
+    let value: toml::Value = toml::from_str(&exported).expect("export must be valid TOML");
+    assert!(value["repo"]["path"].as_str().is_some(), "got:\n{exported}");
+
+    // Exporting the export again must change nothing.
+    let (ok, _, stderr) = run_in(&["config", "export", "-o", "exported.toml"], dir.path());
+    assert!(ok, "config export -o should succeed; stderr:\n{stderr}");
+    let (ok, reexported, _) = run_in(
+        &["--config", "exported.toml", "config", "export"],
+        dir.path(),
+    );
+    assert!(ok);
+    assert_eq!(reexported, exported);
+}
+
+// ─── backup config path ───────────────────────────────────────────────────────
+
+#[test]
+fn config_path_prints_the_config_flag() {
+    let dir = tempfile::tempdir().unwrap();
+    let custom = dir.path().join("elsewhere.toml");
+    let custom = custom.to_str().unwrap();
+
+    let (ok, stdout, _) = run_in(&["--config", custom, "config", "path"], dir.path());
+    assert!(ok);
+    assert_eq!(stdout, format!("{custom}\ndoes not exist\n"));
+
+    fs::write(custom, "").unwrap();
+    let (ok, stdout, _) = run_in(&["--config", custom, "config", "path"], dir.path());
+    assert!(ok);
+    assert_eq!(stdout, format!("{custom}\nexists\n"));
+}
+
+// ─── backup import ────────────────────────────────────────────────────────────
+
+#[test]
+fn import_refuses_to_overwrite_existing_config() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("backup.toml"), "# keep me\n").unwrap();
+
+    let (ok, _, stderr) = run_in(&["import", "--repo", "/tmp/some-repo"], dir.path());
+    assert!(!ok);
+    assert!(stderr.contains("refusing to overwrite"), "got: {stderr}");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("backup.toml")).unwrap(),
+        "# keep me\n"
+    );
+}
+
+// ─── backup mount ─────────────────────────────────────────────────────────────
+
+#[test]
+fn mount_list_shares_prints_known_shares() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ok, stdout, _) = run_in(&["mount", "--list-shares"], dir.path());
+    assert!(ok);
+    assert!(stdout.contains("new-backups"));
+    assert!(stdout.contains("nas.lan:/mnt/vol2/backups"));
+    assert!(stdout.contains("new-documents"));
+}
+
+#[test]
+fn mount_dry_run_prints_command_without_mounting() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "[mount]\nshare = \"isos\"\nuser = \"backup-rs-nobody\"\n",
+    )
+    .unwrap();
+
+    let (ok, stdout, stderr) = run_in(&["--dry-run", "mount"], dir.path());
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(
+        stdout.contains("would run: doas mount -t nfs nas.lan:/mnt/vol1/isos"),
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn umount_dry_run_prints_command_without_unmounting() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "[mount]\nshare = \"isos\"\nuser = \"backup-rs-nobody\"\n",
+    )
+    .unwrap();
+
+    let (ok, stdout, stderr) = run_in(&["--dry-run", "umount"], dir.path());
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(
+        stdout.contains("would run: doas umount /home/backup-rs-nobody/nfs/isos"),
+        "got:\n{stdout}"
+    );
+}
+
+// ─── backup log ───────────────────────────────────────────────────────────────
+
+#[test]
+fn log_label_filters_recorded_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_dir = dir.path().join("backup.rs");
+    fs::create_dir_all(&log_dir).unwrap();
+    fs::write(
+        log_dir.join("runs.jsonl"),
+        concat!(
+            r#"{"started_at":"2026-10-16T09:30:00Z","label":"before-upgrade","repo":"/r","success":true}"#,
+            "\n",
+            r#"{"started_at":"2026-10-17T09:30:00Z","label":null,"repo":"/r","success":false}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let (ok, stdout, _) = run_in(&["log"], dir.path());
+    assert!(ok);
+    assert_eq!(stdout.lines().count(), 2, "got: {stdout}");
+
+    let (ok, stdout, _) = run_in(&["log", "--label", "before-upgrade"], dir.path());
+    assert!(ok);
+    assert!(stdout.contains("2026-10-16T09:30:00Z"), "got: {stdout}");
+    assert!(!stdout.contains("2026-10-17"), "got: {stdout}");
+}
+
+#[test]
+fn log_since_and_until_filter_by_start_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_dir = dir.path().join("backup.rs");
+    fs::create_dir_all(&log_dir).unwrap();
+    let lines: String = (14..=18)
+        .map(|day| {
+            format!(
+                r#"{{"started_at":"2026-10-{day}T09:30:00Z","label":null,"repo":"/r","success":true}}"#
+            ) + "\n"
+        })
+        .collect();
+    fs::write(log_dir.join("runs.jsonl"), lines).unwrap();
+
+    let (ok, stdout, stderr) = run_in(
+        &["log", "--since", "2026-10-15", "--until", "2026-10-16"],
+        dir.path(),
+    );
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout.lines().count(), 2, "got: {stdout}");
+    assert!(stdout.contains("2026-10-15T09:30:00Z"), "got: {stdout}");
+    assert!(stdout.contains("2026-10-16T09:30:00Z"), "got: {stdout}");
+}
+
+#[test]
+fn log_rejects_malformed_dates_as_usage_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ok, _, stderr) = run_in(&["log", "--since", "last tuesday"], dir.path());
+    assert!(!ok);
+    assert!(stderr.contains("--since"), "got:\n{stderr}");
+    assert!(stderr.contains("ISO 8601"), "got:\n{stderr}");
+}
+
+#[test]
+fn failed_run_is_recorded_with_label() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+
+    // rustic may or may not be installed; either way the run is logged.
+    run_in(
+        &[
+            "--repo",
+            repo.to_str().unwrap(),
+            "--label",
+            "smoke",
+            "--no-check",
+        ],
+        dir.path(),
+    );
+
+    let log = fs::read_to_string(dir.path().join("backup.rs").join("runs.jsonl")).unwrap();
+    assert!(log.contains(r#""label":"smoke""#), "got: {log}");
+}
+
+// ─── backup snapshot-id ───────────────────────────────────────────────────────
+
+/// Run `backup-rs` in `dir` with a stub `rustic` first on `PATH` that prints
+/// `snapshots_json` for any arguments.
+fn run_with_fake_rustic(
+    args: &[&str],
+    dir: &std::path::Path,
+    snapshots_json: &str,
+) -> (bool, String, String) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin_dir = dir.join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let script = bin_dir.join("rustic");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\ncat <<'EOF'\n{snapshots_json}\nEOF\n"),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let out = Command::new(BIN)
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env("PATH", path)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
+    (
+        out.status.success(),
+        String::from_utf8_lossy(&out.stdout).into_owned(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+    )
+}
+
+const TWO_SNAPSHOTS: &str = r#"[
+  {"id": "1111aaaa", "time": "2026-10-15T09:00:00Z", "paths": ["/src"]},
+  {"id": "2222bbbb", "time": "2026-10-16T09:00:00Z", "paths": ["/src"]}
+]"#;
+
+#[test]
+fn snapshot_id_latest_prints_bare_id() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["snapshot-id", "--latest"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout, "2222bbbb", "exactly the ID, no trailing newline");
+}
+
+#[test]
+fn snapshot_id_index_counts_back_from_newest() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, _) =
+        run_with_fake_rustic(&["snapshot-id", "--index", "1"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok);
+    assert_eq!(stdout, "1111aaaa");
+    assert_eq!(stdout.lines().count(), 1);
+}
+
+#[test]
+fn snapshot_id_fails_without_snapshots() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["snapshot-id"], dir.path(), "[]");
+    assert!(!ok);
+    assert!(stdout.is_empty(), "got: {stdout:?}");
+    assert!(stderr.contains("no snapshots"), "got:\n{stderr}");
+}
+
+/// Two snapshots whose summaries record their size.
+const SIZED_SNAPSHOTS: &str = r#"[
+  {"id": "1111aaaa", "time": "2026-10-15T09:00:00Z", "paths": ["/src"],
+   "summary": {"total_bytes_processed": 1024}},
+  {"id": "2222bbbb", "time": "2026-10-16T09:00:00Z", "paths": ["/src"],
+   "summary": {"total_bytes_processed": 1572864}}
+]"#;
+
+#[test]
+fn snapshot_size_latest_is_human_readable() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["snapshot-size", "latest"], dir.path(), SIZED_SNAPSHOTS);
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout, "1.5 MiB\n");
+}
+
+#[test]
+fn snapshot_size_raw_bytes_by_id_prefix() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) = run_with_fake_rustic(
+        &["snapshot-size", "1111", "--raw-bytes"],
+        dir.path(),
+        SIZED_SNAPSHOTS,
+    );
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(
+        stdout, "1024",
+        "exactly the byte count, no trailing newline"
+    );
+}
+
+#[test]
+fn snapshot_size_unknown_snapshot_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["snapshot-size", "ffff"], dir.path(), SIZED_SNAPSHOTS);
+    assert!(!ok);
+    assert!(stdout.is_empty(), "got: {stdout:?}");
+    assert!(
+        stderr.contains("no snapshot with ID 'ffff'"),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn snapshots_count_prints_only_the_number() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["snapshots", "--count"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout, "2\n");
+}
+
+#[test]
+fn snapshots_count_json_is_an_object() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, _) = run_with_fake_rustic(
+        &["snapshots", "--count", "--json"],
+        dir.path(),
+        TWO_SNAPSHOTS,
+    );
+    assert!(ok);
+    let v: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(v, serde_json::json!({ "count": 2 }));
+}
+
+#[test]
+fn snapshot_count_prints_a_bare_integer() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["snapshot-count"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok, "stderr:\n{stderr}");
+    assert_eq!(stdout.parse::<usize>().ok(), Some(2), "got: {stdout:?}");
+    assert_eq!(stdout, stdout.trim_end(), "no trailing whitespace");
+}
+
+#[test]
+fn snapshot_count_is_zero_for_an_empty_repo() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, _) = run_with_fake_rustic(&["snapshot-count"], dir.path(), "[]");
+    assert!(ok);
+    assert_eq!(stdout.parse::<usize>().ok(), Some(0), "got: {stdout:?}");
+}
+
+#[test]
+fn snapshots_count_is_zero_for_an_empty_repo() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, _) = run_with_fake_rustic(&["snapshots", "--count"], dir.path(), "[]");
+    assert!(ok);
+    assert_eq!(stdout, "0\n");
+}
+
+// ─── Verify stage ─────────────────────────────────────────────────────────────
+
+/// A config whose repository directory exists, so the pipeline goes
+/// straight to Check and Backup.
+fn existing_repo_fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("repo");
+    fs::create_dir(&repo).unwrap();
+    fs::write(
+        dir.path().join("backup.toml"),
+        format!("[repo]\npath = \"{}\"\npassword = \"\"\n", repo.display()),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn verify_fails_when_backup_records_no_snapshot() {
+    let dir = existing_repo_fixture();
+    // The stub rustic exits zero for `backup` but lists no snapshots.
+    let (ok, stdout, stderr) = run_with_fake_rustic(&[], dir.path(), "[]");
+    assert!(!ok, "stdout:\n{stdout}");
+    assert!(stdout.contains("Verify"), "stdout:\n{stdout}");
+    assert!(
+        stderr.contains("no snapshot was recorded"),
+        "stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn verify_fails_when_newest_snapshot_is_stale() {
+    let dir = existing_repo_fixture();
+    let (ok, _, stderr) = run_with_fake_rustic(&[], dir.path(), TWO_SNAPSHOTS);
+    assert!(!ok);
+    assert!(stderr.contains("predates this run"), "stderr:\n{stderr}");
+}
+
+#[test]
+fn no_verify_skips_the_check() {
+    let dir = existing_repo_fixture();
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["--no-verify"], dir.path(), "[]");
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(!stdout.contains("Verify"), "stdout:\n{stdout}");
+}
+
+// ─── --dry-run forget ─────────────────────────────────────────────────────────
+
+/// What `rustic forget --dry-run` reports: the snapshots it would remove.
+const FORGET_PREVIEW: &str = "snapshots to remove:
+1111aaaa 2026-10-15 09:00:00
+2222bbbb 2026-10-16 09:00:00";
+
+#[test]
+fn dry_run_pipeline_prints_snapshots_forget_would_remove() {
+    let dir = existing_repo_fixture();
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["--dry-run"], dir.path(), FORGET_PREVIEW);
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(stdout.contains("Forget"), "stdout:\n{stdout}");
+    for id in ["1111aaaa", "2222bbbb"] {
+        assert!(stdout.contains(id), "{id} missing; stdout:\n{stdout}");
+    }
+}
+
+#[test]
+fn dry_run_forget_subcommand_prints_snapshots_it_would_remove() {
+    let dir = existing_repo_fixture();
+    let (ok, stdout, stderr) = run_with_fake_rustic(
+        &["--dry-run", "forget", "1111aaaa", "2222bbbb"],
+        dir.path(),
+        FORGET_PREVIEW,
+    );
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(stdout.contains("1111aaaa 2026-10-15"), "stdout:\n{stdout}");
+}
+
+// ─── backup mirror ────────────────────────────────────────────────────────────
+
+/// [`existing_repo_fixture`] plus a `[mirror]` section naming a replica.
+fn mirror_fixture() -> tempfile::TempDir {
+    let dir = existing_repo_fixture();
+    let config = dir.path().join("backup.toml");
+    let text = format!(
+        "{}\n[mirror]\ndest_repo = \"{}\"\n",
+        fs::read_to_string(&config).unwrap(),
+        dir.path().join("replica").display()
+    );
+    fs::write(&config, text).unwrap();
+    dir
+}
+
+#[test]
+fn mirror_records_the_newest_copied_snapshot() {
+    let dir = mirror_fixture();
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["--no-verify", "mirror"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok, "stdout:\n{stdout}\nstderr:\n{stderr}");
+    assert!(stdout.contains("Mirror 2 snapshot(s)"), "stdout:\n{stdout}");
+
+    let state = fs::read_to_string(dir.path().join("backup.rs").join("mirror.json")).unwrap();
+    assert!(
+        state.contains(r#""last_snapshot_time": "2026-10-16T09:00:00Z""#),
+        "state:\n{state}"
+    );
+
+    let (ok, stdout, _) =
+        run_with_fake_rustic(&["--no-verify", "mirror"], dir.path(), TWO_SNAPSHOTS);
+    assert!(ok);
+    assert!(stdout.contains("is up to date"), "stdout:\n{stdout}");
+}
+
+#[test]
+fn mirror_without_dest_repo_fails_before_backing_up() {
+    let dir = existing_repo_fixture();
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["mirror"], dir.path(), TWO_SNAPSHOTS);
+    assert!(!ok);
+    assert!(stderr.contains("[mirror].dest_repo"), "stderr:\n{stderr}");
+    assert!(!stdout.contains("Backup"), "stdout:\n{stdout}");
+}
+
+#[test]
+fn dry_run_mirror_leaves_the_state_file_alone() {
+    let dir = mirror_fixture();
+    let (ok, _, stderr) = run_with_fake_rustic(
+        &["--dry-run", "--no-verify", "mirror"],
+        dir.path(),
+        TWO_SNAPSHOTS,
+    );
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(!dir.path().join("backup.rs").join("mirror.json").exists());
+}
+
+// ─── --stdin-password ─────────────────────────────────────────────────────────
+
+/// Run `backup-rs` in `dir` with `stdin` piped in and a stub `rustic` that
+/// appends its arguments to `dir/rustic-args` and lists no snapshots.
+///
+/// Returns `(exit_success, stderr, logged rustic arguments)`.
+fn run_with_stdin(args: &[&str], dir: &std::path::Path, stdin: &str) -> (bool, String, String) {
+    use std::{io::Write as _, os::unix::fs::PermissionsExt, process::Stdio};
+
+    let bin_dir = dir.join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let script = bin_dir.join("rustic");
+    let log = dir.join("rustic-args");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$@\" >> '{}'\necho '[]'\n", log.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let mut child = Command::new(BIN)
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env("PATH", path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to spawn {BIN}: {e}"));
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    (
+        out.status.success(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+        fs::read_to_string(log).unwrap_or_default(),
+    )
+}
+
+#[test]
+fn stdin_password_is_passed_to_rustic() {
+    let dir = existing_repo_fixture();
+    let (ok, stderr, rustic_args) = run_with_stdin(
+        &["--stdin-password", "snapshots"],
+        dir.path(),
+        "piped-s3cret\n",
+    );
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(
+        rustic_args.contains("--password piped-s3cret snapshots"),
+        "rustic was run with: {rustic_args}"
+    );
+}
+
+#[test]
+fn stdin_password_conflicts_with_password_env() {
+    let dir = existing_repo_fixture();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "[repo]\npath = \"/srv/repo\"\npassword_env = \"BACKUP_RS_TEST_STDIN\"\n",
+    )
+    .unwrap();
+    let (ok, stderr, rustic_args) =
+        run_with_stdin(&["--stdin-password", "snapshots"], dir.path(), "pw\n");
+    assert!(!ok);
+    assert!(stderr.contains("password_env"), "stderr:\n{stderr}");
+    assert!(
+        rustic_args.is_empty(),
+        "rustic should not run: {rustic_args}"
+    );
+}
+
+#[test]
+fn password_flag_does_not_need_password_env_to_be_set() {
+    let dir = existing_repo_fixture();
+    fs::write(
+        dir.path().join("backup.toml"),
+        "[repo]\npath = \"/srv/repo\"\npassword_env = \"BACKUP_RS_TEST_NEVER_SET\"\n",
+    )
+    .unwrap();
+    let (ok, stderr, rustic_args) =
+        run_with_stdin(&["--password", "flag-pw", "snapshots"], dir.path(), "");
+    assert!(ok, "stderr:\n{stderr}");
+    assert!(
+        rustic_args.contains("--password flag-pw snapshots"),
+        "rustic was run with: {rustic_args}"
+    );
+}
+
+#[test]
+fn stdin_password_conflicts_with_password_flag() {
+    let (ok, _, stderr) = run(&["--stdin-password", "--password", "pw", "snapshots"]);
+    assert!(!ok);
+    assert!(stderr.contains("--password"), "stderr:\n{stderr}");
+}
+
+// ─── backup completions ───────────────────────────────────────────────────────
+
+#[test]
+fn bash_completions_include_pipeline_flags() {
+    let (ok, stdout, stderr) = run(&["completions", "bash"]);
+    assert!(ok, "completions should exit 0; stderr:\n{stderr}");
+    assert!(!stdout.is_empty());
+    assert!(stdout.contains("--no-prune"));
+}
+
+#[test]
+fn completions_reject_unknown_shell() {
+    let (ok, _, stderr) = run(&["completions", "tcsh"]);
+    assert!(!ok);
+    assert!(stderr.contains("tcsh"));
+}
+
+// ─── backup export-schema ─────────────────────────────────────────────────────
+
+#[test]
+fn export_schema_prints_a_json_schema_for_the_config() {
+    let (ok, stdout, stderr) = run(&["export-schema"]);
+    assert!(ok, "export-schema should exit 0; stderr:\n{stderr}");
+    let schema: serde_json::Value =
+        serde_json::from_str(&stdout).expect("export-schema prints valid JSON");
+    for section in ["repo", "backup"] {
+        assert!(
+            schema["properties"].get(section).is_some(),
+            "[{section}] missing:\n{stdout}"
+        );
+    }
+}
+
+// ─── backup show-excludes ─────────────────────────────────────────────────────
+
+#[test]
+fn show_excludes_lists_config_and_cli_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+    let (ok, stdout, stderr) = run_in(&["--exclude", "*.iso", "show-excludes"], dir.path());
+    assert!(ok, "show-excludes should exit 0; stderr:\n{stderr}");
+    assert!(stdout.contains("[config:globs]"), "got:\n{stdout}");
+    assert!(stdout.contains("!*.iso"), "got:\n{stdout}");
+}
+
+// ─── --repo / --password ──────────────────────────────────────────────────────
+
+#[test]
+fn repo_and_password_flags_override_config() {
+    let dir = tempfile::tempdir().unwrap();
+    run_in(&["init"], dir.path());
+
+    let (ok, stdout, _) = run_in(
+        &[
+            "--repo",
+            "/tmp/test-repo-override",
+            "--password",
+            "flag-pw",
+            "--print-config",
+        ],
+        dir.path(),
+    );
+    assert!(ok);
+    assert!(stdout.contains("/tmp/test-repo-override"), "got: {stdout}");
+    assert!(stdout.contains("flag-pw"), "got: {stdout}");
+    assert!(
+        !stdout.contains("/nfs/new-backups/rustic/"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn repo_flag_reaches_rustic_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("flag-repo");
+
+    // The pipeline logs each command line at debug level before running it;
+    // the stub only has to get it past the pre-flight check for rustic.
+    let (_, _, stderr) = run_with_fake_rustic(
+        &[
+            "--repo",
+            repo.to_str().unwrap(),
+            "--init-if-missing",
+            "--no-check",
+            "--log-level",
+            "debug",
+        ],
+        dir.path(),
+        "[]",
+    );
+    assert!(
+        stderr.contains(&format!("rustic -r {}", repo.display())),
+        "rustic should be invoked with the --repo path; stderr:\n{stderr}"
+    );
+}
+
+// ─── pre-flight checks ────────────────────────────────────────────────────────
+
+#[test]
+fn pipeline_without_rustic_aborts_before_any_stage() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("new-repo");
+    let out = Command::new(BIN)
+        .args(["--repo", repo.to_str().unwrap(), "--init-if-missing"])
+        .current_dir(dir.path())
+        .env("XDG_DATA_HOME", dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("PATH", dir.path().join("no-such-bin"))
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success());
+    assert!(stderr.contains("rustic is not on PATH"), "got:\n{stderr}");
+    assert!(
+        stderr.contains("pre-flight checks failed"),
+        "got:\n{stderr}"
+    );
+    assert!(!repo.exists(), "no stage may run after a failed check");
+}
+
+#[test]
+fn pipeline_with_missing_source_aborts_before_any_stage() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("new-repo");
+    let missing = dir.path().join("gone");
+    fs::write(
+        dir.path().join("backup.toml"),
+        format!(
+            "[repo]\npath = \"{}\"\npassword = \"\"\n[backup]\nsources = [\"{}\"]\n",
+            repo.display(),
+            missing.display()
+        ),
+    )
+    .unwrap();
+
+    let (ok, stdout, stderr) = run_with_fake_rustic(&["--init-if-missing"], dir.path(), "[]");
+    assert!(!ok);
+    assert!(
+        stdout.contains(&format!("Source {}", missing.display())),
+        "got:\n{stdout}"
+    );
+    assert!(stderr.contains("does not exist"), "got:\n{stderr}");
+    assert!(!repo.exists(), "no stage may run after a failed check");
+}
+
+// ─── --init-if-missing ────────────────────────────────────────────────────────
+
+#[test]
+fn missing_repo_fails_without_init_flag() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("typo-repo");
+
+    let (ok, _, stderr) =
+        run_with_fake_rustic(&["--repo", repo.to_str().unwrap()], dir.path(), "[]");
+    assert!(!ok, "missing repo should be an error");
+    assert!(
+        stderr.contains("repo not found") && stderr.contains("--init-if-missing"),
+        "got: {stderr}"
+    );
+    assert!(!repo.exists(), "nothing should be created without the flag");
+}
+
+#[test]
+fn init_if_missing_creates_repo_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("new-repo");
+
+    // The stub's `rustic init` prints nothing useful, but the mkdir stage
+    // before it runs either way.
+    let (_, _, stderr) = run_with_fake_rustic(
+        &["--repo", repo.to_str().unwrap(), "--init-if-missing"],
+        dir.path(),
+        "[]",
+    );
+    assert!(repo.is_dir(), "repo directory should be created");
+    assert!(!stderr.contains("repo not found"), "got: {stderr}");
+}
+
+// ─── startup banner ───────────────────────────────────────────────────────────
+
+/// Write a config whose repo does not exist yet, so `--init-if-missing
+/// --dry-run` stops right after the banner without touching anything.  Run
+/// it with a stub rustic: the pre-flight checks want one on `PATH`.
+fn dry_run_fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("missing-repo");
+    fs::write(
+        dir.path().join("backup.toml"),
+        format!("[repo]\npath = \"{}\"\npassword = \"\"\n", repo.display()),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn banner_shows_version_and_config_path() {
+    let dir = dry_run_fixture();
+    let (ok, stdout, stderr) =
+        run_with_fake_rustic(&["--init-if-missing", "--dry-run"], dir.path(), "[]");
+    assert!(ok, "dry run should exit 0; stderr:\n{stderr}");
+    assert!(stdout.contains("backup.rs v0.1.0"), "got: {stdout}");
+    assert!(
+        stdout.contains(&dir.path().join("backup.toml").display().to_string()),
+        "banner should show the resolved config path; got: {stdout}"
+    );
+}
+
+#[test]
+fn quiet_suppresses_banner() {
+    let dir = dry_run_fixture();
+    let (ok, stdout, _) = run_with_fake_rustic(
+        &["--quiet", "--init-if-missing", "--dry-run"],
+        dir.path(),
+        "[]",
+    );
+    assert!(ok);
+    assert!(!stdout.contains("backup.rs v"), "got: {stdout}");
+}
+
 // ─── unknown flags ────────────────────────────────────────────────────────────
 
 #[test]